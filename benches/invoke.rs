@@ -0,0 +1,129 @@
+//! Benchmarks of the callback invocations.
+//!
+//! Run the benchmarks through `cargo bench --bench invoke`.
+
+use fx_callback::{Callback, SingleThreadedCallback, Subscription, VariantMask};
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 100_000;
+const SUBSCRIBERS: usize = 10;
+
+#[derive(Debug)]
+enum Event {
+    Foo,
+    Bar,
+}
+
+impl VariantMask for Event {
+    fn variant_bit(&self) -> u64 {
+        match self {
+            Event::Foo => 1 << 0,
+            Event::Bar => 1 << 1,
+        }
+    }
+}
+
+fn main() {
+    let callback = SingleThreadedCallback::<u32>::new();
+    let mut subscriptions: Vec<Subscription<u32>> =
+        (0..SUBSCRIBERS).map(|_| callback.subscribe()).collect();
+
+    report(
+        "invoke",
+        bench(&mut subscriptions, || {
+            callback.invoke(1);
+        }),
+        SUBSCRIBERS,
+    );
+    report(
+        "invoke_untimed",
+        bench(&mut subscriptions, || {
+            callback.invoke_untimed(1);
+        }),
+        SUBSCRIBERS,
+    );
+
+    // a single subscriber within the map of subscribers, compared to a declared single consumer
+    let callback = SingleThreadedCallback::<u32>::new();
+    let mut subscriptions = vec![callback.subscribe()];
+    report(
+        "single_subscriber",
+        bench(&mut subscriptions, || {
+            callback.invoke_untimed(1);
+        }),
+        1,
+    );
+
+    let callback = SingleThreadedCallback::<u32>::single_consumer();
+    let mut subscriptions = vec![callback.subscribe()];
+    report(
+        "single_consumer",
+        bench(&mut subscriptions, || {
+            callback.invoke_untimed(1);
+        }),
+        1,
+    );
+
+    // filter half of the events through a variant mask, compared to a predicate closure
+    let callback = SingleThreadedCallback::<Event>::new();
+    let mut subscriptions: Vec<Subscription<Event>> = (0..SUBSCRIBERS)
+        .map(|_| callback.subscribe_variant_mask(1 << 0))
+        .collect();
+    report(
+        "variant_mask",
+        bench(&mut subscriptions, || {
+            callback.invoke_untimed(Event::Foo);
+            callback.invoke_untimed(Event::Bar);
+        }),
+        SUBSCRIBERS,
+    );
+
+    let callback = SingleThreadedCallback::<Event>::new();
+    let mut subscriptions: Vec<Subscription<Event>> = (0..SUBSCRIBERS)
+        .map(|_| {
+            let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+            callback.subscribe_fn(move |e| {
+                if matches!(*e, Event::Foo) {
+                    let _ = tx.send(e);
+                }
+            });
+            rx
+        })
+        .collect();
+    report(
+        "predicate",
+        bench(&mut subscriptions, || {
+            callback.invoke_untimed(Event::Foo);
+            callback.invoke_untimed(Event::Bar);
+        }),
+        SUBSCRIBERS,
+    );
+}
+
+/// Measure the given invocation for the configured number of iterations.
+/// The subscriptions are drained after the measurement.
+fn bench<T, F>(subscriptions: &mut [Subscription<T>], invocation: F) -> Duration
+where
+    F: Fn(),
+{
+    let start_time = Instant::now();
+    for _ in 0..ITERATIONS {
+        invocation();
+    }
+    let elapsed = start_time.elapsed();
+
+    for subscription in subscriptions.iter_mut() {
+        while subscription.try_recv().is_ok() {}
+    }
+
+    elapsed
+}
+
+fn report(name: &str, elapsed: Duration, subscribers: usize) {
+    println!(
+        "{:<20} {:>10.1} ns/iter ({} subscribers)",
+        name,
+        elapsed.as_nanos() as f64 / ITERATIONS as f64,
+        subscribers
+    );
+}
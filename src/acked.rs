@@ -0,0 +1,434 @@
+use crate::callback::ForwardSender;
+use crate::{CallbackHandle, DeliveryFailure};
+use log::{debug, trace};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Debug, Formatter};
+use std::future::poll_fn;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::task::{Context, Poll};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio::sync::oneshot;
+
+/// The durable subscription type for the interested event.
+/// Each received [AckedEvent] needs to be acknowledged, otherwise it will be redelivered
+/// when a new durable subscription is created with the same key.
+pub type DurableSubscription<T> = UnboundedReceiver<AckedEvent<T>>;
+
+/// An event which should be acknowledged by the subscriber once it has been processed.
+///
+/// Dropping the event without calling [AckedEvent::ack] leaves the event unacknowledged.
+/// The events of a [DurableSubscription] are redelivered when unacknowledged,
+/// while the events of an [AckedSubscription] confirm the processing towards the invoker of the event.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use fx_callback::DurableSubscription;
+///
+/// async fn example(mut receiver: DurableSubscription<String>) {
+///     while let Some(event) = receiver.recv().await {
+///         // process the event
+///         println!("Received event {}", *event);
+///         event.ack();
+///     }
+/// }
+/// ```
+pub struct AckedEvent<T> {
+    value: Arc<T>,
+    sequence: u64,
+    token: AckToken<T>,
+}
+
+impl<T> AckedEvent<T> {
+    /// Get the shared instance of the event value.
+    pub fn value(&self) -> &Arc<T> {
+        &self.value
+    }
+
+    /// Get the sequence number of the event.
+    /// A redelivered event has the same sequence number as the original delivery,
+    /// which can be used by the subscriber to detect duplicate events.
+    /// The events of an [AckedSubscription] are numbered in the order in which they've been received.
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Acknowledge that the event has been processed by the subscriber.
+    pub fn ack(self) {
+        self.token.ack(self.sequence);
+    }
+}
+
+impl<T> Deref for AckedEvent<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T> Debug for AckedEvent<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AckedEvent")
+            .field("value", &self.value)
+            .field("sequence", &self.sequence)
+            .finish()
+    }
+}
+
+/// The token of an [AckedEvent] which informs the origin of the event about the acknowledgement.
+enum AckToken<T> {
+    Durable {
+        registry: Weak<DurableRegistry<T>>,
+        key: String,
+    },
+    /// The confirmation of the invoker which awaits the processing of the event, if any.
+    Confirm(Option<oneshot::Sender<()>>),
+}
+
+impl<T> AckToken<T> {
+    fn ack(self, sequence: u64) {
+        match self {
+            AckToken::Durable { registry, key } => {
+                if let Some(registry) = registry.upgrade() {
+                    registry.ack(&key, sequence);
+                }
+            }
+            AckToken::Confirm(confirmation) => {
+                if let Some(confirmation) = confirmation {
+                    let _ = confirmation.send(());
+                }
+            }
+        }
+    }
+}
+
+/// The registry of durable subscribers which retains unacknowledged events per subscriber key.
+pub(crate) struct DurableRegistry<T> {
+    subscribers: Mutex<HashMap<String, DurableSubscriber<T>>>,
+}
+
+impl<T> DurableRegistry<T> {
+    pub(crate) fn new() -> Arc<Self> {
+        Arc::new(Self {
+            subscribers: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Subscribe with the given key, redelivering all unacknowledged events of the key.
+    pub(crate) fn subscribe(self: &Arc<Self>, key: String) -> DurableSubscription<T> {
+        let mut mutex = self.subscribers.lock().expect("failed to acquire lock");
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let subscriber = mutex
+            .entry(key.clone())
+            .or_insert_with(|| DurableSubscriber {
+                sender: None,
+                unacked: BTreeMap::new(),
+                sequence: 0,
+            });
+
+        for (sequence, value) in subscriber.unacked.iter() {
+            let _ = tx.send(self.event(&key, *sequence, value.clone()));
+        }
+        if !subscriber.unacked.is_empty() {
+            debug!(
+                "Redelivered a total of {} unacknowledged events to durable subscriber {}",
+                subscriber.unacked.len(),
+                key
+            );
+        }
+
+        subscriber.sender = Some(tx);
+        trace!("Added durable callback {}", key);
+        rx
+    }
+
+    /// Remove the durable subscriber with the given key, discarding all its unacknowledged events.
+    pub(crate) fn remove(&self, key: &str) -> bool {
+        let mut mutex = self.subscribers.lock().expect("failed to acquire lock");
+        mutex.remove(key).is_some()
+    }
+
+    /// Get the total number of retained unacknowledged events for the given key.
+    pub(crate) fn unacked_len(&self, key: &str) -> usize {
+        let mutex = self.subscribers.lock().expect("failed to acquire lock");
+        mutex.get(key).map(|e| e.unacked.len()).unwrap_or(0)
+    }
+
+    /// Retain and deliver the given value to all durable subscribers.
+    pub(crate) fn invoke(self: &Arc<Self>, value: &Arc<T>) {
+        let mut mutex = self.subscribers.lock().expect("failed to acquire lock");
+        for (key, subscriber) in mutex.iter_mut() {
+            let sequence = subscriber.sequence;
+            subscriber.sequence += 1;
+            subscriber.unacked.insert(sequence, value.clone());
+
+            if let Some(sender) = subscriber.sender.as_ref() {
+                if sender
+                    .send(self.event(key, sequence, value.clone()))
+                    .is_err()
+                {
+                    trace!("Durable callback {} has been disconnected", key);
+                    subscriber.sender = None;
+                }
+            }
+        }
+    }
+
+    fn ack(&self, key: &str, sequence: u64) {
+        let mut mutex = self.subscribers.lock().expect("failed to acquire lock");
+        if let Some(subscriber) = mutex.get_mut(key) {
+            subscriber.unacked.remove(&sequence);
+        }
+    }
+
+    fn event(self: &Arc<Self>, key: &str, sequence: u64, value: Arc<T>) -> AckedEvent<T> {
+        AckedEvent {
+            value,
+            sequence,
+            token: AckToken::Durable {
+                registry: Arc::downgrade(self),
+                key: key.to_string(),
+            },
+        }
+    }
+}
+
+impl<T> Debug for DurableRegistry<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DurableRegistry")
+            .field("subscribers", &self.subscribers.lock().unwrap().len())
+            .finish()
+    }
+}
+
+struct DurableSubscriber<T> {
+    sender: Option<UnboundedSender<AckedEvent<T>>>,
+    unacked: BTreeMap<u64, Arc<T>>,
+    sequence: u64,
+}
+
+/// The liveness of an acked subscription, as reported by a liveness probe of the callback holder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LivenessStatus {
+    /// The consumer of the subscription answered the probe within the timeout.
+    Alive,
+    /// The consumer of the subscription didn't answer the probe within the timeout,
+    /// with the estimated number of events which are still pending for the consumer.
+    Stalled(usize),
+    /// The subscription has been dropped.
+    Closed,
+}
+
+/// A subscription which answers the liveness probes of its callback holder while its events are being received.
+///
+/// An open subscription only indicates that it hasn't been dropped, not that its consumer is still receiving the events.
+/// The callback holder can probe this subscription to detect a stuck consumer, as a probe is only answered
+/// once the consumer has received all events which have been queued before the probe.
+/// Receiving the events requires no additional action of the consumer.
+///
+/// The events which are received through [AckedSubscription::recv_acked] should be acknowledged once they've been processed,
+/// which confirms the processing towards an invoker awaiting it, see [crate::MultiThreadedCallback::invoke_and_confirm].
+/// The events which are received through [AckedSubscription::recv] are confirmed as soon as they've been received.
+///
+/// Dropping this subscription removes the callback on the next invocation.
+pub struct AckedSubscription<T> {
+    handle: CallbackHandle,
+    receiver: UnboundedReceiver<Envelope<T>>,
+    progress: Arc<AckedProgress>,
+}
+
+impl<T> AckedSubscription<T> {
+    /// Get the handle of this subscription within its callback holder, which can be used to probe its liveness.
+    pub fn handle(&self) -> CallbackHandle {
+        self.handle
+    }
+
+    /// Receive the next event of this subscription.
+    ///
+    /// # Returns
+    ///
+    /// It returns [None] when the callback has been removed and all queued events have been received.
+    pub async fn recv(&mut self) -> Option<Arc<T>> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Poll to receive the next event of this subscription, answering any queued liveness probes.
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Arc<T>>> {
+        self.poll_recv_acked(cx)
+            .map(|event| event.map(Self::confirm_received))
+    }
+
+    /// Receive the next event of this subscription, which should be acknowledged once it has been processed.
+    /// Dropping the event without acknowledging it reports the event as unconfirmed to an invoker awaiting its processing.
+    ///
+    /// # Returns
+    ///
+    /// It returns [None] when the callback has been removed and all queued events have been received.
+    pub async fn recv_acked(&mut self) -> Option<AckedEvent<T>> {
+        poll_fn(|cx| self.poll_recv_acked(cx)).await
+    }
+
+    /// Poll to receive the next event of this subscription which should be acknowledged, answering any queued liveness probes.
+    pub fn poll_recv_acked(&mut self, cx: &mut Context<'_>) -> Poll<Option<AckedEvent<T>>> {
+        loop {
+            match self.receiver.poll_recv(cx) {
+                Poll::Ready(Some(envelope)) => {
+                    if let Some(event) = self.open(envelope) {
+                        return Poll::Ready(Some(event));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    /// Try to receive the next event of this subscription without waiting.
+    ///
+    /// # Returns
+    ///
+    /// It returns [None] when no event is currently queued.
+    pub fn try_recv(&mut self) -> Option<Arc<T>> {
+        while let Ok(envelope) = self.receiver.try_recv() {
+            if let Some(event) = self.open(envelope) {
+                return Some(Self::confirm_received(event));
+            }
+        }
+        None
+    }
+
+    pub(crate) fn set_handle(&mut self, handle: CallbackHandle) {
+        self.handle = handle;
+    }
+
+    /// Open the given envelope, answering it when it's a liveness probe.
+    fn open(&self, envelope: Envelope<T>) -> Option<AckedEvent<T>> {
+        match envelope {
+            Envelope::Event(value, confirmation) => {
+                let sequence = self.progress.received.fetch_add(1, Ordering::Relaxed);
+                Some(AckedEvent {
+                    value,
+                    sequence: sequence as u64,
+                    token: AckToken::Confirm(confirmation),
+                })
+            }
+            Envelope::Probe(reply) => {
+                let _ = reply.send(());
+                None
+            }
+        }
+    }
+
+    /// Confirm the given event on receipt, as the consumer doesn't acknowledge the processing of it.
+    fn confirm_received(event: AckedEvent<T>) -> Arc<T> {
+        let value = event.value.clone();
+        event.ack();
+        value
+    }
+}
+
+impl<T> Debug for AckedSubscription<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AckedSubscription")
+            .field("handle", &self.handle)
+            .field("pending", &self.progress.pending())
+            .finish()
+    }
+}
+
+/// The sender side of an [AckedSubscription], which is registered as a callback of the holder.
+pub(crate) struct AckedSender<T> {
+    sender: UnboundedSender<Envelope<T>>,
+    progress: Arc<AckedProgress>,
+}
+
+impl<T> AckedSender<T> {
+    /// Create a new acked sender and subscription pair.
+    /// The handle of the subscription should be set once the sender has been registered.
+    pub(crate) fn new() -> (Self, AckedSubscription<T>) {
+        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+        let progress = Arc::new(AckedProgress::default());
+
+        (
+            Self {
+                sender,
+                progress: progress.clone(),
+            },
+            AckedSubscription {
+                handle: CallbackHandle::new(),
+                receiver,
+                progress,
+            },
+        )
+    }
+}
+
+impl<T> ForwardSender<T> for AckedSender<T>
+where
+    T: Send + Sync,
+{
+    fn send(&self, value: &Arc<T>) -> Result<(), DeliveryFailure> {
+        self.sender
+            .send(Envelope::Event(value.clone(), None))
+            .map_err(|_| DeliveryFailure::Closed)?;
+        self.progress.sent.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn is_confirmable(&self) -> bool {
+        true
+    }
+
+    fn send_confirmed(&self, value: &Arc<T>) -> Result<oneshot::Receiver<()>, DeliveryFailure> {
+        let (tx, rx) = oneshot::channel();
+        self.sender
+            .send(Envelope::Event(value.clone(), Some(tx)))
+            .map_err(|_| DeliveryFailure::Closed)?;
+        self.progress.sent.fetch_add(1, Ordering::Relaxed);
+        Ok(rx)
+    }
+
+    fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+
+    fn queue_depth(&self) -> Option<usize> {
+        Some(self.progress.pending())
+    }
+
+    fn probe(&self) -> Option<oneshot::Receiver<()>> {
+        let (tx, rx) = oneshot::channel();
+        // a closed subscription drops the probe, which is reported as closed by the receiver of the reply
+        let _ = self.sender.send(Envelope::Probe(tx));
+        Some(rx)
+    }
+}
+
+/// The number of events which have been sent to and received by an [AckedSubscription].
+#[derive(Debug, Default)]
+struct AckedProgress {
+    sent: AtomicUsize,
+    received: AtomicUsize,
+}
+
+impl AckedProgress {
+    /// Get the estimated number of events which haven't been received yet.
+    fn pending(&self) -> usize {
+        self.sent
+            .load(Ordering::Relaxed)
+            .saturating_sub(self.received.load(Ordering::Relaxed))
+    }
+}
+
+/// The message of an [AckedSubscription] channel.
+enum Envelope<T> {
+    /// The event, together with the confirmation of the invoker which awaits the processing of it, if any.
+    Event(Arc<T>, Option<oneshot::Sender<()>>),
+    Probe(oneshot::Sender<()>),
+}
@@ -0,0 +1,249 @@
+use crate::callback::ForwardSender;
+use crate::DeliveryFailure;
+use log::{debug, warn};
+use std::collections::VecDeque;
+use std::fmt::{Debug, Formatter};
+use std::future::poll_fn;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+/// A subscription which queues its events without a bound until its queue depth exceeds the high watermark,
+/// after which the oldest queued events are dropped to cap the queue at the watermark.
+///
+/// This protects long-running services against a stuck or pathologically slow consumer,
+/// trading the completeness of the received events for a bounded memory usage of the subscription.
+/// The queue returns to its regular behavior once the consumer has caught up.
+///
+/// Dropping this subscription removes the callback on the next invocation.
+pub struct AdaptiveSubscription<T> {
+    queue: Arc<AdaptiveQueue<T>>,
+}
+
+impl<T> AdaptiveSubscription<T> {
+    /// Receive the next event of this subscription.
+    ///
+    /// # Returns
+    ///
+    /// It returns [None] when the callback holder has been dropped and all queued events have been received.
+    pub async fn recv(&mut self) -> Option<Arc<T>> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Poll to receive the next event of this subscription.
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Arc<T>>> {
+        let mut state = self.queue.lock();
+        if let Some(event) = state.events.pop_front() {
+            return Poll::Ready(Some(event));
+        }
+        if state.sender_closed {
+            return Poll::Ready(None);
+        }
+
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+
+    /// Try to receive the next event of this subscription without waiting.
+    ///
+    /// # Returns
+    ///
+    /// It returns [None] when no event is currently queued.
+    pub fn try_recv(&mut self) -> Option<Arc<T>> {
+        self.queue.lock().events.pop_front()
+    }
+
+    /// Discard all events which are currently queued for this subscription.
+    pub(crate) fn clear(&mut self) {
+        self.queue.lock().events.clear();
+    }
+
+    /// Get the number of events which are currently queued for this subscription.
+    pub fn len(&self) -> usize {
+        self.queue.lock().events.len()
+    }
+
+    /// Check if no events are currently queued for this subscription.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Check if the queue of this subscription is currently capped at its high watermark,
+    /// meaning that the oldest events are being dropped.
+    pub fn is_capped(&self) -> bool {
+        self.queue.lock().capped
+    }
+
+    /// Get the total number of events which have been dropped for this subscription because its high watermark was exceeded.
+    pub fn dropped_count(&self) -> u64 {
+        self.queue.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl<T> Debug for AdaptiveSubscription<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AdaptiveSubscription")
+            .field("high_watermark", &self.queue.high_watermark)
+            .field("len", &self.len())
+            .field("dropped", &self.dropped_count())
+            .finish()
+    }
+}
+
+impl<T> Drop for AdaptiveSubscription<T> {
+    fn drop(&mut self) {
+        let mut state = self.queue.lock();
+        state.receiver_closed = true;
+        state.events.clear();
+    }
+}
+
+/// The sender side of an [AdaptiveSubscription], which is registered as a callback of the holder.
+pub(crate) struct AdaptiveSender<T> {
+    queue: Arc<AdaptiveQueue<T>>,
+}
+
+impl<T> AdaptiveSender<T> {
+    /// Create a new adaptive sender and subscription pair with the given high watermark.
+    ///
+    /// # Panics
+    ///
+    /// It panics when the given high watermark is 0.
+    pub(crate) fn new(high_watermark: usize) -> (Self, AdaptiveSubscription<T>) {
+        assert!(high_watermark > 0, "high watermark must be greater than 0");
+        let queue = Arc::new(AdaptiveQueue {
+            high_watermark,
+            dropped: AtomicU64::new(0),
+            state: Mutex::new(AdaptiveState {
+                events: VecDeque::new(),
+                capped: false,
+                waker: None,
+                receiver_closed: false,
+                sender_closed: false,
+            }),
+        });
+
+        (
+            Self {
+                queue: queue.clone(),
+            },
+            AdaptiveSubscription { queue },
+        )
+    }
+}
+
+impl<T> ForwardSender<T> for AdaptiveSender<T>
+where
+    T: Send + Sync,
+{
+    fn send(&self, value: &Arc<T>) -> Result<(), DeliveryFailure> {
+        let mut state = self.queue.lock();
+        if state.receiver_closed {
+            return Err(DeliveryFailure::Closed);
+        }
+
+        state.events.push_back(value.clone());
+        if state.events.len() > self.queue.high_watermark {
+            state.events.pop_front();
+            self.queue.dropped.fetch_add(1, Ordering::Relaxed);
+            if !state.capped {
+                state.capped = true;
+                warn!(
+                    "Adaptive subscription exceeded its high watermark of {}, dropping the oldest events",
+                    self.queue.high_watermark
+                );
+            }
+        } else if state.capped && state.events.len() < self.queue.high_watermark {
+            state.capped = false;
+            debug!(
+                "Adaptive subscription recovered below its high watermark of {}, after dropping a total of {} events",
+                self.queue.high_watermark,
+                self.queue.dropped.load(Ordering::Relaxed)
+            );
+        }
+
+        let waker = state.waker.take();
+        drop(state);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        Ok(())
+    }
+
+    fn is_closed(&self) -> bool {
+        self.queue.lock().receiver_closed
+    }
+
+    fn queue_depth(&self) -> Option<usize> {
+        Some(self.queue.lock().events.len())
+    }
+}
+
+impl<T> Drop for AdaptiveSender<T> {
+    fn drop(&mut self) {
+        let mut state = self.queue.lock();
+        state.sender_closed = true;
+        let waker = state.waker.take();
+        drop(state);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// The queue which is shared between an [AdaptiveSender] and its [AdaptiveSubscription].
+struct AdaptiveQueue<T> {
+    high_watermark: usize,
+    dropped: AtomicU64,
+    state: Mutex<AdaptiveState<T>>,
+}
+
+impl<T> AdaptiveQueue<T> {
+    fn lock(&self) -> std::sync::MutexGuard<'_, AdaptiveState<T>> {
+        self.state.lock().expect("failed to acquire lock")
+    }
+}
+
+struct AdaptiveState<T> {
+    events: VecDeque<Arc<T>>,
+    capped: bool,
+    waker: Option<Waker>,
+    receiver_closed: bool,
+    sender_closed: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::init_logger;
+
+    #[test]
+    fn test_send_high_watermark() {
+        init_logger!();
+        let (sender, mut subscription) = AdaptiveSender::<u32>::new(2);
+
+        for i in 0..4 {
+            sender.send(&Arc::new(i)).unwrap();
+        }
+
+        assert!(subscription.is_capped());
+        assert_eq!(2, subscription.dropped_count());
+        assert_eq!(Some(Arc::new(2)), subscription.try_recv());
+        assert_eq!(Some(Arc::new(3)), subscription.try_recv());
+
+        sender.send(&Arc::new(4)).unwrap();
+        assert!(!subscription.is_capped());
+        assert_eq!(Some(Arc::new(4)), subscription.try_recv());
+    }
+
+    #[test]
+    fn test_send_closed_subscription() {
+        init_logger!();
+        let (sender, subscription) = AdaptiveSender::<u32>::new(2);
+
+        drop(subscription);
+
+        assert!(sender.is_closed());
+        assert_eq!(Err(DeliveryFailure::Closed), sender.send(&Arc::new(1)));
+    }
+}
@@ -0,0 +1,154 @@
+use crate::{CallbackHandle, Subscription};
+use log::{debug, trace};
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex};
+
+/// The type-erased event of an [AnyEventBus].
+pub type AnyEvent = Arc<dyn Any + Send + Sync>;
+
+/// The type-erased sender of a typed subscriber.
+/// It returns `false` when the subscription of the subscriber has been dropped.
+type TypedSender = dyn Fn(&AnyEvent) -> bool + Send + Sync;
+
+/// A type-erased event bus which carries events of many different types,
+/// while its subscribers consume only the events of the type they're interested in.
+///
+/// Each invocation checks the type of the event once per subscriber,
+/// which is a cheap [std::any::TypeId] comparison.
+/// Events which don't match the type of a subscriber are skipped for that subscriber,
+/// while matching events are shared with the subscriber without being copied.
+///
+/// The events are delivered on the caller thread.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use fx_callback::AnyEventBus;
+/// use std::sync::Arc;
+///
+/// #[derive(Debug)]
+/// pub struct Connected;
+///
+/// #[derive(Debug)]
+/// pub struct Disconnected;
+///
+/// let bus = AnyEventBus::new();
+/// let mut connected = bus.subscribe_typed::<Connected>();
+/// let mut disconnected = bus.subscribe_typed::<Disconnected>();
+///
+/// bus.invoke(Arc::new(Connected));
+/// ```
+#[derive(Clone, Default)]
+pub struct AnyEventBus {
+    subscribers: Arc<Mutex<HashMap<CallbackHandle, Box<TypedSender>>>>,
+}
+
+impl AnyEventBus {
+    /// Create a new type-erased event bus.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Subscribe to the events of type `E` on this bus.
+    /// Events of any other type are skipped for this subscription.
+    pub fn subscribe_typed<E>(&self) -> Subscription<E>
+    where
+        E: Send + Sync + 'static,
+    {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<Arc<E>>();
+        let handle = CallbackHandle::new();
+        let sender = move |event: &AnyEvent| {
+            if !event.is::<E>() {
+                return !tx.is_closed();
+            }
+
+            match event.clone().downcast::<E>() {
+                Ok(event) => tx.send(event).is_ok(),
+                Err(_) => true,
+            }
+        };
+
+        self.subscribers
+            .lock()
+            .expect("failed to acquire lock")
+            .insert(handle, Box::new(sender));
+        trace!(
+            "Added typed callback {} for {}",
+            handle,
+            std::any::type_name::<E>()
+        );
+        rx
+    }
+
+    /// Invoke the subscribers of the event type with the given event.
+    /// Subscriptions which have been dropped are removed from the bus.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The type-erased event to invoke the subscribers with.
+    pub fn invoke(&self, event: AnyEvent) {
+        let mut mutex = self.subscribers.lock().expect("failed to acquire lock");
+        mutex.retain(|handle, sender| {
+            let open = sender(&event);
+            if !open {
+                debug!("Typed callback {} has been dropped", handle);
+            }
+            open
+        });
+    }
+}
+
+impl Debug for AnyEventBus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AnyEventBus")
+            .field(
+                "subscribers",
+                &self.subscribers.lock().map(|e| e.len()).unwrap_or(0),
+            )
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::init_logger;
+
+    #[derive(Debug, PartialEq)]
+    struct Connected(u32);
+
+    #[derive(Debug, PartialEq)]
+    struct Disconnected;
+
+    #[test]
+    fn test_subscribe_typed() {
+        init_logger!();
+        let bus = AnyEventBus::new();
+
+        let mut connected = bus.subscribe_typed::<Connected>();
+        let mut disconnected = bus.subscribe_typed::<Disconnected>();
+        bus.invoke(Arc::new(Connected(1)));
+        bus.invoke(Arc::new(Disconnected));
+        bus.invoke(Arc::new("unknown"));
+
+        assert_eq!(Connected(1), *connected.try_recv().unwrap());
+        assert!(connected.try_recv().is_err(), "expected no other events");
+        assert_eq!(Disconnected, *disconnected.try_recv().unwrap());
+        assert!(disconnected.try_recv().is_err(), "expected no other events");
+    }
+
+    #[test]
+    fn test_invoke_closed_subscription() {
+        init_logger!();
+        let bus = AnyEventBus::new();
+
+        let connected = bus.subscribe_typed::<Connected>();
+        let _disconnected = bus.subscribe_typed::<Disconnected>();
+        drop(connected);
+        bus.invoke(Arc::new(Disconnected));
+
+        assert_eq!(1, bus.subscribers.lock().unwrap().len());
+    }
+}
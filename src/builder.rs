@@ -0,0 +1,195 @@
+use crate::{Callback, MultiThreadedCallback, SingleThreadedCallback, Subscriber};
+use std::fmt::Debug;
+
+/// The builder of a [MultiThreadedCallback], which allows the holder to be fully wired before it's used.
+///
+/// The subscribers which are known upfront are registered on [MultiThreadedCallbackBuilder::build],
+/// before the holder is returned, so no early event can be invoked before they're attached.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use fx_callback::{MultiThreadedCallback, Subscriber};
+///
+/// #[derive(Debug)]
+/// pub enum MyEvent {
+///     Foo,
+/// }
+///
+/// fn wire(subscribers: Vec<Subscriber<MyEvent>>) -> MultiThreadedCallback<MyEvent> {
+///     MultiThreadedCallback::builder()
+///         .ordered_delivery()
+///         .subscribers(subscribers)
+///         .build()
+/// }
+/// ```
+#[derive(Debug)]
+pub struct MultiThreadedCallbackBuilder<T>
+where
+    T: Debug + Send + Sync,
+{
+    ordered: bool,
+    exclusive: bool,
+    max_subscribers: Option<usize>,
+    subscribers: Vec<Subscriber<T>>,
+}
+
+impl<T> MultiThreadedCallbackBuilder<T>
+where
+    T: Debug + Send + Sync + 'static,
+{
+    pub(crate) fn new() -> Self {
+        Self {
+            ordered: true,
+            exclusive: false,
+            max_subscribers: None,
+            subscribers: Vec::new(),
+        }
+    }
+
+    /// Deliver the events in the exact order in which they've been invoked, see [MultiThreadedCallback::new].
+    /// This is the default delivery of the holder.
+    pub fn ordered_delivery(mut self) -> Self {
+        self.ordered = true;
+        self
+    }
+
+    /// Spawn each invocation as an independent task instead of queueing it on the ordered fan-out task.
+    /// This allows the invocations to be processed concurrently,
+    /// but the scheduler might run them in any order, so subscribers might observe the events out of order.
+    pub fn unordered_delivery(mut self) -> Self {
+        self.ordered = false;
+        self
+    }
+
+    /// Accept at most one subscriber at a time, see [MultiThreadedCallback::new_exclusive].
+    pub fn exclusive(mut self) -> Self {
+        self.exclusive = true;
+        self
+    }
+
+    /// Accept at most the given number of subscribers, see [MultiThreadedCallback::with_max_subscribers].
+    pub fn max_subscribers(mut self, limit: usize) -> Self {
+        self.max_subscribers = Some(limit);
+        self
+    }
+
+    /// Register the given subscriber when the holder is built.
+    pub fn subscriber(mut self, subscriber: Subscriber<T>) -> Self {
+        self.subscribers.push(subscriber);
+        self
+    }
+
+    /// Register the given subscribers when the holder is built.
+    pub fn subscribers<I>(mut self, subscribers: I) -> Self
+    where
+        I: IntoIterator<Item = Subscriber<T>>,
+    {
+        self.subscribers.extend(subscribers);
+        self
+    }
+
+    /// Build the callback holder with the configured subscribers registered.
+    ///
+    /// The subscribers are registered in the order in which they've been added through [Callback::subscribe_with],
+    /// so their handles aren't exposed. They can be looked up through [MultiThreadedCallback::describe_subscribers] when needed.
+    pub fn build(self) -> MultiThreadedCallback<T> {
+        let callback =
+            MultiThreadedCallback::with_options(self.ordered, self.max_subscribers, self.exclusive);
+
+        for subscriber in self.subscribers {
+            callback.subscribe_with(subscriber);
+        }
+        callback
+    }
+}
+
+/// The builder of a [SingleThreadedCallback], which allows the holder to be fully wired before it's used.
+///
+/// The subscribers which are known upfront are registered on [SingleThreadedCallbackBuilder::build],
+/// before the holder is returned, so no early event can be invoked before they're attached.
+#[derive(Debug)]
+pub struct SingleThreadedCallbackBuilder<T>
+where
+    T: Debug + Send + Sync,
+{
+    exclusive: bool,
+    max_subscribers: Option<usize>,
+    subscribers: Vec<Subscriber<T>>,
+}
+
+impl<T> SingleThreadedCallbackBuilder<T>
+where
+    T: Debug + Send + Sync,
+{
+    pub(crate) fn new() -> Self {
+        Self {
+            exclusive: false,
+            max_subscribers: None,
+            subscribers: Vec::new(),
+        }
+    }
+
+    /// Accept at most one subscriber at a time, see [SingleThreadedCallback::new_exclusive].
+    pub fn exclusive(mut self) -> Self {
+        self.exclusive = true;
+        self
+    }
+
+    /// Accept at most the given number of subscribers, see [SingleThreadedCallback::with_max_subscribers].
+    pub fn max_subscribers(mut self, limit: usize) -> Self {
+        self.max_subscribers = Some(limit);
+        self
+    }
+
+    /// Register the given subscriber when the holder is built.
+    pub fn subscriber(mut self, subscriber: Subscriber<T>) -> Self {
+        self.subscribers.push(subscriber);
+        self
+    }
+
+    /// Register the given subscribers when the holder is built.
+    pub fn subscribers<I>(mut self, subscribers: I) -> Self
+    where
+        I: IntoIterator<Item = Subscriber<T>>,
+    {
+        self.subscribers.extend(subscribers);
+        self
+    }
+
+    /// Build the callback holder with the configured subscribers registered.
+    ///
+    /// The subscribers are registered in the order in which they've been added through [Callback::subscribe_with],
+    /// so their handles aren't exposed. They can be looked up through [SingleThreadedCallback::describe_subscribers] when needed.
+    pub fn build(self) -> SingleThreadedCallback<T> {
+        let callback = SingleThreadedCallback::with_options(self.max_subscribers, self.exclusive);
+        for subscriber in self.subscribers {
+            callback.subscribe_with(subscriber);
+        }
+        callback
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::init_logger;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    #[test]
+    fn test_single_threaded_build() {
+        init_logger!();
+        let (first_tx, mut first_rx) = unbounded_channel();
+        let (second_tx, mut second_rx) = unbounded_channel();
+
+        let callback = SingleThreadedCallback::<u32>::builder()
+            .subscriber(first_tx)
+            .subscribers(vec![second_tx])
+            .build();
+        callback.invoke(1);
+
+        assert_eq!(2, callback.describe_subscribers().len());
+        assert_eq!(1, *first_rx.try_recv().unwrap());
+        assert_eq!(1, *second_rx.try_recv().unwrap());
+    }
+}
@@ -1,15 +1,53 @@
+use crate::acked::AckedSender;
+use crate::adaptive::AdaptiveSender;
+use crate::capture::CaptureSender;
+use crate::latest::LatestSender;
+use crate::overflow::OverflowSender;
+use crate::request::collect_replies;
+use crate::{
+    AckedSubscription, AdaptiveSubscription, CallbackError, CallbackStats, CaptureHandle,
+    DurableRegistry, DurableSubscription, LatencyHistogram, LatencyStats, LatestSubscription,
+    LivenessStatus, MultiThreadedCallbackBuilder, NoSubscribersError, OverflowPolicy,
+    OverflowSubscription, Request, SingleThreadedCallbackBuilder, StatsRecorder, Timestamped,
+};
 use fx_handle::Handle;
-use log::{debug, error, trace, warn};
-use std::collections::HashMap;
-use std::fmt::Debug;
-use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use indexmap::IndexMap;
+use log::{debug, error, log, trace, warn};
+use std::cmp::Reverse;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{Debug, Display};
+use std::future::{poll_fn, Future};
+use std::panic;
+use std::panic::{AssertUnwindSafe, RefUnwindSafe};
+use std::pin::pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 use tokio::runtime::Runtime;
-use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::{Receiver, Sender, UnboundedReceiver, UnboundedSender};
+use tokio::sync::{oneshot, Notify};
+use tokio::task::JoinHandle;
 
 /// The unique identifier for a callback.
 pub type CallbackHandle = Handle;
 
+/// The maximum number of attempts to generate a callback handle which isn't in use yet.
+const MAX_HANDLE_ATTEMPTS: usize = 16;
+/// The delivery time after which a callback is considered to be slow.
+const SLOW_CALLBACK_THRESHOLD: Duration = Duration::from_secs(1);
+/// The default minimum interval between two slow callback warnings of the same subscriber.
+const DEFAULT_SLOW_WARNING_INTERVAL: Duration = Duration::from_secs(30);
+/// The number of deliveries after which an async fan-out yields to the scheduler.
+const FAN_OUT_YIELD_INTERVAL: usize = 256;
+/// The default maximum number of events which are buffered for a paused subscriber.
+const DEFAULT_PAUSE_BUFFER_CAPACITY: usize = 1024;
+/// The interval at which a closed future checks for subscriptions which have been dropped, but not yet removed.
+const CLOSED_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+/// The minimum interval between two dropped event warnings of the same subscriber.
+const DROP_WARNING_INTERVAL: Duration = Duration::from_secs(30);
+
 /// The subscription type for the interested event.
 /// Drop this subscription to remove the callback.
 pub type Subscription<T> = UnboundedReceiver<Arc<T>>;
@@ -18,8 +56,25 @@ pub type Subscription<T> = UnboundedReceiver<Arc<T>>;
 /// This can be used to send the interested event from multiple sources into one receiver.
 pub type Subscriber<T> = UnboundedSender<Arc<T>>;
 
+/// The bounded subscription type for the interested event.
+/// Events are dropped for this subscription when its queue is full.
+pub type BoundedSubscription<T> = Receiver<Arc<T>>;
+
+/// The bounded subscriber type for the interested event.
+/// This can be used to send the interested event from multiple sources into one bounded receiver.
+pub type BoundedSubscriber<T> = Sender<Arc<T>>;
+
+/// The owned subscription type for the interested event.
+/// Each event is cloned into an owned value for this subscription.
+pub type OwnedSubscription<T> = UnboundedReceiver<T>;
+
+/// The record of a single traced invocation of a callback holder.
+/// It contains the moment of the invocation, the invoked event and the handles of the notified subscribers in delivery order.
+pub type InvokeRecord<T> = (Instant, Arc<T>, Vec<CallbackHandle>);
+
 /// Allows adding callbacks to the struct.
 /// The struct will inform the [Subscription] when a certain event occurs.
+/// The callback holders of this crate inform their subscribers in the order in which they've subscribed.
 ///
 /// # Example
 ///
@@ -79,9 +134,123 @@ where
     ///
     /// ## Remarks
     ///
-    /// It is possible to grant multiple subscriptions from the same source to the same interested event,
-    /// as the [Callback] is only a holder for the [Subscription] and can't detect any duplicates.
-    fn subscribe_with(&self, subscriber: Subscriber<T>);
+    /// It is possible to grant multiple subscriptions from the same source to the same interested event.
+    /// The [MultiThreadedCallback] and [SingleThreadedCallback] holders can detect such duplicates through their [DuplicatePolicy].
+    ///
+    /// When the holder rejects the subscriber, e.g. because its maximum number of subscribers has been reached,
+    /// the subscriber is dropped with a warning and the returned handle isn't registered with the holder,
+    /// so [Callback::unsubscribe] returns `false` for it.
+    /// Use [Callback::try_subscribe_with] to detect the rejection instead.
+    ///
+    /// # Returns
+    ///
+    /// It returns the handle of the new subscription, which can be used to [Callback::unsubscribe] the subscriber.
+    fn subscribe_with(&self, subscriber: Subscriber<T>) -> CallbackHandle;
+
+    /// Subscribe to the interested event with a [Subscriber], unless the holder rejects the subscriber.
+    ///
+    /// The default implementation never rejects the subscriber and registers it through [Callback::subscribe_with],
+    /// implementations which might reject a subscriber should override it.
+    ///
+    /// # Returns
+    ///
+    /// It returns the handle of the new subscription, or the [CallbackError] when the subscriber has been rejected.
+    fn try_subscribe_with(
+        &self,
+        subscriber: Subscriber<T>,
+    ) -> Result<CallbackHandle, CallbackError> {
+        Ok(self.subscribe_with(subscriber))
+    }
+
+    /// Remove the subscriber with the given handle immediately,
+    /// instead of waiting for the next invocation to detect that its subscription has been dropped.
+    /// Removing the subscriber closes its subscription once all other senders of the subscription have been dropped as well.
+    ///
+    /// # Returns
+    ///
+    /// It returns `true` when the subscriber has been removed,
+    /// or `false` when the handle is unknown or the subscriber has already been removed.
+    fn unsubscribe(&self, handle: CallbackHandle) -> bool;
+
+    /// Get the number of subscribers which are currently registered.
+    /// Subscribers of which the subscription has been dropped are counted until they're removed from the holder.
+    ///
+    /// The count is cheap to retrieve, e.g. to skip building an expensive event when nobody is interested in it.
+    fn subscriber_count(&self) -> usize;
+}
+
+/// The policy of a callback holder for subscribers which are registered more than once,
+/// being a [Subscriber] of which the channel is already registered with the holder.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Register the duplicate subscriber as an additional subscription, which receives each event once more.
+    #[default]
+    AllowDuplicates,
+    /// Ignore the duplicate subscriber, returning the handle of the already registered subscriber instead.
+    IgnoreDuplicates,
+    /// Reject the duplicate subscriber with [CallbackError::DuplicateSubscriber].
+    ErrorOnDuplicate,
+}
+
+/// The policy of a callback holder for the invocations which require at least one subscriber,
+/// see [SingleThreadedCallback::invoke_require_subscribers] and [MultiThreadedCallback::invoke_require_subscribers].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NoSubscribersPolicy {
+    /// Only invoke the value when it's delivered to at least one subscriber.
+    /// The tap and the durable subscribers observe the value after it has been delivered to the subscribers,
+    /// and don't observe it at all when it couldn't be delivered to any subscriber.
+    #[default]
+    Reject,
+    /// Always invoke the value, as with a regular invocation, and only report when it couldn't be delivered to any subscriber.
+    /// The tap and the durable subscribers observe the value even when it couldn't be delivered to any subscriber.
+    FanOut,
+}
+
+/// A listener which is informed each time a subscriber is added to or removed from a callback holder,
+/// e.g. to only run an expensive producer while the holder has subscribers.
+///
+/// The listener is never invoked while the subscribers of the holder are locked,
+/// so it's allowed to interact with the callback holder from within the listener.
+pub trait SubscriptionListener: Send + Sync {
+    /// Invoked when the subscriber with the given handle has been added.
+    /// The count is the number of registered subscribers, including the new subscriber.
+    fn on_subscribed(&self, handle: CallbackHandle, count: usize);
+
+    /// Invoked when the subscriber with the given handle has been removed,
+    /// either explicitly or because its subscription has been dropped.
+    /// The count is the number of remaining subscribers.
+    fn on_unsubscribed(&self, handle: CallbackHandle, count: usize);
+}
+
+/// Maps each variant of an event to a bit, allowing subscribers to filter the variants they're interested in
+/// through a cheap bitwise mask, see [MultiThreadedCallback::subscribe_variant_mask].
+///
+/// As the bit is a `u64`, at most 64 distinct variants can be filtered.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use fx_callback::VariantMask;
+///
+/// #[derive(Debug)]
+/// pub enum MyEvent {
+///     Foo,
+///     Bar(u32),
+/// }
+///
+/// impl VariantMask for MyEvent {
+///     fn variant_bit(&self) -> u64 {
+///         match self {
+///             MyEvent::Foo => 1 << 0,
+///             MyEvent::Bar(_) => 1 << 1,
+///         }
+///     }
+/// }
+/// ```
+pub trait VariantMask {
+    /// Get the bit of the variant of this event.
+    /// Each variant should map to a single distinct bit.
+    fn variant_bit(&self) -> u64;
 }
 
 /// A multithreaded callback holder.
@@ -91,7 +260,7 @@ where
 /// # Example
 ///
 /// ```rust,no_run
-/// use fx_callback::{Callback, MultiThreadedCallback, Subscriber, Subscription};
+/// use fx_callback::{Callback, CallbackHandle, MultiThreadedCallback, Subscriber, Subscription};
 ///
 /// /// The events of the struct that informs subscribers about changes to the data within the struct.
 /// #[derive(Debug, Clone, PartialEq)]
@@ -116,9 +285,17 @@ where
 ///         self.callbacks.subscribe()
 ///     }
 ///
-///     fn subscribe_with(&self, subscriber: Subscriber<MyEvent>) {
+///     fn subscribe_with(&self, subscriber: Subscriber<MyEvent>) -> CallbackHandle {
 ///         self.callbacks.subscribe_with(subscriber)
 ///     }
+///
+///     fn unsubscribe(&self, handle: CallbackHandle) -> bool {
+///         self.callbacks.unsubscribe(handle)
+///     }
+///
+///     fn subscriber_count(&self) -> usize {
+///         self.callbacks.subscriber_count()
+///     }
 /// }
 /// ```
 #[derive(Debug)]
@@ -128,6 +305,7 @@ where
 {
     base: Arc<BaseCallback<T>>,
     runtime: Arc<Mutex<Option<Runtime>>>,
+    ordered: Option<Arc<OrderedDispatcher<T>>>,
 }
 
 impl<T> Callback<T> for MultiThreadedCallback<T>
@@ -138,9 +316,24 @@ where
         self.base.subscribe()
     }
 
-    fn subscribe_with(&self, subscriber: Subscriber<T>) {
+    fn subscribe_with(&self, subscriber: Subscriber<T>) -> CallbackHandle {
         self.base.subscribe_with(subscriber)
     }
+
+    fn try_subscribe_with(
+        &self,
+        subscriber: Subscriber<T>,
+    ) -> Result<CallbackHandle, CallbackError> {
+        self.base.try_subscribe_with(subscriber)
+    }
+
+    fn unsubscribe(&self, handle: CallbackHandle) -> bool {
+        self.base.remove(&handle)
+    }
+
+    fn subscriber_count(&self) -> usize {
+        self.base.subscriber_count()
+    }
 }
 
 impl<T> Clone for MultiThreadedCallback<T>
@@ -151,334 +344,7023 @@ where
         Self {
             base: self.base.clone(),
             runtime: self.runtime.clone(),
+            ordered: self.ordered.clone(),
         }
     }
 }
 
-impl<T> MultiThreadedCallback<T>
+impl<T> Drop for MultiThreadedCallback<T>
 where
-    T: Debug + Send + Sync + 'static,
+    T: Debug + Send + Sync,
 {
-    /// Creates a new multithreaded callback.
-    pub fn new() -> Self {
-        Self {
-            base: Arc::new(BaseCallback::<T>::new()),
-            runtime: Arc::new(Mutex::new(None)),
+    fn drop(&mut self) {
+        // the last holder processes the queued invocations itself, so the holder is always dropped on this thread
+        if let Some(ordered) = self.ordered.as_ref() {
+            if Arc::strong_count(ordered) == 1 {
+                ordered.drain(&self.base);
+            }
         }
-    }
 
-    /// Invoke the currently registered callbacks and inform them of the given value.
-    ///
-    /// # Arguments
-    ///
-    /// * `value` - The value to invoke the callbacks with.
-    pub fn invoke(&self, value: T) {
-        let inner = self.base.clone();
-        match tokio::runtime::Handle::try_current() {
-            Ok(_) => {
-                // spawn the invocation operation in a new thread
-                tokio::spawn(async move {
-                    inner.invoke(value);
-                });
-            }
-            Err(_) => match self.runtime.lock() {
-                Ok(mut runtime) => {
-                    runtime
-                        .get_or_insert_with(|| Runtime::new().unwrap())
-                        .spawn(async move {
-                            inner.invoke(value);
-                        });
-                }
-                Err(e) => error!("Failed to acquire lock: {}", e),
-            },
+        // shutdown the internal runtime in the background when this is the last holder of it,
+        // as the runtime might otherwise be dropped within an async context which panics
+        if let Some(runtime) = Arc::get_mut(&mut self.runtime)
+            .and_then(|e| e.get_mut().ok())
+            .and_then(|e| e.take())
+        {
+            runtime.shutdown_background();
         }
     }
 }
 
-/// A single threaded or current threaded callback holder.
-///
-/// This callback holder will invoke the given events on the current thread, thus blocking the caller thread for other tasks.
-#[derive(Debug, Clone)]
-pub struct SingleThreadedCallback<T>
+impl<T> Default for MultiThreadedCallback<T>
 where
-    T: Debug + Send + Sync,
+    T: Debug + Send + Sync + 'static,
 {
-    base: Arc<BaseCallback<T>>,
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-impl<T> SingleThreadedCallback<T>
-where
-    T: Debug + Send + Sync,
-{
-    /// Create a new single/current threaded callback holder.
-    pub fn new() -> Self {
-        Self {
-            base: Arc::new(BaseCallback::<T>::new()),
+/// Implements the methods which are shared between the callback holders, delegating them to the [BaseCallback] of the holder.
+///
+/// The `static` arm implements the methods which require the event type to be `'static`.
+macro_rules! shared_callback_methods {
+    () => {
+        /// Subscribe to the interested event, unless the maximum number of subscribers has been reached.
+        ///
+        /// # Returns
+        ///
+        /// It returns [CallbackError::TooManySubscribers] when the holder has no capacity left for the subscription.
+        pub fn try_subscribe(&self) -> Result<Subscription<T>, CallbackError> {
+            let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+            self.try_subscribe_with(tx).map(|_| rx)
         }
-    }
 
-    /// Invoke the currently registered callbacks and inform them of the given value.
-    ///
-    /// # Arguments
-    ///
-    /// * `value` - The value to invoke the callbacks with.
-    pub fn invoke(&self, value: T) {
-        self.base.invoke(value)
-    }
-}
+        /// Register the given subscriber, unless the maximum number of subscribers has been reached.
+        ///
+        /// # Returns
+        ///
+        /// It returns the handle of the subscriber, or [CallbackError::TooManySubscribers] when the holder has no capacity left.
+        /// When the subscriber is already registered, the result depends on the [DuplicatePolicy] of this holder.
+        pub fn try_subscribe_with(
+            &self,
+            subscriber: Subscriber<T>,
+        ) -> Result<CallbackHandle, CallbackError> {
+            self.base.try_subscribe_with(subscriber)
+        }
 
-impl<T> Callback<T> for SingleThreadedCallback<T>
-where
-    T: Debug + Send + Sync,
-{
-    fn subscribe(&self) -> Subscription<T> {
-        self.base.subscribe()
-    }
+        /// Subscribe to the interested event and retrieve the handle of the new subscription.
+        /// The handle can be used to manage the subscription, e.g. through [Self::pause_subscriber].
+        pub fn subscribe_handle(&self) -> (CallbackHandle, Subscription<T>) {
+            self.base.subscribe_handle()
+        }
 
-    fn subscribe_with(&self, subscriber: Subscriber<T>) {
-        self.base.subscribe_with(subscriber)
-    }
-}
+        /// Subscribe to the interested event with a [ManagedSubscription].
+        /// The managed subscription is removed from this callback holder as soon as it's dropped or closed.
+        pub fn subscribe_managed(&self) -> ManagedSubscription<T> {
+            let (handle, receiver) = self.base.subscribe_handle();
+            ManagedSubscription {
+                handle,
+                receiver,
+                holder: Arc::downgrade(&self.base),
+            }
+        }
 
-struct BaseCallback<T>
-where
-    T: Debug + Send + Sync,
-{
-    callbacks: Mutex<HashMap<CallbackHandle, UnboundedSender<Arc<T>>>>,
-}
+        /// Subscribe to the interested event together with a [SubscriptionGuard].
+        /// Dropping the guard immediately removes the subscriber from this callback holder,
+        /// releasing the events which are still queued once the subscription is dropped as well.
+        ///
+        /// Unlike a [ManagedSubscription], the guard is separated from the subscription,
+        /// so the guard can be kept by the owner of a scope while the subscription is consumed elsewhere.
+        pub fn subscribe_guarded(&self) -> (SubscriptionGuard<T>, Subscription<T>) {
+            let (handle, receiver) = self.base.subscribe_handle();
+            (
+                SubscriptionGuard {
+                    handle,
+                    holder: Arc::downgrade(&self.base),
+                },
+                receiver,
+            )
+        }
 
-impl<T> BaseCallback<T>
-where
-    T: Debug + Send + Sync,
-{
-    fn new() -> Self {
-        Self {
-            callbacks: Mutex::new(HashMap::new()),
+        /// Subscribe to the interested event with the given name.
+        /// The name identifies the subscription within the logs and the [SubscriberInfo], and doesn't have to be unique.
+        pub fn subscribe_named(&self, name: impl Into<String>) -> Subscription<T> {
+            let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+            self.base.subscribe_named(name.into(), tx);
+            rx
         }
-    }
 
-    fn subscribe(&self) -> Subscription<T> {
-        let mut mutex = self.callbacks.lock().expect("failed to acquire lock");
-        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
-        let handle = CallbackHandle::new();
-        mutex.insert(handle, tx);
-        drop(mutex);
-        trace!("Added callback {} to {:?}", handle, self);
-        rx
-    }
+        /// Register the given subscriber with the given name, see [Self::subscribe_named].
+        ///
+        /// # Returns
+        ///
+        /// It returns the handle of the subscriber.
+        pub fn subscribe_named_with(
+            &self,
+            name: impl Into<String>,
+            subscriber: Subscriber<T>,
+        ) -> CallbackHandle {
+            self.base.subscribe_named(name.into(), subscriber)
+        }
 
-    fn subscribe_with(&self, subscriber: Subscriber<T>) {
-        let mut mutex = self.callbacks.lock().expect("failed to acquire lock");
-        let handle = CallbackHandle::new();
-        mutex.insert(handle, subscriber);
-        drop(mutex);
-        trace!("Added callback {} to {:?}", handle, self);
-    }
+        /// Probe the liveness of the acked subscription with the given handle.
+        /// The probe is queued behind the pending events of the subscription,
+        /// so it's only answered once its consumer has received all events which have been invoked before the probe.
+        ///
+        /// The result is logged and retained within the [SubscriberInfo] of the subscriber.
+        ///
+        /// # Returns
+        ///
+        /// It returns the liveness of the subscription, or [None] when the subscriber isn't an [AckedSubscription].
+        pub async fn probe(
+            &self,
+            handle: CallbackHandle,
+            timeout: Duration,
+        ) -> Option<LivenessStatus> {
+            self.base.probe(&handle, timeout).await
+        }
 
-    fn invoke(&self, value: T) {
-        let mut mutex = self.callbacks.lock().expect("failed to acquire lock");
-        let value = Arc::new(value);
+        /// Subscribe the given number of subscriptions to the interested event under a single lock acquisition,
+        /// which reduces the lock churn when registering many subscribers at once.
+        /// The subscriptions are registered in the order of the returned vector, which is also the order in which they receive events.
+        pub fn subscribe_many(&self, count: usize) -> Vec<Subscription<T>> {
+            self.base.subscribe_many(count)
+        }
 
-        trace!(
-            "Invoking a total of {} callbacks for {:?}",
-            mutex.len(),
-            *value
-        );
+        /// Register all given subscribers under a single lock acquisition, e.g. to bridge an externally managed pool of senders into this holder.
+        /// The subscribers are registered in the given order, which is also the order in which they receive events.
+        ///
+        /// # Returns
+        ///
+        /// It returns the assigned handles in the same order as the given subscribers,
+        /// so the handle at each index belongs to the subscriber at the same index.
+        pub fn subscribe_into(&self, senders: Vec<Subscriber<T>>) -> Vec<CallbackHandle> {
+            self.base.subscribe_into(senders)
+        }
 
-        let handles_to_remove: Vec<CallbackHandle> = mutex
-            .iter()
-            .map(|(handle, callback)| {
-                BaseCallback::invoke_callback(handle, callback, value.clone())
-            })
-            .flat_map(|e| e)
-            .collect();
+        /// Subscribe to the variants of the interested event which match the given mask.
+        /// An event is only delivered to the subscription when the [VariantMask::variant_bit] of the event is part of the mask,
+        /// which is a cheap bitwise check compared to filtering the events through a predicate.
+        ///
+        /// As the mask is a `u64`, at most 64 distinct variants can be filtered.
+        pub fn subscribe_variant_mask(&self, mask: u64) -> Subscription<T>
+        where
+            T: VariantMask,
+        {
+            self.base.subscribe_variant_mask(mask)
+        }
 
-        let total_handles = handles_to_remove.len();
-        for handle in handles_to_remove {
-            mutex.remove(&handle);
+        /// Subscribe to the interested event for at most the given number of events.
+        /// The subscriber is removed from this holder right after the last event has been delivered,
+        /// so the subscription returns [None] once it has received all its events.
+        ///
+        /// The deliveries are counted while the subscribers are locked, so the limit is exact when invoking from multiple threads.
+        /// A limit of 0 results in a subscription which is closed immediately.
+        pub fn subscribe_limited(&self, limit: usize) -> Subscription<T> {
+            let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+            self.base.subscribe_with_limit(tx, limit);
+            rx
         }
 
-        if total_handles > 0 {
-            debug!("Removed a total of {} callbacks", total_handles);
+        /// Subscribe to the interested event with a [Subscriber] for at most the given number of events,
+        /// see [Self::subscribe_limited].
+        ///
+        /// # Returns
+        ///
+        /// It returns the handle of the subscriber, or [None] when the limit is 0,
+        /// in which case the subscriber is dropped without being registered.
+        pub fn subscribe_with_limited(
+            &self,
+            subscriber: Subscriber<T>,
+            limit: usize,
+        ) -> Option<CallbackHandle> {
+            self.base.subscribe_with_limit(subscriber, limit)
         }
-    }
 
-    /// Try to invoke the callback for the given value.
-    /// This is a convenience method for handling dropped callbacks.
-    ///
-    /// # Returns
-    ///
-    /// It returns the callback handle if the callback has been dropped.
-    fn invoke_callback(
-        handle: &CallbackHandle,
-        callback: &UnboundedSender<Arc<T>>,
-        value: Arc<T>,
-    ) -> Option<CallbackHandle> {
-        let start_time = Instant::now();
-        if let Err(_) = callback.send(value) {
-            trace!("Callback {} has been dropped", handle);
-            return Some(handle.clone());
+        /// Subscribe to the interested event with a closure.
+        /// The closure is invoked inline during the fan-out of each event, while the subscribers of this holder are locked.
+        /// It should therefore be cheap and must not call back into this callback holder.
+        ///
+        /// When the closure panics, the panic is caught and logged, and the closure is unsubscribed from this holder.
+        /// This isolates a faulty closure from the other subscribers, channel based subscribers are never affected by it.
+        ///
+        /// ## Unwind safety
+        ///
+        /// The closure requires [RefUnwindSafe] as it might be interrupted by a panic.
+        /// The event itself is shared with the other subscribers, so a closure that panics halfway through
+        /// mutating the event, e.g. through interior mutability, can leave the event in an inconsistent state for other subscribers.
+        ///
+        /// # Returns
+        ///
+        /// It returns the handle of the closure subscription.
+        pub fn subscribe_fn<F>(&self, closure: F) -> CallbackHandle
+        where
+            F: Fn(Arc<T>) + Send + Sync + RefUnwindSafe + 'static,
+        {
+            self.base.subscribe_fn(closure)
         }
-        let elapsed = start_time.elapsed();
-        let message = format!(
-            "Callback {} took {}.{:03}ms to process the invocation",
-            handle,
-            elapsed.as_millis(),
-            elapsed.subsec_micros() % 1000
-        );
-        if elapsed.as_millis() >= 1000 {
-            warn!("{}", message);
-        } else {
-            trace!("{}", message);
+
+        /// Subscribe to the interested event with a bounded queue of the given capacity.
+        /// When the queue of the subscription is full, new events are dropped for this subscription
+        /// and counted in [Self::dropped_count].
+        ///
+        /// # Panics
+        ///
+        /// It panics when the given capacity is 0.
+        pub fn subscribe_bounded(&self, capacity: usize) -> BoundedSubscription<T> {
+            self.base.subscribe_bounded(capacity)
         }
 
-        None
-    }
-}
+        /// Register the given bounded subscriber, see [Self::subscribe_bounded].
+        ///
+        /// # Returns
+        ///
+        /// It returns the handle of the subscriber.
+        pub fn subscribe_bounded_with(&self, subscriber: BoundedSubscriber<T>) -> CallbackHandle {
+            self.base.insert(CallbackSender::Bounded(subscriber))
+        }
 
-impl<T> Debug for BaseCallback<T>
-where
-    T: Debug + Send + Sync,
-{
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("BaseCallback")
-            .field("callbacks", &self.callbacks.lock().unwrap().len())
-            .finish()
-    }
-}
+        /// Get the total number of events which have been dropped because the queue of a bounded subscription
+        /// or the buffer of a paused subscriber was full.
+        /// This includes the events which have been evicted by a subscription which drops its oldest event when it's full,
+        /// so the count matches the sum of the dropped events of each subscriber.
+        ///
+        /// The counter is shared between all clones of this callback holder and covers all of its bounded subscriptions.
+        /// It is monotonic, meaning that it is never decreased or reset during the lifetime of the holder.
+        /// Subscriptions which are removed because they have been closed are not counted as dropped events.
+        pub fn dropped_count(&self) -> u64 {
+            self.base.dropped_count()
+        }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::init_logger;
-    use std::sync::mpsc::channel;
-    use std::time::Duration;
-    use tokio::{select, time};
+        /// Get the number of events which have been dropped for the subscriber with the given handle,
+        /// because its bounded queue or its pause buffer was full.
+        ///
+        /// # Returns
+        ///
+        /// It returns [None] when no subscriber is registered for the given handle.
+        pub fn dropped_count_of(&self, handle: &CallbackHandle) -> Option<u64> {
+            self.base.dropped_count_of(handle)
+        }
 
-    #[derive(Debug, Clone, PartialEq)]
-    pub enum Event {
-        Foo,
-    }
+        /// Pause the delivery of events to the subscriber with the given handle.
+        /// The subscription and its queue are kept intact while the subscriber is paused.
+        ///
+        /// Events which are invoked while the subscriber is paused are not buffered,
+        /// they are never delivered to the paused subscriber.
+        ///
+        /// # Returns
+        ///
+        /// It returns `true` when the subscriber has been found, else `false`.
+        pub fn pause_subscriber(&self, handle: &CallbackHandle) -> bool {
+            self.base.set_paused(handle, true)
+        }
 
-    #[derive(Debug, PartialEq)]
-    enum NoneCloneEvent {
-        Bar,
-    }
+        /// Resume the delivery of events to the paused subscriber with the given handle.
+        ///
+        /// # Returns
+        ///
+        /// It returns `true` when the subscriber has been found, else `false`.
+        pub fn resume_subscriber(&self, handle: &CallbackHandle) -> bool {
+            self.base.set_paused(handle, false)
+        }
 
-    #[tokio::test]
-    async fn test_multi_threaded_invoke() {
-        init_logger!();
-        let expected_result = Event::Foo;
-        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
-        let callback = MultiThreadedCallback::<Event>::new();
+        /// Subscribe to the interested event with an inactive subscription, which doesn't receive any events until it's activated.
+        /// This allows the consumer of the subscription to be fully set up before the delivery of events starts,
+        /// without receiving the events which have been invoked in the meantime.
+        ///
+        /// Use [Self::subscribe_inactive_buffered] to receive the events which have been invoked in the meantime instead.
+        pub fn subscribe_inactive(&self) -> (CallbackHandle, Subscription<T>) {
+            self.base.subscribe_inactive(false)
+        }
 
-        let mut receiver = callback.subscribe();
-        tokio::spawn(async move {
-            if let Some(e) = receiver.recv().await {
-                let _ = tx.send(e).await;
-            }
-        });
+        /// Subscribe to the interested event with an inactive subscription, which buffers the invoked events until it's activated.
+        /// The buffer is limited as described by [Self::pause].
+        pub fn subscribe_inactive_buffered(&self) -> (CallbackHandle, Subscription<T>) {
+            self.base.subscribe_inactive(true)
+        }
 
-        callback.invoke(expected_result.clone());
-        let result = select! {
-            _ = time::sleep(Duration::from_millis(150)) => {
-                panic!("Callback invocation receiver timed out")
-            },
-            Some(result) = rx.recv() => result,
-        };
+        /// Activate the inactive subscription with the given handle, starting the delivery of events to it.
+        /// The buffered events of the subscription, if any, are delivered in order before any event which is invoked afterwards.
+        ///
+        /// The activation is atomic with regard to concurrent invocations, so each event is either delivered or skipped as a whole.
+        ///
+        /// # Returns
+        ///
+        /// It returns `true` when the subscriber has been found, else `false`.
+        pub fn activate(&self, handle: &CallbackHandle) -> bool {
+            self.base.activate(handle)
+        }
 
-        assert_eq!(expected_result, *result);
-    }
+        /// Pause the delivery of events to the subscriber with the given handle, while buffering the events which are invoked in the meantime.
+        /// The buffered events are delivered in order when the subscriber is resumed through [Self::resume].
+        ///
+        /// Unlike [Self::pause_subscriber], the subscriber doesn't miss any events as long as the buffer isn't full.
+        /// The buffer holds at most [Self::set_pause_buffer_capacity] events, which defaults to 1024,
+        /// after which the oldest buffered events are evicted and counted in [Self::dropped_count].
+        /// Dropping the subscription of a paused subscriber discards its buffer and removes it on the next invocation.
+        ///
+        /// # Returns
+        ///
+        /// It returns `true` when the subscriber has been found, else `false`.
+        pub fn pause(&self, handle: &CallbackHandle) -> bool {
+            self.base.pause(handle)
+        }
 
-    #[test]
-    fn test_multi_threaded_invoke_without_runtime() {
-        init_logger!();
-        let expected_result = Event::Foo;
-        let (tx, rx) = channel();
-        let runtime = Runtime::new().unwrap();
-        let callback = MultiThreadedCallback::<Event>::new();
+        /// Resume the subscriber with the given handle which has been paused through [Self::pause],
+        /// flushing its buffered events in order before any event which is invoked afterwards.
+        ///
+        /// # Returns
+        ///
+        /// It returns `true` when the subscriber has been found, else `false`.
+        pub fn resume(&self, handle: &CallbackHandle) -> bool {
+            self.base.resume(handle)
+        }
 
-        let mut receiver = callback.subscribe();
-        runtime.spawn(async move {
-            if let Some(e) = receiver.recv().await {
-                tx.send(e).unwrap();
-            }
-        });
+        /// Set the maximum number of events which are buffered for a subscriber paused through [Self::pause].
+        /// The capacity applies to the subscribers which are paused after it has been set, a capacity of 0 is treated as 1.
+        pub fn set_pause_buffer_capacity(&self, capacity: usize) {
+            self.base
+                .pause_buffer_capacity
+                .store(capacity, Ordering::Relaxed);
+        }
 
-        callback.invoke(expected_result.clone());
-        let result = rx.recv_timeout(Duration::from_millis(50)).unwrap();
+        /// Remove all subscribers of which the subscription has been dropped, or which have expired,
+        /// without waiting for the next invocation to detect them.
+        /// This prevents the subscribers of a rarely invoked holder from growing without bound.
+        ///
+        /// Closure subscribers are never pruned, as they can't be closed.
+        ///
+        /// # Returns
+        ///
+        /// It returns the number of subscribers which have been removed.
+        pub fn prune(&self) -> usize {
+            self.base.prune()
+        }
+
+        /// Replace the subscriber of the given handle with the new subscriber, while keeping the handle and its configuration.
+        ///
+        /// The subscriber is swapped atomically, so each event is delivered to either the previous or the new subscriber, never to neither.
+        /// Events which have already been delivered to the previous subscriber remain queued within its subscription.
+        ///
+        /// # Returns
+        ///
+        /// It returns [CallbackError::UnknownHandle] when no subscriber is registered for the handle, without registering the new subscriber.
+        pub fn replace_subscriber(
+            &self,
+            handle: CallbackHandle,
+            new: Subscriber<T>,
+        ) -> Result<(), CallbackError> {
+            self.base.replace(&handle, CallbackSender::Unbounded(new))
+        }
+
+        /// Set the policy for subscribers which are registered while their channel is already registered with this holder.
+        /// The policy only applies to new registrations, already registered duplicates aren't removed.
+        ///
+        /// With [DuplicatePolicy::ErrorOnDuplicate], the infallible [Callback::subscribe_with] rejects the duplicate with a warning,
+        /// while [Self::try_subscribe_with] returns the error.
+        pub fn set_duplicate_policy(&self, policy: DuplicatePolicy) {
+            self.base.set_duplicate_policy(policy);
+        }
+
+        /// Set the listener which is informed each time a subscriber is added to or removed from this holder.
+        /// The listener replaces any previously set listener, and isn't informed about the current subscribers.
+        pub fn set_listener(&self, listener: Box<dyn SubscriptionListener>) {
+            self.base.set_listener(Some(Arc::from(listener)));
+        }
+
+        /// Remove the current subscription listener of this holder, if any.
+        pub fn clear_listener(&self) {
+            self.base.set_listener(None);
+        }
+
+        /// Check if this holder has any subscriber of which the subscription is still open.
+        /// Unlike [Callback::subscriber_count], subscriptions which have been dropped but not yet removed aren't included.
+        pub fn has_subscribers(&self) -> bool {
+            self.base.has_subscribers()
+        }
+
+        /// Wait until this holder has no subscriber left of which the subscription is still open,
+        /// e.g. to stop an expensive producer when nobody is interested in its events anymore.
+        ///
+        /// The future completes immediately when there are no subscribers.
+        /// Subscriptions which are dropped without being removed are detected within a short interval.
+        pub async fn closed(&self) {
+            self.base.closed().await
+        }
+
+        /// Remove all subscribers which have been tagged with the given tag key and value, e.g. when offboarding a tenant.
+        /// The tag value must exactly match the tag of the subscriber for the given key.
+        ///
+        /// The matching subscribers are removed at once under a single lock, closing their receivers.
+        /// Untagged and durable subscribers are never removed.
+        ///
+        /// # Returns
+        ///
+        /// It returns the number of subscribers which have been removed.
+        pub fn unsubscribe_by_tag(&self, key: &str, tag: &str) -> usize {
+            self.base.unsubscribe_by_tag(key, tag)
+        }
+
+        /// Stop buffering the events of this callback holder and drop all buffered events without delivering them.
+        ///
+        /// # Returns
+        ///
+        /// It returns the number of buffered events which have been dropped.
+        pub fn discard_buffer(&self) -> usize {
+            let total = self.base.take_buffer().len();
+            debug!("Discarded a total of {} buffered events", total);
+            total
+        }
+
+        /// Emit a final event to the subscribers when this callback holder is dropped.
+        /// The event is created by the given function when the last clone of this holder has been dropped.
+        ///
+        /// The final event is always invoked inline on the thread that drops the holder, it's never spawned on a runtime,
+        /// so it's safe to use during a shutdown of the runtime.
+        /// Calling this method again replaces the previously configured final event.
+        pub fn on_drop_emit<F>(&self, value_fn: F)
+        where
+            F: FnOnce() -> T + Send + 'static,
+        {
+            *self.base.drop_event.lock().expect("failed to acquire lock") =
+                Some(Box::new(value_fn));
+        }
+
+        /// Set the tap of this callback holder, which is called once for each invoked event before it's delivered to the subscribers.
+        /// This replaces any previously set tap.
+        ///
+        /// The tap is a lightweight hook to observe the events, e.g. to log or meter them,
+        /// without registering a subscriber, so it isn't included in the subscribers of this holder.
+        /// It runs inline within the invocation, so it should be cheap and must not invoke this holder again.
+        pub fn set_tap<F>(&self, tap: F)
+        where
+            F: Fn(&Arc<T>) + Send + Sync + 'static,
+        {
+            *self.base.tap.lock().expect("failed to acquire lock") = Some(Arc::new(tap));
+        }
+
+        /// Remove the tap of this callback holder, if any.
+        pub fn clear_tap(&self) {
+            *self.base.tap.lock().expect("failed to acquire lock") = None;
+        }
+
+        /// Describe all subscribers of this callback holder, e.g. for an administrative overview.
+        ///
+        /// The information of all subscribers is gathered at once while the subscribers are locked,
+        /// resulting in a consistent point-in-time view which might be outdated as soon as it's returned.
+        /// Durable subscribers are not included.
+        pub fn describe_subscribers(&self) -> Vec<SubscriberInfo> {
+            self.base.describe_subscribers()
+        }
+
+        /// Get the handles of all subscribers which are currently registered, in the order in which they receive events.
+        /// Durable subscribers are not included.
+        pub fn handles(&self) -> Vec<CallbackHandle> {
+            self.base.handles()
+        }
+
+        /// Get the information of the subscriber with the given handle.
+        ///
+        /// # Returns
+        ///
+        /// It returns [None] when the handle is unknown or the subscriber has already been removed.
+        pub fn subscriber_info(&self, handle: &CallbackHandle) -> Option<SubscriberInfo> {
+            self.base.subscriber_info(handle)
+        }
+
+        /// Get the accumulated fan-out statistics of this callback holder.
+        ///
+        /// The statistics cover the whole lifetime of the holder and are shared between all its clones.
+        /// They're always recorded, which costs a few atomic operations per invocation and per delivered event.
+        /// The latencies are an estimate based on the measured time of sending an event to a subscriber.
+        pub fn stats(&self) -> CallbackStats {
+            self.base.stats.snapshot()
+        }
+
+        /// Set the number of invocations which are kept in the invoke trace of this holder, which is disabled by default.
+        /// A capacity of 0 disables the trace and discards the invocations which have been recorded so far.
+        ///
+        /// Each record keeps its event alive together with the handles of the notified subscribers,
+        /// so the memory used by the trace is bounded by the capacity, but the recorded events are only released once they're evicted.
+        /// Once the capacity has been reached, the oldest record is evicted for each new invocation.
+        pub fn set_invoke_trace_capacity(&self, capacity: usize) {
+            self.base.set_invoke_trace_capacity(capacity)
+        }
+
+        /// Get the recent invocations of this holder from the invoke trace, ordered from oldest to newest.
+        /// This allows reconstructing which subscribers received which events at what moment, e.g. when reproducing an event ordering issue.
+        ///
+        /// It returns an empty list when the trace is disabled, see [Self::set_invoke_trace_capacity].
+        /// Requests and the events of durable subscribers are not recorded.
+        pub fn recent_invokes(&self) -> Vec<InvokeRecord<T>> {
+            self.base.recent_invokes()
+        }
+
+        /// Set the minimum interval between two slow callback warnings of the same subscriber, which defaults to 30 seconds.
+        ///
+        /// A delivery which takes a second or longer is logged as a warning at most once per interval for each subscriber,
+        /// the other slow deliveries within the interval are logged at trace level.
+        /// The delivery time is always measured, regardless of this interval.
+        pub fn set_slow_warning_interval(&self, interval: Duration) {
+            self.base.slow_warning_interval.store(
+                u64::try_from(interval.as_nanos()).unwrap_or(u64::MAX),
+                Ordering::Relaxed,
+            );
+        }
+
+        /// Get a future which resolves when this callback holder is dropped,
+        /// allowing a consumer to distinguish the producer going away from its own unsubscription.
+        ///
+        /// The holder is dropped when its last clone has been dropped, after the [Self::on_drop_emit] event has been delivered.
+        /// The future resolves immediately when the holder has already been dropped by the time it's polled.
+        pub fn on_holder_dropped(&self) -> impl Future<Output = ()> + Send + 'static {
+            self.base.on_holder_dropped()
+        }
+
+        /// Subscribe to the delivery errors of this callback holder.
+        /// A [DeliveryError] is published each time an event couldn't be delivered to one of the subscribers,
+        /// e.g. because the subscription has been dropped or the queue of a bounded subscription is full.
+        ///
+        /// Events which have been successfully delivered to unbounded subscriptions never result in a delivery error.
+        pub fn error_subscription(&self) -> Subscription<DeliveryError<T>> {
+            self.base.error_subscription()
+        }
+
+        /// Subscribe to the interested event with at-least-once delivery semantics for the given key.
+        ///
+        /// Every event is retained for the key until it has been acknowledged through [crate::AckedEvent::ack].
+        /// When a new durable subscription is created with the same key, e.g. after the consumer has been restarted,
+        /// all retained unacknowledged events are redelivered in order before any new event.
+        /// Any previous subscription of the key is replaced by the new subscription.
+        ///
+        /// ## Remarks
+        ///
+        /// The retained events of a key keep growing while events are not acknowledged,
+        /// even when no subscription of the key is currently connected.
+        /// Use [Self::remove_durable] to release the retained events of a key which is no longer used.
+        ///
+        /// A redelivered event might have already been (partially) processed by a previous subscription,
+        /// the consumer is responsible for deduplicating events based on [crate::AckedEvent::sequence].
+        pub fn subscribe_durable<S: Into<String>>(&self, key: S) -> DurableSubscription<T> {
+            self.base.durable.subscribe(key.into())
+        }
+
+        /// Remove the durable subscriber with the given key and release all its retained unacknowledged events.
+        ///
+        /// # Returns
+        ///
+        /// It returns `true` when a durable subscriber was registered for the key, else `false`.
+        pub fn remove_durable(&self, key: &str) -> bool {
+            self.base.durable.remove(key)
+        }
+
+        /// Get the total number of retained unacknowledged events of the durable subscriber with the given key.
+        pub fn durable_pending(&self, key: &str) -> usize {
+            self.base.durable.unacked_len(key)
+        }
+
+        /// Set the policy for the values which couldn't be delivered to any subscriber by [Self::invoke_require_subscribers].
+        pub fn set_no_subscribers_policy(&self, policy: NoSubscribersPolicy) {
+            self.base.set_no_subscribers_policy(policy);
+        }
+
+        /// Enable or disable the invocations of this callback holder.
+        ///
+        /// While disabled, each invocation is discarded without being delivered to any subscriber.
+        /// The discarded events are lost, they're not delivered when the holder is enabled again.
+        pub fn set_enabled(&self, enabled: bool) {
+            self.base.set_enabled(enabled);
+        }
+
+        /// Check if the invocations of this callback holder are enabled, see [Self::set_enabled].
+        pub fn is_enabled(&self) -> bool {
+            self.base.is_enabled()
+        }
+
+        /// Invoke the currently registered callbacks with the value built by the given fallible producer.
+        ///
+        /// The producer is called on the caller thread. When it succeeds, the value is invoked as with [Self::invoke],
+        /// while on failure its error is returned and no event is delivered to any subscriber.
+        ///
+        /// # Returns
+        ///
+        /// It returns the error of the producer when the value couldn't be built.
+        pub fn invoke_try<F, E>(&self, producer: F) -> Result<(), E>
+        where
+            F: FnOnce() -> Result<T, E>,
+        {
+            let value = producer()?;
+            self.invoke(value);
+            Ok(())
+        }
+    };
+    (static) => {
+        /// Subscribe to the interested event with an [AckedSubscription], of which the liveness can be probed.
+        /// See [Self::probe] for more info.
+        pub fn subscribe_acked(&self) -> AckedSubscription<T> {
+            self.base.subscribe_acked()
+        }
+
+        /// Subscribe to the next event only.
+        /// The subscriber is registered immediately and removed from this holder right after the event has been delivered,
+        /// so no closed subscriber is left behind once the event has been received.
+        ///
+        /// # Returns
+        ///
+        /// It returns a future which resolves with the next event,
+        /// or [None] when this holder is dropped or its subscribers are cleared before any event has been invoked.
+        pub fn subscribe_once(&self) -> impl Future<Output = Option<Arc<T>>> {
+            let receiver = self.base.subscribe_once();
+            async move { receiver.await.ok() }
+        }
+
+        /// Subscribe to the interested event with a function which transforms each event into zero or more events of another type.
+        /// Each produced value is delivered as a separate event to the returned subscription,
+        /// which allows splitting a batch event into individual events, or suppressing events through an empty iterator.
+        ///
+        /// The function is applied inline during the fan-out of each event, while the subscribers of this holder are locked,
+        /// so the produced events follow the order of the invoked events, and within a single event the order of the iterator.
+        /// When the function panics, the subscription is removed from this holder.
+        pub fn subscribe_flat_map<U, I, F>(&self, function: F) -> Subscription<U>
+        where
+            U: Send + Sync + 'static,
+            I: IntoIterator<Item = U> + 'static,
+            F: Fn(Arc<T>) -> I + Send + Sync + RefUnwindSafe + 'static,
+        {
+            self.base.subscribe_flat_map(function)
+        }
+
+        /// Subscribe to the interested event with a closure which is isolated from the fan-out through a deadline.
+        ///
+        /// When invoked within a tokio runtime, the closure is run on the blocking thread pool through [tokio::task::spawn_blocking],
+        /// so a slow or blocking closure doesn't delay the delivery to the other subscribers.
+        /// A closure which doesn't complete within the given timeout is logged as an overrun.
+        /// Outside a runtime, the closure is invoked inline and an overrun is only logged after the closure has completed.
+        ///
+        /// ## Limitations
+        ///
+        /// Synchronous code can't be interrupted, so a closure which overruns its timeout keeps running until it completes.
+        /// As each event is handled by its own blocking task, the closure might be invoked concurrently and out of order.
+        /// A panic of the closure on the blocking thread pool is logged, but doesn't unsubscribe the closure.
+        ///
+        /// # Returns
+        ///
+        /// It returns the handle of the closure subscription.
+        pub fn subscribe_fn_with_timeout<F>(&self, closure: F, timeout: Duration) -> CallbackHandle
+        where
+            F: Fn(Arc<T>) + Send + Sync + RefUnwindSafe + 'static,
+        {
+            self.base.subscribe_fn_with_timeout(closure, timeout)
+        }
+
+        /// Subscribe to the interested event with owned values instead of shared [Arc] values.
+        /// This allows consumers which require an owned event to coexist with the shared subscriptions of this holder.
+        ///
+        /// The event is still shared between all other subscriptions, but it's cloned once per delivery to each owned subscription.
+        /// Prefer [Callback::subscribe] for events which are expensive to clone.
+        pub fn subscribe_owned(&self) -> OwnedSubscription<T>
+        where
+            T: Clone,
+        {
+            self.base.subscribe_owned()
+        }
+
+        /// Subscribe to the interested event with a bounded queue of the given capacity,
+        /// which applies the given [OverflowPolicy] when an event is invoked while its queue is full.
+        ///
+        /// The events which are dropped by the policy are counted per subscriber in [Self::dropped_count_of].
+        ///
+        /// # Panics
+        ///
+        /// It panics when the given capacity is 0.
+        pub fn subscribe_overflow(
+            &self,
+            capacity: usize,
+            policy: OverflowPolicy,
+        ) -> OverflowSubscription<T> {
+            self.base.subscribe_overflow(capacity, policy)
+        }
+
+        /// Subscribe to the interested event with an adaptive queue, which is unbounded until its depth exceeds the given high watermark.
+        /// Once the watermark is exceeded, the oldest queued events are dropped for this subscription, capping its queue at the watermark.
+        ///
+        /// This trades the completeness of the received events for memory safety when the consumer is stuck or pathologically slow.
+        /// The transition to and from the capped state is logged, and the dropped events are counted in [AdaptiveSubscription::dropped_count]
+        /// instead of [Self::dropped_count]. The queue depth is reported through [Self::describe_subscribers].
+        ///
+        /// # Panics
+        ///
+        /// It panics when the given high watermark is 0.
+        pub fn subscribe_adaptive(&self, high_watermark: usize) -> AdaptiveSubscription<T> {
+            self.base.subscribe_adaptive(high_watermark)
+        }
+
+        /// Subscribe to the interested event with a conflating [LatestSubscription], which only holds the newest event.
+        /// A newer event overwrites the event which hasn't been received yet, so the consumer always receives the freshest event.
+        ///
+        /// The overwritten events are counted in [LatestSubscription::overwritten] instead of [Self::dropped_count].
+        pub fn subscribe_latest(&self) -> LatestSubscription<T> {
+            self.base.subscribe_latest()
+        }
+
+        /// Start capturing all events of this holder for the given window, e.g. for live troubleshooting,
+        /// without disturbing the other subscribers.
+        ///
+        /// The capture registers a regular subscriber, which is automatically removed on the first invocation after the window has elapsed.
+        /// The events are captured in the order in which they've been delivered, and can be retrieved through [CaptureHandle::collect].
+        ///
+        /// The captured events are shared with the other subscribers and aren't copied,
+        /// but the capture keeps all events which have been invoked within the window alive until they're collected or the handle is dropped.
+        /// Keep the window short for holders with a high event rate.
+        pub fn start_capture(&self, window: Duration) -> CaptureHandle<T> {
+            self.base.start_capture(window)
+        }
+    };
+}
+
+impl<T> MultiThreadedCallback<T>
+where
+    T: Debug + Send + Sync + 'static,
+{
+    /// Creates a new multithreaded callback.
+    ///
+    /// The invocations are queued on a single ordered fan-out task, which processes them one by one,
+    /// so the events invoked from the same thread are delivered to each subscriber in the order in which they've been invoked,
+    /// and the priority order of [MultiThreadedCallback::subscribe_with_priority] is observed by the subscribers as well.
+    /// Use [MultiThreadedCallbackBuilder::unordered_delivery] to spawn each invocation as an independent task instead.
+    pub fn new() -> Self {
+        Self::with_options(true, None, false)
+    }
+
+    /// Creates a new multithreaded callback which delivers all invocations through a single ordered fan-out task.
+    ///
+    /// The invocations are delivered in order by default, so this is equivalent to [MultiThreadedCallback::new].
+    #[deprecated(note = "ordered delivery is the default, use MultiThreadedCallback::new")]
+    pub fn with_ordered_delivery() -> Self {
+        Self::with_options(true, None, false)
+    }
+
+    /// Creates a new multithreaded callback which accepts at most the given number of subscribers.
+    ///
+    /// Once the limit has been reached, [MultiThreadedCallback::try_subscribe] and [MultiThreadedCallback::try_subscribe_with]
+    /// return [CallbackError::TooManySubscribers] instead of registering the subscriber.
+    /// The infallible [Callback::subscribe] and [Callback::subscribe_with] reject the subscriber with a warning,
+    /// which closes the returned subscription immediately.
+    ///
+    /// Removed subscribers, either unsubscribed or dropped, free their capacity again.
+    pub fn with_max_subscribers(limit: usize) -> Self {
+        Self::with_options(true, Some(limit), false)
+    }
+
+    /// Creates a new multithreaded callback with at most one subscriber at a time.
+    ///
+    /// Each new subscriber replaces the previously registered subscriber, which closes the previous subscription,
+    /// so its consumer receives the remaining queued events after which [Subscription::recv] returns [None].
+    /// Concurrent subscribers are serialized, leaving exactly one of them registered.
+    pub fn new_exclusive() -> Self {
+        Self::with_options(true, None, true)
+    }
+
+    /// Creates a new multithreaded callback for exactly one consumer.
+    ///
+    /// The consumer which is registered through [Callback::subscribe] or [Callback::subscribe_with]
+    /// is kept as a single sender instead of within the map of subscribers,
+    /// so each invocation is delivered to it without the overhead of the map and its pruning.
+    ///
+    /// The mode has the following constraints:
+    /// * At most one subscriber is accepted, as with [MultiThreadedCallback::with_max_subscribers] with a limit of 1.
+    ///   A second subscriber is rejected with [CallbackError::TooManySubscribers] through [MultiThreadedCallback::try_subscribe],
+    ///   while the infallible [Callback::subscribe] closes its subscription immediately.
+    /// * Any other kind of subscription, e.g. a bounded or named subscription, is registered as a regular subscriber.
+    /// * Inspecting or managing the consumer individually, e.g. through [MultiThreadedCallback::pause_subscriber]
+    ///   or [MultiThreadedCallback::describe_subscribers], moves it into the map of subscribers.
+    ///   It's kept there until it has been removed, after which a new consumer is kept as a single sender again.
+    pub fn single_consumer() -> Self {
+        Self::with_base(BaseCallback::single_consumer(), true)
+    }
+
+    pub(crate) fn with_options(
+        ordered: bool,
+        max_subscribers: Option<usize>,
+        exclusive: bool,
+    ) -> Self {
+        Self::with_base(
+            BaseCallback::<T>::with_options(max_subscribers, exclusive),
+            ordered,
+        )
+    }
+
+    fn with_base(base: BaseCallback<T>, ordered: bool) -> Self {
+        Self {
+            base: Arc::new(base),
+            runtime: Arc::new(Mutex::new(None)),
+            ordered: ordered.then(|| Arc::new(OrderedDispatcher::new())),
+        }
+    }
+
+    /// Create a new builder for a callback holder, which allows subscribers to be registered before the holder is used.
+    pub fn builder() -> MultiThreadedCallbackBuilder<T> {
+        MultiThreadedCallbackBuilder::new()
+    }
+
+    shared_callback_methods!();
+    shared_callback_methods!(static);
+
+    /// Subscribe to the interested event with the given metadata tags.
+    /// The tags can be used to route events to specific subscribers through [MultiThreadedCallback::invoke_where_tag].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use fx_callback::MultiThreadedCallback;
+    ///
+    /// #[derive(Debug)]
+    /// pub enum MyEvent {
+    ///     Foo,
+    /// }
+    ///
+    /// let callback = MultiThreadedCallback::<MyEvent>::new();
+    /// let mut receiver = callback.subscribe_tagged([("region", "eu"), ("role", "audit")]);
+    ///
+    /// callback.invoke_where_tag("region", "eu", MyEvent::Foo);
+    /// ```
+    pub fn subscribe_tagged<I, K, V>(&self, tags: I) -> Subscription<T>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.base.subscribe_tagged(
+            tags.into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        )
+    }
+
+    /// Subscribe to the interested event for the given duration, after which the subscription is closed.
+    ///
+    /// The subscriber is removed by a timer on the runtime once the duration has elapsed, so the consumer's receive loop ends cleanly.
+    /// Invocations which are fanned out after the duration has elapsed are never delivered to the subscriber,
+    /// even when the timer hasn't removed it yet. The timer doesn't keep this holder alive.
+    pub fn subscribe_for(&self, duration: Duration) -> Subscription<T> {
+        let (handle, receiver) = self.base.subscribe_for(duration);
+        let holder = Arc::downgrade(&self.base);
+        let _ = self.spawn(async move {
+            tokio::time::sleep(duration).await;
+            if let Some(holder) = holder.upgrade() {
+                if holder.remove(&handle) {
+                    trace!("Callback {} has expired", handle);
+                }
+            }
+        });
+        receiver
+    }
+
+    /// Subscribe to the interested event until the given future completes.
+    /// The subscription is closed as soon as the future completes, after which the remaining queued events can still be received.
+    ///
+    /// The future is awaited on the runtime, and is dropped without being completed
+    /// when the subscription or this holder is dropped before the future completes.
+    pub fn subscribe_until<F>(&self, future: F) -> Subscription<T>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let subscriber = tx.clone();
+        let handle = self.base.subscribe_with(tx);
+        let holder = Arc::downgrade(&self.base);
+        let holder_dropped = self.base.on_holder_dropped();
+        let _ = self.spawn(async move {
+            let mut future = pin!(future);
+            let mut closed = pin!(subscriber.closed());
+            let mut holder_dropped = pin!(holder_dropped);
+            let completed = poll_fn(|cx| {
+                if future.as_mut().poll(cx).is_ready() {
+                    return Poll::Ready(true);
+                }
+                if closed.as_mut().poll(cx).is_ready()
+                    || holder_dropped.as_mut().poll(cx).is_ready()
+                {
+                    return Poll::Ready(false);
+                }
+                Poll::Pending
+            })
+            .await;
+
+            if let Some(holder) = holder.upgrade() {
+                if holder.remove(&handle) && completed {
+                    trace!("Callback {} has been completed", handle);
+                }
+            }
+        });
+        rx
+    }
+
+    /// Subscribe to the interested event while the given gate is open, allowing the consumer to toggle its delivery without the holder.
+    /// The events which are invoked while the gate is `false` are dropped for this subscription, they aren't delivered once the gate reopens.
+    ///
+    /// Unlike [MultiThreadedCallback::pause_subscriber], which is controlled through the holder,
+    /// the gate is owned by the consumer and can be shared between multiple subscriptions, e.g. as a feature flag.
+    ///
+    /// The gate is checked when the invocation is fanned out by the runtime, not when [MultiThreadedCallback::invoke] is called.
+    pub fn subscribe_gated(&self, gate: Arc<AtomicBool>) -> Subscription<T> {
+        self.base.subscribe_gated(gate)
+    }
+
+    /// Subscribe to the interested event with the given delivery priority.
+    /// On each invocation, the event is delivered to subscribers with a higher priority before subscribers with a lower priority.
+    /// Subscribers without an explicit priority have a priority of 0,
+    /// and subscribers with the same priority receive the event in the order in which they've subscribed.
+    ///
+    /// The priority only orders the deliveries within a single invocation.
+    /// When the holder has been built with [MultiThreadedCallbackBuilder::unordered_delivery],
+    /// the scheduler might run a later invocation before an earlier one, undoing the priority order as observed by a subscriber.
+    pub fn subscribe_with_priority(&self, priority: i32) -> Subscription<T> {
+        self.base.subscribe_with_priority(priority)
+    }
+
+    /// Periodically [MultiThreadedCallback::prune] the closed subscribers of this holder with the given interval on the runtime.
+    /// The pruning task doesn't keep this holder alive and stops when the holder is dropped, or when the returned handle is dropped.
+    ///
+    /// # Panics
+    ///
+    /// It panics when the given interval is zero.
+    pub fn prune_interval(&self, interval: Duration) -> TaskHandle {
+        assert!(
+            !interval.is_zero(),
+            "prune interval must be greater than zero"
+        );
+        let holder = Arc::downgrade(&self.base);
+        TaskHandle::new(self.spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                match holder.upgrade() {
+                    Some(holder) => holder.prune(),
+                    None => break,
+                };
+            }
+            trace!("Callback pruning task has stopped");
+        }))
+    }
+
+    /// Remove all subscribers from this callback holder, closing their subscriptions.
+    /// Each receiver returns [None] once it has received the events which were already queued,
+    /// unless other senders of the subscription are still alive.
+    ///
+    /// The subscribers are removed at once under a single lock, so events which are invoked after the clear
+    /// only reach the subscribers which have been added after the clear. Durable subscribers are not removed.
+    ///
+    /// Invocations which have been made before the clear, but haven't been fanned out by the runtime yet,
+    /// aren't delivered to the removed subscribers either.
+    ///
+    /// # Returns
+    ///
+    /// It returns the number of subscribers which have been removed.
+    pub fn clear(&self) -> usize {
+        self.base.clear()
+    }
+
+    /// Start buffering the invoked events of this callback holder instead of delivering them to the subscribers.
+    /// The buffered events are delivered on [MultiThreadedCallback::flush_buffer], or dropped on [MultiThreadedCallback::discard_buffer],
+    /// which gives transactional control over the event delivery, e.g. during a speculative operation.
+    ///
+    /// Only the events which are broadcast to all subscribers are buffered,
+    /// tag routed invocations through [MultiThreadedCallback::invoke_where_tag] are always delivered immediately.
+    /// Calling this method while the holder is already buffering has no effect.
+    /// Buffered events which haven't been flushed are discarded when the holder is dropped.
+    ///
+    /// As the invocations of this holder are processed asynchronously,
+    /// an event which has been invoked before this call might still be buffered when it hasn't been processed yet.
+    pub fn begin_buffering(&self) {
+        self.base.begin_buffering()
+    }
+
+    /// Stop buffering the events of this callback holder and deliver all buffered events to the subscribers,
+    /// in the order in which they've been invoked.
+    ///
+    /// # Returns
+    ///
+    /// It returns the number of buffered events which will be delivered.
+    pub fn flush_buffer(&self) -> usize {
+        let values = self.base.take_buffer();
+        let total = values.len();
+        match self.ordered.as_ref() {
+            // each buffered value is a separate invocation, so the ordered fan-out task can yield in between them
+            Some(ordered) => self.dispatch_ordered(
+                ordered,
+                values
+                    .into_iter()
+                    .map(|value| {
+                        Box::new(move |base: &BaseCallback<T>| {
+                            base.fan_out(value, true, None);
+                        }) as Invocation<T>
+                    })
+                    .collect(),
+            ),
+            None => self.dispatch(move |base| {
+                for value in values {
+                    base.fan_out(value, true, None);
+                }
+            }),
+        }
+        total
+    }
+
+    /// Enable or disable the tracing of the [Arc::strong_count] of each event after its fan-out has completed, which is disabled by default.
+    /// The count is logged at trace level and excludes the reference of the fan-out itself,
+    /// which helps to diagnose subscribers that keep holding on to the events longer than expected.
+    ///
+    /// The count is less meaningful for this holder, as it also includes the events which are still queued
+    /// within the subscriptions, next to the references of invocations which are still in-flight.
+    pub fn set_strong_count_tracing(&self, enabled: bool) {
+        self.base
+            .trace_strong_count
+            .store(enabled, Ordering::Relaxed);
+    }
+
+    /// Invoke the currently registered callbacks and inform them of the given value.
+    ///
+    /// The callbacks are invoked on the current tokio runtime when available,
+    /// otherwise an internal runtime is created to invoke the callbacks on.
+    ///
+    /// ## Drop
+    ///
+    /// This method is safe to be called from within a `Drop` implementation, e.g. to emit a final "closing" event.
+    /// When no runtime is available and the internal runtime can't be created,
+    /// which might be the case during a shutdown of the application,
+    /// the callbacks are invoked inline on the caller thread instead of panicking.
+    ///
+    /// Keep in mind that the internal runtime is shut down when the last clone of this callback holder is dropped,
+    /// which might cancel an invocation that has not yet been processed.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to invoke the callbacks with.
+    pub fn invoke(&self, value: T) {
+        self.dispatch(move |base| {
+            base.invoke(value);
+        })
+    }
+
+    /// Invoke the currently registered callbacks and inform them of the given value, as with [MultiThreadedCallback::invoke].
+    /// See [MultiThreadedCallback::invoke_dispatched] for more info.
+    ///
+    /// # Returns
+    ///
+    /// It returns a future which resolves to the number of subscribers the value has been delivered to once the invocation has been processed.
+    /// Subscribers which have been dropped aren't counted, and it resolves to 0 when the holder is disabled or buffering its invocations.
+    pub fn invoke_counted(&self, value: T) -> impl Future<Output = usize> {
+        self.invoke_dispatched(value)
+    }
+
+    /// Invoke the currently registered callbacks and inform them of the given value,
+    /// returning a future which resolves once the fan-out of the value to all subscribers has completed.
+    ///
+    /// Unlike [MultiThreadedCallback::invoke], this allows the caller to wait until the value has been pushed
+    /// into the channels of all subscribers, e.g. before asserting the received events or shutting down.
+    /// The invocation is dispatched when this method is called, on the current tokio runtime or on the internal runtime,
+    /// so it keeps its order with the other invocations of this holder, even when the returned future is never awaited.
+    ///
+    /// # Returns
+    ///
+    /// It returns a future which resolves to the number of subscribers the value has been delivered to.
+    /// Subscribers which have been dropped aren't counted, and it resolves to 0 when the holder is disabled or buffering its invocations.
+    pub fn invoke_dispatched(&self, value: T) -> impl Future<Output = usize> {
+        let (tx, rx) = oneshot::channel();
+        self.dispatch(move |base| {
+            let _ = tx.send(base.invoke(value));
+        });
+
+        async move { rx.await.unwrap_or(0) }
+    }
+
+    /// Invoke the currently registered callbacks and inform them of the given value,
+    /// requiring the value to be delivered to at least one subscriber of this holder.
+    /// The invocation is dispatched as with [MultiThreadedCallback::invoke_dispatched], keeping its order with the other invocations.
+    ///
+    /// This turns an invocation without any listeners into an explicit error, which helps to catch wiring mistakes.
+    /// The subscribers are checked within the same fan-out which delivers the value,
+    /// so subscriptions which have been dropped but not yet removed don't count as subscribers.
+    /// Whether the tap and the durable subscribers still observe a value which couldn't be delivered
+    /// depends on the [NoSubscribersPolicy] of this holder, see [MultiThreadedCallback::set_no_subscribers_policy].
+    /// Durable subscribers aren't taken into account as registered subscribers.
+    ///
+    /// Unlike a [SubscriptionListener], which is called back when the last subscriber of the holder has been removed,
+    /// the missing subscribers are reported to the invoker of the event itself, at the moment the event is invoked.
+    ///
+    /// # Returns
+    ///
+    /// It returns a future which resolves to the number of subscribers the value has been delivered to,
+    /// which is 0 when the value has been buffered,
+    /// or a [NoSubscribersError] when the value hasn't been delivered to any subscriber.
+    pub fn invoke_require_subscribers(
+        &self,
+        value: T,
+    ) -> impl Future<Output = Result<usize, NoSubscribersError<T>>> {
+        let (tx, rx) = oneshot::channel();
+        let value = Arc::new(value);
+        let invoked = value.clone();
+        self.dispatch(move |base| {
+            let _ = tx.send(base.invoke_required(invoked));
+        });
+
+        // the invocation might be cancelled by a shutdown of the internal runtime
+        async move {
+            rx.await
+                .unwrap_or_else(|_| Err(NoSubscribersError::new(value)))
+        }
+    }
+
+    /// Invoke the currently registered callbacks with the given value, applying backpressure on the bounded subscriptions.
+    /// Instead of dropping the event when the queue of a bounded subscription is full,
+    /// this waits until each bounded subscription has accepted the event.
+    /// The other subscribers receive the event immediately, as with [MultiThreadedCallback::invoke].
+    ///
+    /// The event is delivered on the calling task, bypassing the ordered fan-out task of this holder.
+    /// This means that the order of the events isn't guaranteed for the bounded subscriptions,
+    /// as a concurrent [MultiThreadedCallback::invoke] might deliver its event into a bounded queue ahead of the awaited event.
+    /// When a timeout is given, it's shared between all bounded subscriptions,
+    /// and the subscriptions which didn't accept the event before it elapsed are skipped and reported as [DeliveryFailure::Timeout].
+    ///
+    /// Beware that awaiting this invocation from the consumer of one of the bounded subscriptions of this holder
+    /// deadlocks once the queue of that subscription is full, as the consumer never receives its queued events.
+    /// Such a consumer is only skipped when a timeout is given.
+    ///
+    /// The regular subscribers receive the event before the bounded subscriptions are awaited,
+    /// so dropping the returned future before it completed, e.g. within a `select!`, results in a partial delivery
+    /// where the bounded subscriptions which haven't accepted the event yet never receive it.
+    ///
+    /// # Returns
+    ///
+    /// It returns the handles of the bounded subscriptions which have been skipped because they didn't accept the event in time.
+    pub async fn invoke_async(&self, value: T, timeout: Option<Duration>) -> Vec<CallbackHandle> {
+        self.base.invoke_backpressure(value, timeout).await
+    }
+
+    /// Invoke the currently registered callbacks with the given value, and wait until the acked subscriptions have confirmed its processing.
+    /// The consumer of an [AckedSubscription] confirms the processing by acknowledging the [crate::AckedEvent] which is received through [AckedSubscription::recv_acked],
+    /// while the events which are received through [AckedSubscription::recv] are confirmed on receipt.
+    /// The other subscribers receive the event immediately, as with [MultiThreadedCallback::invoke], and aren't awaited.
+    ///
+    /// The event is delivered on the calling task, bypassing the ordered fan-out task of this holder.
+    /// When a timeout is given, it's shared between all acked subscriptions.
+    ///
+    /// Beware that awaiting this invocation from the consumer of one of the acked subscriptions of this holder
+    /// deadlocks, as the consumer never receives the event to confirm.
+    ///
+    /// # Returns
+    ///
+    /// It returns the handles of the acked subscriptions which didn't confirm the event,
+    /// either because the timeout elapsed or the event has been dropped without being acknowledged.
+    pub async fn invoke_and_confirm(
+        &self,
+        value: T,
+        timeout: Option<Duration>,
+    ) -> Vec<CallbackHandle> {
+        self.base.invoke_confirmed(value, timeout).await
+    }
+
+    /// Invoke the currently registered callbacks and inform them of the given value,
+    /// without measuring the time it takes to deliver the value to each callback.
+    ///
+    /// Prefer this method over [MultiThreadedCallback::invoke] for throughput critical invocations,
+    /// as it skips the timing instrumentation and slow callback logging for each delivery.
+    /// The deliveries are still counted within [MultiThreadedCallback::stats], but aren't part of the latencies.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to invoke the callbacks with.
+    pub fn invoke_untimed(&self, value: T) {
+        self.dispatch(move |base| {
+            base.invoke_untimed(value);
+        })
+    }
+
+    /// Invoke the subscribers which have been tagged with the given tag key and value, and inform them of the given value.
+    ///
+    /// The tag value must exactly match the tag of the subscriber for the given key.
+    /// Subscribers which don't have the tag, including untagged and durable subscribers, won't receive the value.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The tag key to match.
+    /// * `tag` - The tag value which should exactly match the tag of the subscriber.
+    /// * `value` - The value to invoke the callbacks with.
+    pub fn invoke_where_tag(&self, key: &str, tag: &str, value: T) {
+        let key = key.to_string();
+        let tag = tag.to_string();
+        self.dispatch(move |base| {
+            base.invoke_where_tag(&key, &tag, value);
+        })
+    }
+
+    /// Invoke the currently registered callbacks with each value of the given batch, preserving the order of the batch.
+    /// The returned future completes once all values of the batch have been delivered to the subscribers.
+    ///
+    /// The batch is delivered within a single invocation task, so each subscriber receives the values in the given order.
+    /// The batch isn't atomic against invocations from other threads though,
+    /// the values of a concurrent invocation might be interleaved with the values of the batch.
+    ///
+    /// The invocation task cooperatively yields to the scheduler after every 256 deliveries,
+    /// so a large batch with many subscribers doesn't starve the other tasks of the runtime.
+    /// This slightly increases the latency of very large batches.
+    /// The ordered fan-out task queues each value of the batch as a separate invocation, so it yields in between the values as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The ordered values to invoke the callbacks with.
+    pub async fn invoke_all_ordered(&self, values: Vec<T>) {
+        if let Some(ordered) = self.ordered.as_ref() {
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            let mut invocations: Vec<Invocation<T>> = values
+                .into_iter()
+                .map(|value| {
+                    Box::new(move |base: &BaseCallback<T>| {
+                        base.invoke(value);
+                    }) as Invocation<T>
+                })
+                .collect();
+            invocations.push(Box::new(move |_: &BaseCallback<T>| {
+                let _ = tx.send(());
+            }));
+            self.dispatch_ordered(ordered, invocations);
+            let _ = rx.await;
+            return;
+        }
+
+        match self.runtime_handle() {
+            Some(handle) => {
+                let base = self.base.clone();
+                let _ = handle
+                    .spawn(async move { base.invoke_all_cooperative(values).await })
+                    .await;
+            }
+            None => self.base.invoke_all_cooperative(values).await,
+        }
+    }
+
+    /// Log every event of this callback holder at the given log level.
+    ///
+    /// This registers a new regular subscription on this callback holder, which is included in its subscribers,
+    /// and spawns a task that logs each received event.
+    /// The logging is stopped when the returned [TaskHandle] is dropped or aborted.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use fx_callback::MultiThreadedCallback;
+    ///
+    /// #[derive(Debug)]
+    /// pub enum MyEvent {
+    ///     Foo,
+    /// }
+    ///
+    /// let callback = MultiThreadedCallback::<MyEvent>::new();
+    /// let handle = callback.log_events(log::Level::Debug);
+    ///
+    /// callback.invoke(MyEvent::Foo);
+    /// ```
+    pub fn log_events(&self, level: log::Level) -> TaskHandle {
+        let mut receiver = self.subscribe();
+        TaskHandle::new(self.spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                log!(level, "Received callback event {:?}", event);
+            }
+        }))
+    }
+
+    /// Create a filtered view of this callback holder, which only re-invokes the events that match the given predicate.
+    /// Consumers can subscribe to the returned holder as to any other [Callback], without being aware of the predicate.
+    ///
+    /// The view registers a regular subscription on this holder and spawns a background task which forwards
+    /// the matching events to the view, sharing the same event instance without cloning it.
+    /// The forwarding task stops when either this holder or all clones of the view have been dropped,
+    /// after which its subscription is removed from this holder on the next invocation.
+    /// The events which are invoked directly on the view are delivered in order, as with [MultiThreadedCallback::new].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use fx_callback::{Callback, MultiThreadedCallback};
+    ///
+    /// #[derive(Debug)]
+    /// pub enum MyEvent {
+    ///     Foo,
+    ///     Bar,
+    /// }
+    ///
+    /// let callback = MultiThreadedCallback::<MyEvent>::new();
+    /// let foo_events = callback.filtered_view(|e| matches!(e, MyEvent::Foo));
+    ///
+    /// let mut receiver = foo_events.subscribe();
+    /// ```
+    pub fn filtered_view<F>(&self, predicate: F) -> MultiThreadedCallback<T>
+    where
+        F: Fn(&T) -> bool + Send + 'static,
+    {
+        let view = MultiThreadedCallback {
+            base: Arc::new(BaseCallback::new()),
+            runtime: self.runtime.clone(),
+            ordered: Some(Arc::new(OrderedDispatcher::new())),
+        };
+        let view_base = Arc::downgrade(&view.base);
+        let mut receiver = self.subscribe();
+
+        let task = self.spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                let Some(view_base) = view_base.upgrade() else {
+                    break;
+                };
+                if predicate(&event) {
+                    view_base.invoke_shared_with(event, true, None);
+                }
+            }
+            trace!("Filtered view forwarding task has stopped");
+        });
+        if task.is_none() {
+            warn!("Failed to spawn the forwarding task of the filtered view");
+        }
+
+        view
+    }
+
+    /// Dispatch the given invocation of the callbacks on a runtime.
+    /// When no runtime is available, the invocation is executed inline on the caller thread.
+    fn dispatch<F>(&self, invocation: F)
+    where
+        F: FnOnce(&BaseCallback<T>) + Send + 'static,
+    {
+        if !self.base.is_enabled() {
+            trace!("Callback holder is disabled, discarding the invocation");
+            return;
+        }
+
+        let inner = self.base.clone();
+        match self.runtime_handle() {
+            Some(handle) if self.ordered.is_some() => {
+                if let Some(ordered) = self.ordered.as_ref() {
+                    ordered.dispatch(&inner, &handle, [Box::new(invocation) as Invocation<T>]);
+                }
+            }
+            Some(handle) => {
+                // spawn the invocation operation in a new thread
+                handle.spawn(async move {
+                    invocation(&inner);
+                });
+            }
+            None => {
+                trace!("Invoking callbacks inline for {:?}", inner);
+                // the invocations which are still queued for the fan-out task are invoked before this one
+                if let Some(ordered) = self.ordered.as_ref() {
+                    ordered.drain(&inner);
+                }
+                invocation(&inner);
+            }
+        }
+    }
+
+    /// Dispatch the given invocations contiguously on the ordered fan-out task, which processes each of them separately.
+    /// When no runtime is available, the invocations are executed inline on the caller thread.
+    fn dispatch_ordered(&self, ordered: &OrderedDispatcher<T>, invocations: Vec<Invocation<T>>) {
+        if !self.base.is_enabled() {
+            trace!("Callback holder is disabled, discarding the invocations");
+            return;
+        }
+
+        match self.runtime_handle() {
+            Some(handle) => ordered.dispatch(&self.base, &handle, invocations),
+            None => {
+                trace!("Invoking callbacks inline for {:?}", self.base);
+                ordered.drain(&self.base);
+                for invocation in invocations {
+                    invocation(&self.base);
+                }
+            }
+        }
+    }
+
+    /// Spawn the given future on the current runtime, or on the internal runtime when no runtime is available.
+    ///
+    /// # Returns
+    ///
+    /// It returns the join handle of the spawned future, or [None] when the internal runtime couldn't be created.
+    pub(crate) fn spawn<F>(&self, future: F) -> Option<JoinHandle<F::Output>>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.runtime_handle().map(|handle| handle.spawn(future))
+    }
+
+    /// Get the handle of the current runtime, or of the internal runtime when no runtime is available.
+    /// The internal runtime is created when it doesn't exist yet.
+    ///
+    /// # Returns
+    ///
+    /// It returns [None] when the internal runtime couldn't be created.
+    fn runtime_handle(&self) -> Option<tokio::runtime::Handle> {
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => Some(handle),
+            Err(_) => match self.runtime.lock() {
+                Ok(mut runtime) => {
+                    if runtime.is_none() {
+                        match Runtime::new() {
+                            Ok(e) => *runtime = Some(e),
+                            Err(e) => warn!("Failed to create callback runtime, {}", e),
+                        }
+                    }
+
+                    runtime.as_ref().map(|e| e.handle().clone())
+                }
+                Err(e) => {
+                    error!("Failed to acquire lock: {}", e);
+                    None
+                }
+            },
+        }
+    }
+}
+
+impl<T> MultiThreadedCallback<Timestamped<T>>
+where
+    T: Debug + Send + Sync + 'static,
+{
+    /// Timestamp the given value and invoke the subscribers with it,
+    /// allowing the consumers to record its end-to-end delivery latency through [Self::record_latency].
+    pub fn invoke_timestamped(&self, value: T) {
+        self.invoke(Timestamped::new(value))
+    }
+
+    /// Record the end-to-end delivery latency of the given event, which is the time between timestamping the event and now.
+    /// The latency is recorded in a histogram which is shared between all clones of this holder, see [Self::latency_stats].
+    ///
+    /// This requires the cooperation of the consumers, which should record each event on receipt.
+    /// Events which are never recorded, e.g. because they're dropped, don't appear in the latency statistics.
+    pub fn record_latency(&self, event: &Timestamped<T>) {
+        self.base
+            .latency
+            .get_or_init(LatencyHistogram::new)
+            .record(event.at.elapsed());
+    }
+
+    /// Get the end-to-end delivery latency statistics of the events which have been recorded through [Self::record_latency].
+    pub fn latency_stats(&self) -> LatencyStats {
+        self.base
+            .latency
+            .get_or_init(LatencyHistogram::new)
+            .snapshot()
+    }
+}
+
+impl<T, R> MultiThreadedCallback<Request<T, R>>
+where
+    T: Debug + Send + Sync + 'static,
+    R: Send + Sync + 'static,
+{
+    /// Invoke the currently registered subscribers with a request for the given value,
+    /// and await the reply of each subscriber until the given timeout expires.
+    ///
+    /// Each subscriber receives its own [Request] for the shared value, which allows tracking the reply per subscriber.
+    /// The timeout is shared between all subscribers, it's not applied per subscriber.
+    /// Subscribers which didn't reply in time, or dropped the request without replying, are reported with a `None` reply.
+    /// Subscribers which are registered while the request is being invoked aren't included.
+    /// Paused and durable subscribers don't receive the request, nor is it observed by the tap of this holder.
+    /// The other policies of the subscribers apply as with a regular invocation,
+    /// so expired, gated and variant filtered subscribers don't receive the request and aren't included either,
+    /// while the request counts towards the delivery limit of a limited subscriber.
+    ///
+    /// The requests are dispatched as with [MultiThreadedCallback::invoke_dispatched],
+    /// so they keep their order with the other invocations of this holder,
+    /// and the timeout starts once the requests have been delivered to the subscribers.
+    /// The timeout requires the `time` driver of the tokio runtime to be enabled.
+    ///
+    /// # Returns
+    ///
+    /// It returns the reply of each subscriber that received the request.
+    pub async fn invoke_request_all(
+        &self,
+        value: T,
+        timeout: Duration,
+    ) -> HashMap<CallbackHandle, Option<Arc<R>>> {
+        let (tx, rx) = oneshot::channel();
+        self.dispatch(move |base| {
+            let value = Arc::new(value);
+            let mut pending = Vec::new();
+            let offered = base.invoke_each(|handle| {
+                let (request, reply) = Request::new(value.clone());
+                pending.push((*handle, reply));
+                request
+            });
+            pending.retain(|(handle, _)| offered.contains(handle));
+            let _ = tx.send(pending);
+        });
+
+        let pending = rx.await.unwrap_or_default();
+        collect_replies(pending, timeout).await
+    }
+}
+
+/// The invocation of the callbacks of a holder.
+type Invocation<T> = Box<dyn FnOnce(&BaseCallback<T>) + Send>;
+
+/// The dispatcher which processes the invocations of a holder in order on a single fan-out task.
+///
+/// The fan-out task only holds a weak reference to the holder, so the holder is never dropped on the fan-out task.
+/// The invocations which are still queued when the last holder is dropped are processed by the dropping thread instead.
+#[derive(Debug)]
+struct OrderedDispatcher<T>
+where
+    T: Debug + Send + Sync,
+{
+    queue: Arc<DispatchQueue<T>>,
+}
+
+impl<T> OrderedDispatcher<T>
+where
+    T: Debug + Send + Sync,
+{
+    fn new() -> Self {
+        Self {
+            queue: Arc::new(DispatchQueue {
+                state: Mutex::new(DispatchState {
+                    invocations: VecDeque::new(),
+                    running: false,
+                }),
+                processing: Mutex::new(()),
+            }),
+        }
+    }
+
+    /// Queue the given invocations contiguously on the fan-out task.
+    /// The fan-out task is spawned on the given runtime when it's not running yet.
+    ///
+    /// When the fan-out task is dropped before it completed, e.g. because its runtime has been shut down before the task was polled,
+    /// the queued invocations are processed by the thread which drops the task instead, and the next invocation spawns a new task.
+    fn dispatch<I>(
+        &self,
+        base: &Arc<BaseCallback<T>>,
+        runtime: &tokio::runtime::Handle,
+        invocations: I,
+    ) where
+        T: 'static,
+        I: IntoIterator<Item = Invocation<T>>,
+    {
+        let mut state = self.queue.lock_state();
+        state.invocations.extend(invocations);
+        if state.running {
+            return;
+        }
+        state.running = true;
+        drop(state);
+
+        // the guard is owned by the task from its creation, so a task which is never polled still resets the running state
+        let mut running = RunningGuard {
+            queue: self.queue.clone(),
+            base: Arc::downgrade(base),
+            armed: true,
+        };
+        runtime.spawn(async move {
+            let mut deliveries = 0;
+            while let Some(delivered) = running.queue.process_next(&running.base) {
+                deliveries += delivered;
+                if deliveries >= FAN_OUT_YIELD_INTERVAL {
+                    deliveries = 0;
+                    tokio::task::yield_now().await;
+                }
+            }
+            running.complete();
+            trace!("Ordered fan-out task has stopped");
+        });
+    }
+
+    /// Process all queued invocations on the calling thread,
+    /// after the invocation which is currently being processed by the fan-out task has completed.
+    fn drain(&self, base: &BaseCallback<T>) {
+        self.queue.drain(base);
+    }
+}
+
+/// The queue of the invocations of an [OrderedDispatcher], which is shared with its fan-out task.
+struct DispatchQueue<T>
+where
+    T: Debug + Send + Sync,
+{
+    state: Mutex<DispatchState<T>>,
+    /// Held while an invocation is being processed, so the invocations are never processed concurrently.
+    processing: Mutex<()>,
+}
+
+impl<T> DispatchQueue<T>
+where
+    T: Debug + Send + Sync,
+{
+    /// Process the next queued invocation on the holder, if it's still alive.
+    ///
+    /// # Returns
+    ///
+    /// It returns the estimated number of deliveries of the invocation,
+    /// or [None] when the queue is empty or the holder has been dropped.
+    fn process_next(&self, base: &Weak<BaseCallback<T>>) -> Option<usize> {
+        let _processing = self.lock_processing();
+        let invocation = {
+            let mut state = self.lock_state();
+            match state.invocations.pop_front() {
+                Some(invocation) => invocation,
+                None => {
+                    state.running = false;
+                    return None;
+                }
+            }
+        };
+
+        // the holder is released before the next invocation can be drained by the dropping thread
+        let base = base.upgrade()?;
+        invocation(&base);
+        Some(base.subscriber_count().max(1))
+    }
+
+    /// Process all queued invocations on the calling thread.
+    /// See [OrderedDispatcher::drain] for more info.
+    fn drain(&self, base: &BaseCallback<T>) {
+        let _processing = self.lock_processing();
+        let mut total = 0;
+        while let Some(invocation) = self.lock_state().invocations.pop_front() {
+            invocation(base);
+            total += 1;
+        }
+        if total > 0 {
+            trace!("Processed a total of {} queued invocations inline", total);
+        }
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, DispatchState<T>> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_processing(&self) -> std::sync::MutexGuard<'_, ()> {
+        self.processing.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<T> Debug for DispatchQueue<T>
+where
+    T: Debug + Send + Sync,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        f.debug_struct("DispatchQueue")
+            .field("pending", &state.invocations.len())
+            .field("running", &state.running)
+            .finish()
+    }
+}
+
+struct DispatchState<T>
+where
+    T: Debug + Send + Sync,
+{
+    invocations: VecDeque<Invocation<T>>,
+    /// Indicates if a fan-out task is processing the invocations.
+    running: bool,
+}
+
+/// The state of the fan-out task of a [DispatchQueue], which is moved into the task when it's spawned.
+///
+/// When the task is dropped before it completed, e.g. because its runtime has been shut down,
+/// the task is marked as stopped and the remaining invocations are processed inline by the dropping thread.
+struct RunningGuard<T>
+where
+    T: Debug + Send + Sync,
+{
+    queue: Arc<DispatchQueue<T>>,
+    base: Weak<BaseCallback<T>>,
+    armed: bool,
+}
+
+impl<T> RunningGuard<T>
+where
+    T: Debug + Send + Sync,
+{
+    /// Mark the fan-out task as completed, after which the queue has been marked as stopped by the task itself.
+    fn complete(&mut self) {
+        self.armed = false;
+    }
+}
+
+impl<T> Drop for RunningGuard<T>
+where
+    T: Debug + Send + Sync,
+{
+    fn drop(&mut self) {
+        if self.armed {
+            debug!("Ordered fan-out task has been dropped before it completed");
+            self.queue.lock_state().running = false;
+            if let Some(base) = self.base.upgrade() {
+                self.queue.drain(&base);
+            }
+        }
+    }
+}
+
+/// The handle of a background task which has been spawned by a callback holder.
+/// The task is aborted when this handle is dropped.
+#[derive(Debug)]
+pub struct TaskHandle {
+    handle: Option<JoinHandle<()>>,
+}
+
+impl TaskHandle {
+    pub(crate) fn new(handle: Option<JoinHandle<()>>) -> Self {
+        Self { handle }
+    }
+
+    /// Abort the background task of this handle.
+    pub fn abort(&self) {
+        if let Some(handle) = self.handle.as_ref() {
+            handle.abort();
+        }
+    }
+
+    /// Check if the background task of this handle has finished.
+    pub fn is_finished(&self) -> bool {
+        self.handle
+            .as_ref()
+            .map(|e| e.is_finished())
+            .unwrap_or(true)
+    }
+}
+
+impl Drop for TaskHandle {
+    fn drop(&mut self) {
+        self.abort();
+    }
+}
+
+/// A single threaded or current threaded callback holder.
+///
+/// This callback holder will invoke the given events on the current thread, thus blocking the caller thread for other tasks.
+#[derive(Debug, Clone)]
+pub struct SingleThreadedCallback<T>
+where
+    T: Debug + Send + Sync,
+{
+    base: Arc<BaseCallback<T>>,
+}
+
+impl<T> Default for SingleThreadedCallback<T>
+where
+    T: Debug + Send + Sync,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SingleThreadedCallback<T>
+where
+    T: Debug + Send + Sync,
+{
+    /// Create a new single/current threaded callback holder.
+    pub fn new() -> Self {
+        Self {
+            base: Arc::new(BaseCallback::<T>::new()),
+        }
+    }
+
+    /// Create a new single/current threaded callback holder which accepts at most the given number of subscribers.
+    /// See [MultiThreadedCallback::with_max_subscribers] for more info.
+    pub fn with_max_subscribers(limit: usize) -> Self {
+        Self::with_options(Some(limit), false)
+    }
+
+    /// Create a new single/current threaded callback holder with at most one subscriber at a time.
+    /// See [MultiThreadedCallback::new_exclusive] for more info.
+    pub fn new_exclusive() -> Self {
+        Self::with_options(None, true)
+    }
+
+    /// Create a new single/current threaded callback holder for exactly one consumer.
+    /// See [MultiThreadedCallback::single_consumer] for more info.
+    pub fn single_consumer() -> Self {
+        Self {
+            base: Arc::new(BaseCallback::<T>::single_consumer()),
+        }
+    }
+
+    pub(crate) fn with_options(max_subscribers: Option<usize>, exclusive: bool) -> Self {
+        Self {
+            base: Arc::new(BaseCallback::<T>::with_options(max_subscribers, exclusive)),
+        }
+    }
+
+    /// Create a new builder for a callback holder, which allows subscribers to be registered before the holder is used.
+    pub fn builder() -> SingleThreadedCallbackBuilder<T> {
+        SingleThreadedCallbackBuilder::new()
+    }
+
+    shared_callback_methods!();
+
+    /// Subscribe to the interested event with the given metadata tags.
+    /// The tags can be used to route events to specific subscribers through [SingleThreadedCallback::invoke_where_tag].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use fx_callback::SingleThreadedCallback;
+    ///
+    /// #[derive(Debug)]
+    /// pub enum MyEvent {
+    ///     Foo,
+    /// }
+    ///
+    /// let callback = SingleThreadedCallback::<MyEvent>::new();
+    /// let mut receiver = callback.subscribe_tagged([("region", "eu"), ("role", "audit")]);
+    ///
+    /// callback.invoke_where_tag("region", "eu", MyEvent::Foo);
+    /// ```
+    pub fn subscribe_tagged<I, K, V>(&self, tags: I) -> Subscription<T>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.base.subscribe_tagged(
+            tags.into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        )
+    }
+
+    /// Subscribe to the interested event for the given duration, after which the subscription is closed.
+    ///
+    /// The expiry is checked lazily on each invocation, the subscriber only receives the events which are invoked before the duration has elapsed
+    /// and is removed on the first invocation afterwards, which closes the subscription.
+    pub fn subscribe_for(&self, duration: Duration) -> Subscription<T> {
+        self.base.subscribe_for(duration).1
+    }
+
+    /// Subscribe to the interested event while the given gate is open, allowing the consumer to toggle its delivery without the holder.
+    /// The events which are invoked while the gate is `false` are dropped for this subscription, they aren't delivered once the gate reopens.
+    ///
+    /// Unlike [SingleThreadedCallback::pause_subscriber], which is controlled through the holder,
+    /// the gate is owned by the consumer and can be shared between multiple subscriptions, e.g. as a feature flag.
+    pub fn subscribe_gated(&self, gate: Arc<AtomicBool>) -> Subscription<T> {
+        self.base.subscribe_gated(gate)
+    }
+
+    /// Subscribe to the interested event with the given delivery priority.
+    /// On each invocation, the event is delivered to subscribers with a higher priority before subscribers with a lower priority.
+    /// Subscribers without an explicit priority have a priority of 0,
+    /// and subscribers with the same priority receive the event in the order in which they've subscribed.
+    pub fn subscribe_with_priority(&self, priority: i32) -> Subscription<T> {
+        self.base.subscribe_with_priority(priority)
+    }
+
+    /// Remove all subscribers from this callback holder, closing their subscriptions.
+    /// Each receiver returns [None] once it has received the events which were already queued,
+    /// unless other senders of the subscription are still alive.
+    ///
+    /// The subscribers are removed at once under a single lock, so events which are invoked after the clear
+    /// only reach the subscribers which have been added after the clear. Durable subscribers are not removed.
+    ///
+    /// # Returns
+    ///
+    /// It returns the number of subscribers which have been removed.
+    pub fn clear(&self) -> usize {
+        self.base.clear()
+    }
+
+    /// Start buffering the invoked events of this callback holder instead of delivering them to the subscribers.
+    /// The buffered events are delivered on [SingleThreadedCallback::flush_buffer], or dropped on [SingleThreadedCallback::discard_buffer],
+    /// which gives transactional control over the event delivery, e.g. during a speculative operation.
+    ///
+    /// Only the events which are broadcast to all subscribers are buffered,
+    /// tag routed invocations through [SingleThreadedCallback::invoke_where_tag] are always delivered immediately.
+    /// Calling this method while the holder is already buffering has no effect.
+    /// Buffered events which haven't been flushed are discarded when the holder is dropped.
+    pub fn begin_buffering(&self) {
+        self.base.begin_buffering()
+    }
+
+    /// Stop buffering the events of this callback holder and deliver all buffered events to the subscribers,
+    /// in the order in which they've been invoked.
+    ///
+    /// # Returns
+    ///
+    /// It returns the number of buffered events which have been delivered.
+    pub fn flush_buffer(&self) -> usize {
+        self.base.flush_buffer()
+    }
+
+    /// Enable or disable the tracing of the [Arc::strong_count] of each event after its fan-out has completed, which is disabled by default.
+    /// The count is logged at trace level and excludes the reference of the fan-out itself,
+    /// which helps to diagnose subscribers that keep holding on to the events longer than expected.
+    ///
+    /// The count includes the events which haven't been received yet from the subscriptions.
+    pub fn set_strong_count_tracing(&self, enabled: bool) {
+        self.base
+            .trace_strong_count
+            .store(enabled, Ordering::Relaxed);
+    }
+
+    /// Invoke the currently registered callbacks and inform them of the given value.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to invoke the callbacks with.
+    ///
+    /// # Returns
+    ///
+    /// It returns the number of subscribers the value has been delivered to.
+    /// Subscribers which have been dropped aren't counted, and it returns 0 when the holder is disabled or buffering its invocations.
+    pub fn invoke(&self, value: T) -> usize {
+        self.base.invoke(value)
+    }
+
+    /// Invoke the currently registered callbacks with the given shared value, without wrapping it into a new [Arc].
+    pub(crate) fn invoke_shared(&self, value: Arc<T>) {
+        self.base.invoke_shared_with(value, true, None);
+    }
+
+    /// Invoke the currently registered callbacks with the given value, applying backpressure on the bounded subscriptions.
+    /// Instead of dropping the event when the queue of a bounded subscription is full,
+    /// this waits until each bounded subscription has accepted the event.
+    /// The other subscribers receive the event immediately, as with [SingleThreadedCallback::invoke].
+    ///
+    /// The event is delivered on the calling task.
+    /// When a timeout is given, it's shared between all bounded subscriptions,
+    /// and the subscriptions which didn't accept the event before it elapsed are skipped and reported as [DeliveryFailure::Timeout].
+    ///
+    /// Beware that awaiting this invocation from the consumer of one of the bounded subscriptions of this holder
+    /// deadlocks once the queue of that subscription is full, as the consumer never receives its queued events.
+    /// Such a consumer is only skipped when a timeout is given.
+    ///
+    /// The regular subscribers receive the event before the bounded subscriptions are awaited,
+    /// so dropping the returned future before it completed, e.g. within a `select!`, results in a partial delivery
+    /// where the bounded subscriptions which haven't accepted the event yet never receive it.
+    ///
+    /// # Returns
+    ///
+    /// It returns the handles of the bounded subscriptions which have been skipped because they didn't accept the event in time.
+    pub async fn invoke_async(&self, value: T, timeout: Option<Duration>) -> Vec<CallbackHandle> {
+        self.base.invoke_backpressure(value, timeout).await
+    }
+
+    /// Invoke the currently registered callbacks with the given value, and wait until the acked subscriptions have confirmed its processing.
+    /// The consumer of an [AckedSubscription] confirms the processing by acknowledging the [crate::AckedEvent] which is received through [AckedSubscription::recv_acked],
+    /// while the events which are received through [AckedSubscription::recv] are confirmed on receipt.
+    /// The other subscribers receive the event immediately, as with [SingleThreadedCallback::invoke], and aren't awaited.
+    ///
+    /// The event is delivered on the calling task.
+    /// When a timeout is given, it's shared between all acked subscriptions.
+    ///
+    /// Beware that awaiting this invocation from the consumer of one of the acked subscriptions of this holder
+    /// deadlocks, as the consumer never receives the event to confirm.
+    ///
+    /// # Returns
+    ///
+    /// It returns the handles of the acked subscriptions which didn't confirm the event,
+    /// either because the timeout elapsed or the event has been dropped without being acknowledged.
+    pub async fn invoke_and_confirm(
+        &self,
+        value: T,
+        timeout: Option<Duration>,
+    ) -> Vec<CallbackHandle> {
+        self.base.invoke_confirmed(value, timeout).await
+    }
+
+    /// Invoke the currently registered callbacks and inform them of the given value,
+    /// without measuring the time it takes to deliver the value to each callback.
+    ///
+    /// Prefer this method over [SingleThreadedCallback::invoke] for throughput critical invocations,
+    /// as it skips the timing instrumentation and slow callback logging for each delivery.
+    /// The deliveries are still counted within [SingleThreadedCallback::stats], but aren't part of the latencies.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to invoke the callbacks with.
+    ///
+    /// # Returns
+    ///
+    /// It returns the number of subscribers the value has been delivered to, see [SingleThreadedCallback::invoke].
+    pub fn invoke_untimed(&self, value: T) -> usize {
+        self.base.invoke_untimed(value)
+    }
+
+    /// Invoke the currently registered callbacks and inform them of the given value,
+    /// requiring the value to be delivered to at least one subscriber of this holder.
+    ///
+    /// This turns an invocation without any listeners into an explicit error, which helps to catch wiring mistakes.
+    /// The subscribers are checked within the same fan-out which delivers the value,
+    /// so subscriptions which have been dropped but not yet removed don't count as subscribers.
+    /// Whether the tap and the durable subscribers still observe a value which couldn't be delivered
+    /// depends on the [NoSubscribersPolicy] of this holder, see [SingleThreadedCallback::set_no_subscribers_policy].
+    /// Durable subscribers aren't taken into account as registered subscribers.
+    ///
+    /// Unlike a [SubscriptionListener], which is called back when the last subscriber of the holder has been removed,
+    /// the missing subscribers are reported to the invoker of the event itself, at the moment the event is invoked.
+    ///
+    /// # Returns
+    ///
+    /// It returns the number of subscribers the value has been delivered to, which is 0 when the value has been buffered,
+    /// or a [NoSubscribersError] handing back the value when it hasn't been delivered to any subscriber.
+    pub fn invoke_require_subscribers(&self, value: T) -> Result<usize, NoSubscribersError<T>> {
+        self.base.invoke_required(Arc::new(value))
+    }
+
+    /// Invoke the subscribers which have been tagged with the given tag key and value, and inform them of the given value.
+    ///
+    /// The tag value must exactly match the tag of the subscriber for the given key.
+    /// Subscribers which don't have the tag, including untagged and durable subscribers, won't receive the value.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The tag key to match.
+    /// * `tag` - The tag value which should exactly match the tag of the subscriber.
+    /// * `value` - The value to invoke the callbacks with.
+    pub fn invoke_where_tag(&self, key: &str, tag: &str, value: T) {
+        self.base.invoke_where_tag(key, tag, value);
+    }
+
+    /// Invoke the currently registered callbacks with each value of the given batch, preserving the order of the batch.
+    /// The returned future completes once all values of the batch have been delivered to the subscribers.
+    ///
+    /// The batch isn't atomic against invocations from other threads,
+    /// the values of a concurrent invocation might be interleaved with the values of the batch.
+    ///
+    /// The invocation cooperatively yields to the scheduler after every 256 deliveries,
+    /// so a large batch with many subscribers doesn't starve the other tasks of the runtime.
+    /// This slightly increases the latency of very large batches.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The ordered values to invoke the callbacks with.
+    pub async fn invoke_all_ordered(&self, values: Vec<T>) {
+        self.base.invoke_all_cooperative(values).await
+    }
+}
+
+impl<T> SingleThreadedCallback<T>
+where
+    T: Debug + Send + Sync + 'static,
+{
+    shared_callback_methods!(static);
+
+    /// Invoke the currently registered callbacks with the given value on a spawned task of the current tokio runtime,
+    /// which allows an occasional slow event to be fanned out off the caller thread.
+    ///
+    /// This requires the method to be called from within the context of a tokio runtime.
+    /// When no runtime is available, the callbacks are invoked synchronously on the caller thread instead,
+    /// just like [SingleThreadedCallback::invoke].
+    /// A spawned invocation might be delivered after invocations which are made later on the caller thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to invoke the callbacks with.
+    pub fn invoke_spawned(&self, value: T) {
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                let base = self.base.clone();
+                runtime.spawn(async move {
+                    base.invoke(value);
+                });
+            }
+            Err(_) => {
+                trace!("No runtime available, invoking callbacks inline");
+                self.base.invoke(value);
+            }
+        }
+    }
+}
+
+impl<T> SingleThreadedCallback<Timestamped<T>>
+where
+    T: Debug + Send + Sync + 'static,
+{
+    /// Timestamp the given value and invoke the subscribers with it,
+    /// allowing the consumers to record its end-to-end delivery latency through [Self::record_latency].
+    pub fn invoke_timestamped(&self, value: T) {
+        self.invoke(Timestamped::new(value));
+    }
+
+    /// Record the end-to-end delivery latency of the given event, which is the time between timestamping the event and now.
+    /// The latency is recorded in a histogram which is shared between all clones of this holder, see [Self::latency_stats].
+    ///
+    /// This requires the cooperation of the consumers, which should record each event on receipt.
+    /// Events which are never recorded, e.g. because they're dropped, don't appear in the latency statistics.
+    pub fn record_latency(&self, event: &Timestamped<T>) {
+        self.base
+            .latency
+            .get_or_init(LatencyHistogram::new)
+            .record(event.at.elapsed());
+    }
+
+    /// Get the end-to-end delivery latency statistics of the events which have been recorded through [Self::record_latency].
+    pub fn latency_stats(&self) -> LatencyStats {
+        self.base
+            .latency
+            .get_or_init(LatencyHistogram::new)
+            .snapshot()
+    }
+}
+
+impl<T, R> SingleThreadedCallback<Request<T, R>>
+where
+    T: Debug + Send + Sync,
+    R: Send + Sync,
+{
+    /// Invoke the currently registered subscribers with a request for the given value,
+    /// and await the reply of each subscriber until the given timeout expires.
+    ///
+    /// Each subscriber receives its own [Request] for the shared value, which allows tracking the reply per subscriber.
+    /// The timeout is shared between all subscribers, it's not applied per subscriber.
+    /// Subscribers which didn't reply in time, or dropped the request without replying, are reported with a `None` reply.
+    /// Subscribers which are registered while the request is being invoked aren't included.
+    /// Paused and durable subscribers don't receive the request, nor is it observed by the tap of this holder.
+    /// The other policies of the subscribers apply as with a regular invocation,
+    /// so expired, gated and variant filtered subscribers don't receive the request and aren't included either,
+    /// while the request counts towards the delivery limit of a limited subscriber.
+    ///
+    /// The timeout requires the `time` driver of the tokio runtime to be enabled.
+    ///
+    /// # Returns
+    ///
+    /// It returns the reply of each subscriber that received the request.
+    pub async fn invoke_request_all(
+        &self,
+        value: T,
+        timeout: Duration,
+    ) -> HashMap<CallbackHandle, Option<Arc<R>>> {
+        let value = Arc::new(value);
+        let mut pending = Vec::new();
+        let offered = self.base.invoke_each(|handle| {
+            let (request, reply) = Request::new(value.clone());
+            pending.push((*handle, reply));
+            request
+        });
+        pending.retain(|(handle, _)| offered.contains(handle));
+        collect_replies(pending, timeout).await
+    }
+}
+
+impl<T> Callback<T> for SingleThreadedCallback<T>
+where
+    T: Debug + Send + Sync,
+{
+    fn subscribe(&self) -> Subscription<T> {
+        self.base.subscribe()
+    }
+
+    fn subscribe_with(&self, subscriber: Subscriber<T>) -> CallbackHandle {
+        self.base.subscribe_with(subscriber)
+    }
+
+    fn try_subscribe_with(
+        &self,
+        subscriber: Subscriber<T>,
+    ) -> Result<CallbackHandle, CallbackError> {
+        self.base.try_subscribe_with(subscriber)
+    }
+
+    fn unsubscribe(&self, handle: CallbackHandle) -> bool {
+        self.base.remove(&handle)
+    }
+
+    fn subscriber_count(&self) -> usize {
+        self.base.subscriber_count()
+    }
+}
+
+/// A subscription which is deterministically removed from its callback holder.
+///
+/// Unlike a plain [Subscription], which is only removed from the callback holder the next time an event is invoked,
+/// this subscription removes itself from the holder immediately when it's dropped.
+/// Any events which are still queued at that moment are dropped together with the subscription.
+/// Use [ManagedSubscription::close] instead to remove the subscription while still receiving the queued events.
+///
+/// The subscription only holds a weak reference to the callback holder, so it doesn't keep the holder alive.
+///
+/// ## Stream
+///
+/// The subscription implements [crate::EventStream], so the stream combinators can be applied to it,
+/// while [ManagedSubscription::poll_recv] can be used to adapt it into a `Stream`, e.g. through `futures::stream::poll_fn`.
+#[derive(Debug)]
+pub struct ManagedSubscription<T>
+where
+    T: Debug + Send + Sync,
+{
+    handle: CallbackHandle,
+    receiver: Subscription<T>,
+    holder: Weak<BaseCallback<T>>,
+}
+
+impl<T> ManagedSubscription<T>
+where
+    T: Debug + Send + Sync,
+{
+    /// Get the handle of the subscription within the callback holder.
+    pub fn handle(&self) -> CallbackHandle {
+        self.handle
+    }
+
+    /// Receive the next event of the subscription.
+    ///
+    /// # Returns
+    ///
+    /// It returns [None] when the subscription has been closed or the callback holder has been dropped.
+    pub async fn recv(&mut self) -> Option<Arc<T>> {
+        self.receiver.recv().await
+    }
+
+    /// Poll to receive the next event of the subscription.
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Arc<T>>> {
+        self.receiver.poll_recv(cx)
+    }
+
+    /// Close the subscription by removing it from the callback holder,
+    /// and drain all events which were still queued for this subscription.
+    ///
+    /// # Returns
+    ///
+    /// It returns the remaining events of the subscription in the order they were invoked.
+    pub async fn close(mut self) -> Vec<Arc<T>> {
+        self.unsubscribe();
+
+        let mut events = Vec::new();
+        while let Some(event) = self.receiver.recv().await {
+            events.push(event);
+        }
+        events
+    }
+
+    fn unsubscribe(&self) {
+        if let Some(holder) = self.holder.upgrade() {
+            holder.remove(&self.handle);
+        }
+    }
+}
+
+impl<T> Drop for ManagedSubscription<T>
+where
+    T: Debug + Send + Sync,
+{
+    fn drop(&mut self) {
+        self.unsubscribe();
+    }
+}
+
+/// A guard which removes its subscriber from the callback holder when it's dropped.
+///
+/// The guard only holds a weak reference to the callback holder,
+/// so it doesn't keep the holder alive and can safely be dropped after the holder has been dropped.
+#[derive(Debug)]
+pub struct SubscriptionGuard<T>
+where
+    T: Debug + Send + Sync,
+{
+    handle: CallbackHandle,
+    holder: Weak<BaseCallback<T>>,
+}
+
+impl<T> SubscriptionGuard<T>
+where
+    T: Debug + Send + Sync,
+{
+    /// Get the handle of the guarded subscriber within the callback holder.
+    pub fn handle(&self) -> CallbackHandle {
+        self.handle
+    }
+}
+
+impl<T> Drop for SubscriptionGuard<T>
+where
+    T: Debug + Send + Sync,
+{
+    fn drop(&mut self) {
+        if let Some(holder) = self.holder.upgrade() {
+            holder.remove(&self.handle);
+        }
+    }
+}
+
+struct BaseCallback<T>
+where
+    T: Debug + Send + Sync,
+{
+    callbacks: Mutex<IndexMap<CallbackHandle, CallbackEntry<T>>>,
+    /// The only consumer of a single consumer holder, while it's kept outside of the callbacks.
+    single: Mutex<Option<SingleConsumer<T>>>,
+    /// Indicates if the only consumer of this holder is kept outside of the callbacks, see [MultiThreadedCallback::single_consumer].
+    single_consumer: bool,
+    /// The number of registered callbacks, which is updated each time the callbacks are modified.
+    callbacks_len: AtomicUsize,
+    errors: Mutex<Vec<UnboundedSender<Arc<DeliveryError<T>>>>>,
+    durable: Arc<DurableRegistry<T>>,
+    dropped: AtomicU64,
+    stats: StatsRecorder,
+    drop_event: Mutex<Option<Box<dyn FnOnce() -> T + Send>>>,
+    tap: Mutex<Option<Arc<EventTap<T>>>>,
+    handle_factory: fn() -> CallbackHandle,
+    dropped_signal: tokio::sync::watch::Sender<()>,
+    slow_warning_interval: AtomicU64,
+    pause_buffer_capacity: AtomicUsize,
+    buffer: Mutex<Option<Vec<Arc<T>>>>,
+    trace_strong_count: AtomicBool,
+    variant_bit: OnceLock<fn(&T) -> u64>,
+    invoke_trace: Mutex<Option<InvokeTrace<T>>>,
+    latency: OnceLock<LatencyHistogram>,
+    /// The maximum number of registered callbacks, or [None] when unlimited.
+    max_subscribers: Option<usize>,
+    enabled: AtomicBool,
+    listener: Mutex<Option<Arc<dyn SubscriptionListener>>>,
+    duplicate_policy: Mutex<DuplicatePolicy>,
+    no_subscribers_policy: Mutex<NoSubscribersPolicy>,
+    /// Indicates if a new callback replaces all previously registered callbacks.
+    exclusive: bool,
+    unsubscribed: Notify,
+}
+
+impl<T> BaseCallback<T>
+where
+    T: Debug + Send + Sync,
+{
+    fn new() -> Self {
+        Self::with_handle_factory(CallbackHandle::new)
+    }
+
+    /// Create a new base callback which generates the handles of its subscribers through the given factory.
+    fn with_handle_factory(handle_factory: fn() -> CallbackHandle) -> Self {
+        Self {
+            callbacks: Mutex::new(IndexMap::new()),
+            single: Mutex::new(None),
+            single_consumer: false,
+            callbacks_len: AtomicUsize::new(0),
+            errors: Mutex::new(Vec::new()),
+            durable: DurableRegistry::new(),
+            dropped: AtomicU64::new(0),
+            stats: StatsRecorder::new(),
+            drop_event: Mutex::new(None),
+            tap: Mutex::new(None),
+            handle_factory,
+            dropped_signal: tokio::sync::watch::channel(()).0,
+            slow_warning_interval: AtomicU64::new(DEFAULT_SLOW_WARNING_INTERVAL.as_nanos() as u64),
+            pause_buffer_capacity: AtomicUsize::new(DEFAULT_PAUSE_BUFFER_CAPACITY),
+            buffer: Mutex::new(None),
+            trace_strong_count: AtomicBool::new(false),
+            variant_bit: OnceLock::new(),
+            invoke_trace: Mutex::new(None),
+            latency: OnceLock::new(),
+            max_subscribers: None,
+            enabled: AtomicBool::new(true),
+            listener: Mutex::new(None),
+            duplicate_policy: Mutex::new(DuplicatePolicy::default()),
+            no_subscribers_policy: Mutex::new(NoSubscribersPolicy::default()),
+            exclusive: false,
+            unsubscribed: Notify::new(),
+        }
+    }
+
+    /// Create a new base callback which accepts at most the given number of callbacks.
+    /// When exclusive, each new callback replaces all previously registered callbacks.
+    fn with_options(max_subscribers: Option<usize>, exclusive: bool) -> Self {
+        let mut base = Self::new();
+        base.max_subscribers = max_subscribers;
+        base.exclusive = exclusive;
+        base
+    }
+
+    /// Create a new base callback which accepts a single consumer, which is kept outside of the callbacks.
+    fn single_consumer() -> Self {
+        let mut base = Self::with_options(Some(1), false);
+        base.single_consumer = true;
+        base
+    }
+
+    /// Lock the registered callbacks.
+    /// The single consumer is moved into the callbacks first, as the caller might inspect or modify it.
+    fn lock_callbacks(
+        &self,
+    ) -> std::sync::MutexGuard<'_, IndexMap<CallbackHandle, CallbackEntry<T>>> {
+        if !self.single_consumer {
+            return self.callbacks.lock().expect("failed to acquire lock");
+        }
+
+        let mut single = self.single.lock().expect("failed to acquire lock");
+        let mut mutex = self.callbacks.lock().expect("failed to acquire lock");
+        if let Some(consumer) = single.take() {
+            trace!(
+                "Moving single consumer {} into the callbacks",
+                consumer.handle
+            );
+            mutex.insert(
+                consumer.handle,
+                CallbackEntry::new(CallbackSender::Unbounded(consumer.sender)),
+            );
+        }
+        mutex
+    }
+
+    fn on_holder_dropped(&self) -> impl Future<Output = ()> + Send + 'static {
+        let mut receiver = self.dropped_signal.subscribe();
+        async move {
+            // the signal never changes, it only closes when the sender is dropped together with this holder
+            while receiver.changed().await.is_ok() {}
+        }
+    }
+
+    fn subscribe(&self) -> Subscription<T> {
+        self.subscribe_handle().1
+    }
+
+    fn subscribe_handle(&self) -> (CallbackHandle, Subscription<T>) {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        (self.subscribe_with(tx), rx)
+    }
+
+    fn subscribe_with(&self, subscriber: Subscriber<T>) -> CallbackHandle {
+        match self.insert_single(subscriber) {
+            Ok(handle) => handle,
+            Err(subscriber) => self.insert(CallbackSender::Unbounded(subscriber)),
+        }
+    }
+
+    /// Try to register the given subscriber.
+    /// See [BaseCallback::try_insert_entries] for more info.
+    fn try_subscribe_with(
+        &self,
+        subscriber: Subscriber<T>,
+    ) -> Result<CallbackHandle, CallbackError> {
+        match self.insert_single(subscriber) {
+            Ok(handle) => Ok(handle),
+            Err(subscriber) => self
+                .try_insert_entries(vec![CallbackEntry::new(CallbackSender::Unbounded(
+                    subscriber,
+                ))])
+                .map(|mut handles| handles.pop().expect("expected the handle of the callback")),
+        }
+    }
+
+    /// Register the given subscriber as the single consumer of this holder,
+    /// when this is a single consumer holder without any registered callbacks.
+    /// A closed single consumer is replaced by the given subscriber.
+    ///
+    /// # Returns
+    ///
+    /// It returns the handle of the single consumer, or hands back the subscriber when it should be registered as a regular callback.
+    fn insert_single(&self, subscriber: Subscriber<T>) -> Result<CallbackHandle, Subscriber<T>> {
+        if !self.single_consumer {
+            return Err(subscriber);
+        }
+
+        let mut single = self.single.lock().expect("failed to acquire lock");
+        let mutex = self.callbacks.lock().expect("failed to acquire lock");
+        let is_available = single
+            .as_ref()
+            .map_or(true, |consumer| consumer.sender.is_closed());
+        if !is_available || !mutex.is_empty() {
+            return Err(subscriber);
+        }
+        drop(mutex);
+
+        let handle = (self.handle_factory)();
+        let replaced = single.replace(SingleConsumer {
+            handle,
+            sender: subscriber,
+        });
+        self.callbacks_len.store(1, Ordering::Relaxed);
+        drop(single);
+        trace!("Added single consumer {} to {:?}", handle, self);
+
+        if let Some(replaced) = replaced {
+            trace!("Replaced closed single consumer {}", replaced.handle);
+            self.notify_unsubscribed(&[replaced.handle], 0);
+        }
+        self.notify_subscribed(&[handle], 1);
+        Ok(handle)
+    }
+
+    fn subscribe_bounded(&self, capacity: usize) -> BoundedSubscription<T> {
+        let (tx, rx) = tokio::sync::mpsc::channel(capacity);
+        self.insert(CallbackSender::Bounded(tx));
+        rx
+    }
+
+    fn subscribe_fn<F>(&self, closure: F) -> CallbackHandle
+    where
+        F: Fn(Arc<T>) + Send + Sync + 'static,
+    {
+        self.insert(CallbackSender::Closure(Box::new(closure)))
+    }
+
+    fn start_capture(&self, window: Duration) -> CaptureHandle<T>
+    where
+        T: 'static,
+    {
+        let (sender, handle) = CaptureSender::new(window);
+        self.insert(CallbackSender::Forward(Box::new(sender)));
+        handle
+    }
+
+    fn subscribe_adaptive(&self, high_watermark: usize) -> AdaptiveSubscription<T>
+    where
+        T: 'static,
+    {
+        let (sender, subscription) = AdaptiveSender::new(high_watermark);
+        self.insert(CallbackSender::Forward(Box::new(sender)));
+        subscription
+    }
+
+    fn subscribe_overflow(&self, capacity: usize, policy: OverflowPolicy) -> OverflowSubscription<T>
+    where
+        T: 'static,
+    {
+        let (sender, subscription) = OverflowSender::new(capacity, policy);
+        self.insert(CallbackSender::Forward(Box::new(sender)));
+        subscription
+    }
+
+    fn subscribe_latest(&self) -> LatestSubscription<T>
+    where
+        T: 'static,
+    {
+        let (sender, subscription) = LatestSender::new();
+        self.insert(CallbackSender::Forward(Box::new(sender)));
+        subscription
+    }
+
+    fn subscribe_acked(&self) -> AckedSubscription<T>
+    where
+        T: 'static,
+    {
+        let (sender, mut subscription) = AckedSender::new();
+        subscription.set_handle(self.insert(CallbackSender::Forward(Box::new(sender))));
+        subscription
+    }
+
+    async fn probe(&self, handle: &CallbackHandle, timeout: Duration) -> Option<LivenessStatus> {
+        let probe = {
+            let mutex = self.lock_callbacks();
+            match mutex.get(handle) {
+                None => return Some(LivenessStatus::Closed),
+                Some(entry) if entry.sender.is_closed() => Err(LivenessStatus::Closed),
+                Some(entry) => Ok(entry.sender.probe()?),
+            }
+        };
+
+        let status = match probe {
+            Ok(probe) => match tokio::time::timeout(timeout, probe).await {
+                Ok(Ok(_)) => LivenessStatus::Alive,
+                Ok(Err(_)) => LivenessStatus::Closed,
+                Err(_) => LivenessStatus::Stalled(self.queue_depth(handle).unwrap_or(0)),
+            },
+            Err(status) => status,
+        };
+        match status {
+            LivenessStatus::Alive => trace!("Callback {} is alive", handle),
+            LivenessStatus::Stalled(pending) => warn!(
+                "Callback {} has stalled with an estimated {} pending events",
+                handle, pending
+            ),
+            LivenessStatus::Closed => debug!("Callback {} has been closed", handle),
+        }
+
+        let mutex = self.lock_callbacks();
+        if let Some(entry) = mutex.get(handle) {
+            *entry.liveness.lock().expect("failed to acquire lock") = Some(status);
+        }
+        Some(status)
+    }
+
+    fn queue_depth(&self, handle: &CallbackHandle) -> Option<usize> {
+        self.lock_callbacks()
+            .get(handle)
+            .and_then(|entry| entry.sender.queue_depth())
+    }
+
+    fn subscribe_flat_map<U, I, F>(&self, function: F) -> Subscription<U>
+    where
+        T: 'static,
+        U: Send + Sync + 'static,
+        I: IntoIterator<Item = U> + 'static,
+        F: Fn(Arc<T>) -> I + Send + Sync + 'static,
+    {
+        let (sender, rx) = tokio::sync::mpsc::unbounded_channel();
+        self.insert(CallbackSender::Forward(Box::new(FlatMapSender {
+            sender,
+            function,
+        })));
+        rx
+    }
+
+    fn subscribe_fn_with_timeout<F>(&self, closure: F, timeout: Duration) -> CallbackHandle
+    where
+        F: Fn(Arc<T>) + Send + Sync + 'static,
+        T: 'static,
+    {
+        let closure = Arc::new(closure);
+        self.subscribe_fn(move |value| match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                let closure = closure.clone();
+                let task = runtime.spawn_blocking(move || closure(value));
+                runtime.spawn(async move {
+                    match tokio::time::timeout(timeout, task).await {
+                        Ok(Ok(_)) => {}
+                        Ok(Err(e)) => error!("Closure callback failed, {}", e),
+                        Err(_) => warn!("Closure callback overran its timeout of {:?}", timeout),
+                    }
+                });
+            }
+            Err(_) => {
+                let start_time = Instant::now();
+                closure(value);
+                let elapsed = start_time.elapsed();
+                if elapsed > timeout {
+                    warn!(
+                        "Closure callback overran its timeout of {:?}, took {:?}",
+                        timeout, elapsed
+                    );
+                }
+            }
+        })
+    }
+
+    fn subscribe_owned(&self) -> OwnedSubscription<T>
+    where
+        T: Clone + 'static,
+    {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        self.insert(CallbackSender::Forward(Box::new(tx)));
+        rx
+    }
+
+    fn subscribe_many(&self, count: usize) -> Vec<Subscription<T>> {
+        let (senders, receivers): (Vec<_>, Vec<_>) = (0..count)
+            .map(|_| tokio::sync::mpsc::unbounded_channel())
+            .unzip();
+        self.insert_entries(
+            senders
+                .into_iter()
+                .map(|e| CallbackEntry::new(CallbackSender::Unbounded(e)))
+                .collect(),
+        );
+        receivers
+    }
+
+    fn subscribe_into(&self, senders: Vec<Subscriber<T>>) -> Vec<CallbackHandle> {
+        self.insert_entries(
+            senders
+                .into_iter()
+                .map(|e| CallbackEntry::new(CallbackSender::Unbounded(e)))
+                .collect(),
+        )
+    }
+
+    fn subscribe_with_priority(&self, priority: i32) -> Subscription<T> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut entry = CallbackEntry::new(CallbackSender::Unbounded(tx));
+        entry.priority = priority;
+        self.insert_entry(entry);
+        rx
+    }
+
+    fn subscribe_variant_mask(&self, mask: u64) -> Subscription<T>
+    where
+        T: VariantMask,
+    {
+        let _ = self.variant_bit.set(T::variant_bit);
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut entry = CallbackEntry::new(CallbackSender::Unbounded(tx));
+        entry.variant_mask = Some(mask);
+        self.insert_entry(entry);
+        rx
+    }
+
+    fn subscribe_with_limit(
+        &self,
+        subscriber: Subscriber<T>,
+        limit: usize,
+    ) -> Option<CallbackHandle> {
+        if limit == 0 {
+            // the subscriber is dropped immediately, as it may never receive any event
+            trace!("Rejected callback with a delivery limit of 0");
+            return None;
+        }
+
+        let mut entry = CallbackEntry::new(CallbackSender::Unbounded(subscriber));
+        entry.remaining = Some(AtomicUsize::new(limit));
+        Some(self.insert_entry(entry))
+    }
+
+    fn subscribe_once(&self) -> oneshot::Receiver<Arc<T>>
+    where
+        T: 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        let mut entry = CallbackEntry::new(CallbackSender::Forward(Box::new(OnceSender {
+            sender: Mutex::new(Some(tx)),
+        })));
+        entry.remaining = Some(AtomicUsize::new(1));
+        self.insert_entry(entry);
+        rx
+    }
+
+    fn subscribe_for(&self, duration: Duration) -> (CallbackHandle, Subscription<T>) {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut entry = CallbackEntry::new(CallbackSender::Unbounded(tx));
+        entry.expires = Some(entry.created + duration);
+        (self.insert_entry(entry), rx)
+    }
+
+    fn subscribe_named(&self, name: String, subscriber: Subscriber<T>) -> CallbackHandle {
+        let mut entry = CallbackEntry::new(CallbackSender::Unbounded(subscriber));
+        entry.name = Some(name);
+        self.insert_entry(entry)
+    }
+
+    fn subscribe_gated(&self, gate: Arc<AtomicBool>) -> Subscription<T> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut entry = CallbackEntry::new(CallbackSender::Unbounded(tx));
+        entry.gate = Some(gate);
+        self.insert_entry(entry);
+        rx
+    }
+
+    fn subscribe_tagged(&self, tags: HashMap<String, String>) -> Subscription<T> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut entry = CallbackEntry::new(CallbackSender::Unbounded(tx));
+        entry.tags = tags;
+        self.insert_entry(entry);
+        rx
+    }
+
+    /// Register the given sender as a new callback.
+    ///
+    /// # Returns
+    ///
+    /// It returns the handle of the new callback.
+    fn insert(&self, sender: CallbackSender<T>) -> CallbackHandle {
+        self.insert_entry(CallbackEntry::new(sender))
+    }
+
+    /// Register the given callback entry.
+    /// When the generated handle is already in use by another callback, a new handle is generated
+    /// to prevent the existing callback from being overwritten.
+    ///
+    /// # Returns
+    ///
+    /// It returns the handle of the new callback.
+    ///
+    /// # Panics
+    ///
+    /// It panics when no unique handle could be generated within [MAX_HANDLE_ATTEMPTS].
+    fn insert_entry(&self, entry: CallbackEntry<T>) -> CallbackHandle {
+        self.insert_entries(vec![entry])
+            .pop()
+            .expect("expected the handle of the callback")
+    }
+
+    /// Register the given callback entries under a single lock acquisition, in the given order.
+    /// See [BaseCallback::insert_entry] for more info.
+    ///
+    /// # Returns
+    ///
+    /// It returns the handles of the new callbacks, in the order of the given entries.
+    /// When the entries are rejected, the returned handles aren't registered, see [Callback::subscribe_with].
+    fn insert_entries(&self, entries: Vec<CallbackEntry<T>>) -> Vec<CallbackHandle> {
+        let total = entries.len();
+        match self.try_insert_entries(entries) {
+            Ok(handles) => handles,
+            Err(e) => {
+                warn!("Rejected {} callbacks of {:?}, {}", total, self, e);
+                // the infallible registrations need a handle, which refers to no callback at all
+                (0..total).map(|_| (self.handle_factory)()).collect()
+            }
+        }
+    }
+
+    /// Try to register the given callback entries under a single lock acquisition, in the given order.
+    /// The entries are either all registered, or none of them when they exceed the maximum number of callbacks.
+    /// Entries of which the sender is already registered are handled according to the [DuplicatePolicy] of this holder.
+    ///
+    /// Callbacks which have been closed are removed first when the limit would be exceeded,
+    /// so their capacity is freed without waiting for the next invocation.
+    ///
+    /// # Returns
+    ///
+    /// It returns the handles of the callbacks, or the [CallbackError] when the entries couldn't be registered.
+    fn try_insert_entries(
+        &self,
+        entries: Vec<CallbackEntry<T>>,
+    ) -> Result<Vec<CallbackHandle>, CallbackError> {
+        let policy = *self
+            .duplicate_policy
+            .lock()
+            .expect("failed to acquire lock");
+        let mut mutex = self.lock_callbacks();
+        let mut pruned = Vec::new();
+        if let Some(limit) = self.max_subscribers {
+            if mutex.len() + entries.len() > limit {
+                mutex.retain(|handle, entry| {
+                    let closed = entry.sender.is_closed();
+                    if closed {
+                        pruned.push(*handle);
+                    }
+                    !closed
+                });
+                self.callbacks_len.store(mutex.len(), Ordering::Relaxed);
+            }
+        }
+        let pruned_count = mutex.len();
+
+        let duplicates: Vec<Option<CallbackHandle>> = entries
+            .iter()
+            .map(|entry| match policy {
+                DuplicatePolicy::AllowDuplicates => None,
+                _ => mutex
+                    .iter()
+                    .find(|(_, e)| e.sender.same_channel(&entry.sender))
+                    .map(|(handle, _)| *handle),
+            })
+            .collect();
+        let existing = duplicates.iter().flatten().next().copied();
+        // the exclusive callbacks are replaced, so they don't take up any capacity
+        let current = if self.exclusive { 0 } else { mutex.len() };
+        let error = match (policy, existing, self.max_subscribers) {
+            (DuplicatePolicy::ErrorOnDuplicate, Some(existing), _) => {
+                Some(CallbackError::DuplicateSubscriber(existing))
+            }
+            (_, _, Some(limit))
+                if current + duplicates.iter().filter(|e| e.is_none()).count() > limit =>
+            {
+                Some(CallbackError::TooManySubscribers { limit, current })
+            }
+            _ => None,
+        };
+        if let Some(error) = error {
+            drop(mutex);
+            self.notify_unsubscribed(&pruned, pruned_count);
+            return Err(error);
+        }
+
+        let mut replaced = Vec::new();
+        let mut handles = Vec::with_capacity(entries.len());
+        let mut added = Vec::with_capacity(entries.len());
+        let mut names = Vec::with_capacity(entries.len());
+        for (entry, duplicate) in entries.into_iter().zip(duplicates) {
+            if let Some(existing) = duplicate {
+                trace!(
+                    "Callback {} is already registered, ignoring duplicate",
+                    existing
+                );
+                handles.push(existing);
+                continue;
+            }
+
+            let handle = (0..MAX_HANDLE_ATTEMPTS)
+                .map(|_| (self.handle_factory)())
+                .find(|handle| {
+                    let collision = mutex.contains_key(handle);
+                    if collision {
+                        warn!(
+                            "Callback handle {} is already in use, generating a new handle",
+                            handle
+                        );
+                    }
+                    !collision
+                })
+                .expect("failed to generate a unique callback handle");
+            if self.exclusive {
+                replaced.extend(mutex.drain(..));
+            }
+            names.push(entry.name.clone());
+            mutex.insert(handle, entry);
+            handles.push(handle);
+            added.push(handle);
+        }
+        let count = mutex.len();
+        self.callbacks_len.store(count, Ordering::Relaxed);
+        drop(mutex);
+        for (handle, name) in added.iter().zip(names) {
+            trace!(
+                "Added callback {} to {:?}",
+                CallbackName::new(handle, name.as_deref()),
+                self
+            );
+        }
+
+        // the replaced entries are dropped outside the lock, which closes their subscriptions
+        let mut replaced: Vec<CallbackHandle> =
+            replaced.into_iter().map(|(handle, _)| handle).collect();
+        if self.exclusive && added.len() > 1 {
+            // the new callbacks which have been replaced within the same registration are never reported
+            replaced.retain(|handle| !added.contains(handle));
+            added.drain(..added.len() - 1);
+        }
+        if !replaced.is_empty() {
+            debug!("Replaced a total of {} exclusive callbacks", replaced.len());
+        }
+        self.notify_unsubscribed(&pruned, pruned_count);
+        self.notify_unsubscribed(&replaced, 0);
+        self.notify_subscribed(&added, count);
+        Ok(handles)
+    }
+
+    /// Remove the callback with the given handle.
+    ///
+    /// # Returns
+    ///
+    /// It returns `true` when the callback has been removed, else `false`.
+    fn remove(&self, handle: &CallbackHandle) -> bool {
+        let mut mutex = self.lock_callbacks();
+        let removed = mutex.shift_remove(handle);
+        let count = mutex.len();
+        self.callbacks_len.store(count, Ordering::Relaxed);
+        drop(mutex);
+        match removed {
+            Some(entry) => {
+                trace!("Removed callback {} from {:?}", entry.display(handle), self);
+                drop(entry);
+                self.notify_unsubscribed(&[*handle], count);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn replace(
+        &self,
+        handle: &CallbackHandle,
+        sender: CallbackSender<T>,
+    ) -> Result<(), CallbackError> {
+        let mut mutex = self.lock_callbacks();
+        let previous = match mutex.get_mut(handle) {
+            Some(entry) => std::mem::replace(&mut entry.sender, sender),
+            None => return Err(CallbackError::UnknownHandle(*handle)),
+        };
+        drop(mutex);
+
+        // the previous sender is dropped outside the lock, as dropping a closure might interact with this holder
+        drop(previous);
+        trace!(
+            "Replaced the subscriber of callback {} in {:?}",
+            handle,
+            self
+        );
+        Ok(())
+    }
+
+    fn clear(&self) -> usize {
+        let mut mutex = self.lock_callbacks();
+        let entries = std::mem::take(&mut *mutex);
+        self.callbacks_len.store(0, Ordering::Relaxed);
+        drop(mutex);
+
+        // the entries are dropped outside the lock, as dropping a closure might interact with this holder
+        let removed = entries.len();
+        let handles: Vec<CallbackHandle> = entries.keys().copied().collect();
+        drop(entries);
+        trace!("Cleared a total of {} callbacks from {:?}", removed, self);
+        self.notify_unsubscribed(&handles, 0);
+        removed
+    }
+
+    fn prune(&self) -> usize {
+        let mut mutex = self.lock_callbacks();
+        let now = Instant::now();
+        let mut removed = Vec::new();
+        mutex.retain(|handle, entry| {
+            let closed = entry.sender.is_closed() || entry.expires.is_some_and(|e| now >= e);
+            if closed {
+                trace!("Pruning callback {}", entry.display(handle));
+                removed.push(*handle);
+            }
+            !closed
+        });
+        let count = mutex.len();
+        self.callbacks_len.store(count, Ordering::Relaxed);
+        drop(mutex);
+
+        if !removed.is_empty() {
+            debug!("Pruned a total of {} callbacks", removed.len());
+        }
+        self.notify_unsubscribed(&removed, count);
+        removed.len()
+    }
+
+    fn unsubscribe_by_tag(&self, key: &str, tag: &str) -> usize {
+        let mut mutex = self.lock_callbacks();
+        let mut handles = Vec::new();
+        mutex.retain(|handle, entry| {
+            let retained = entry.tags.get(key).map(|e| e != tag).unwrap_or(true);
+            if !retained {
+                handles.push(*handle);
+            }
+            retained
+        });
+        let count = mutex.len();
+        self.callbacks_len.store(count, Ordering::Relaxed);
+        drop(mutex);
+
+        let removed = handles.len();
+        self.notify_unsubscribed(&handles, count);
+
+        trace!(
+            "Removed a total of {} callbacks tagged with {}={}",
+            removed,
+            key,
+            tag
+        );
+        removed
+    }
+
+    fn describe_subscribers(&self) -> Vec<SubscriberInfo> {
+        let mutex = self.lock_callbacks();
+        let now = Instant::now();
+        mutex
+            .iter()
+            .map(|(handle, entry)| entry.info(*handle, now))
+            .collect()
+    }
+
+    fn set_paused(&self, handle: &CallbackHandle, paused: bool) -> bool {
+        let mut mutex = self.lock_callbacks();
+        match mutex.get_mut(handle) {
+            Some(entry) => {
+                entry.paused = paused;
+                trace!("Callback {} paused state changed to {}", handle, paused);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn set_invoke_trace_capacity(&self, capacity: usize) {
+        let mut mutex = self.invoke_trace.lock().expect("failed to acquire lock");
+        if capacity == 0 {
+            *mutex = None;
+            return;
+        }
+
+        let trace = mutex.get_or_insert_with(|| InvokeTrace {
+            capacity,
+            records: VecDeque::with_capacity(capacity),
+        });
+        trace.capacity = capacity;
+        while trace.records.len() > capacity {
+            trace.records.pop_front();
+        }
+    }
+
+    fn recent_invokes(&self) -> Vec<InvokeRecord<T>> {
+        self.invoke_trace
+            .lock()
+            .expect("failed to acquire lock")
+            .as_ref()
+            .map(|e| e.records.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn dropped_count(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    fn dropped_count_of(&self, handle: &CallbackHandle) -> Option<u64> {
+        self.lock_callbacks()
+            .get(handle)
+            .map(|entry| entry.dropped_count())
+    }
+
+    fn subscribe_inactive(&self, buffered: bool) -> (CallbackHandle, Subscription<T>) {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut entry = CallbackEntry::new(CallbackSender::Unbounded(tx));
+        if buffered {
+            let capacity = self.pause_buffer_capacity.load(Ordering::Relaxed).max(1);
+            *entry
+                .pause_buffer
+                .get_mut()
+                .expect("failed to acquire lock") = Some(PauseBuffer {
+                capacity,
+                events: VecDeque::new(),
+            });
+            entry.buffering = true;
+        } else {
+            entry.paused = true;
+        }
+        (self.insert_entry(entry), rx)
+    }
+
+    fn activate(&self, handle: &CallbackHandle) -> bool {
+        // a buffering callback isn't paused, so its buffer is flushed atomically by the resume
+        self.set_paused(handle, false) && self.resume(handle)
+    }
+
+    fn pause(&self, handle: &CallbackHandle) -> bool {
+        let mut mutex = self.lock_callbacks();
+        match mutex.get_mut(handle) {
+            Some(entry) => {
+                if !entry.buffering {
+                    let capacity = self.pause_buffer_capacity.load(Ordering::Relaxed).max(1);
+                    *entry.pause_buffer.lock().expect("failed to acquire lock") =
+                        Some(PauseBuffer {
+                            capacity,
+                            events: VecDeque::new(),
+                        });
+                    entry.buffering = true;
+                    trace!(
+                        "Callback {} has been paused with a buffer of {}",
+                        handle,
+                        capacity
+                    );
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn resume(&self, handle: &CallbackHandle) -> bool {
+        let mut mutex = self.lock_callbacks();
+        let Some(entry) = mutex.get_mut(handle) else {
+            return false;
+        };
+
+        entry.buffering = false;
+        let mut exhausted = false;
+        let buffer = entry
+            .pause_buffer
+            .lock()
+            .expect("failed to acquire lock")
+            .take();
+        if let Some(buffer) = buffer {
+            trace!(
+                "Callback {} has been resumed, flushing {} buffered events",
+                handle,
+                buffer.events.len()
+            );
+            // the buffered events are flushed while the callbacks are locked,
+            // so they're delivered before any event which is invoked after the resume
+            for value in buffer.events {
+                match self.invoke_callback_untimed(handle, entry, value) {
+                    Ok(_) if entry.consume_delivery() => {
+                        trace!("Callback {} has reached its delivery limit", handle);
+                        mutex.shift_remove(handle);
+                        self.callbacks_len.store(mutex.len(), Ordering::Relaxed);
+                        exhausted = true;
+                        break;
+                    }
+                    Err(DeliveryFailure::Closed) | Err(DeliveryFailure::Panicked) => break,
+                    _ => {}
+                }
+            }
+        }
+        let count = mutex.len();
+        drop(mutex);
+
+        if exhausted {
+            self.notify_unsubscribed(&[*handle], count);
+        }
+        true
+    }
+
+    fn set_duplicate_policy(&self, policy: DuplicatePolicy) {
+        *self
+            .duplicate_policy
+            .lock()
+            .expect("failed to acquire lock") = policy;
+    }
+
+    fn set_no_subscribers_policy(&self, policy: NoSubscribersPolicy) {
+        *self
+            .no_subscribers_policy
+            .lock()
+            .expect("failed to acquire lock") = policy;
+    }
+
+    fn set_listener(&self, listener: Option<Arc<dyn SubscriptionListener>>) {
+        *self.listener.lock().expect("failed to acquire lock") = listener;
+    }
+
+    /// Inform the subscription listener, if any, about the given added callbacks.
+    /// The count is the number of registered callbacks after all of them have been added.
+    ///
+    /// This may never be called while the callbacks are locked, as the listener might interact with this holder.
+    fn notify_subscribed(&self, handles: &[CallbackHandle], count: usize) {
+        if handles.is_empty() {
+            return;
+        }
+
+        let listener = self
+            .listener
+            .lock()
+            .expect("failed to acquire lock")
+            .clone();
+        if let Some(listener) = listener {
+            let initial = count.saturating_sub(handles.len());
+            for (index, handle) in handles.iter().enumerate() {
+                listener.on_subscribed(*handle, initial + index + 1);
+            }
+        }
+    }
+
+    /// Inform the subscription listener, if any, about the given removed callbacks.
+    /// The count is the number of registered callbacks after all of them have been removed.
+    ///
+    /// This may never be called while the callbacks are locked, as the listener might interact with this holder.
+    fn notify_unsubscribed(&self, handles: &[CallbackHandle], count: usize) {
+        if handles.is_empty() {
+            return;
+        }
+
+        self.unsubscribed.notify_waiters();
+        let listener = self
+            .listener
+            .lock()
+            .expect("failed to acquire lock")
+            .clone();
+        if let Some(listener) = listener {
+            for (index, handle) in handles.iter().enumerate() {
+                listener.on_unsubscribed(*handle, count + handles.len() - index - 1);
+            }
+        }
+    }
+
+    fn has_subscribers(&self) -> bool {
+        if let Some(consumer) = self.single.lock().expect("failed to acquire lock").as_ref() {
+            return !consumer.sender.is_closed();
+        }
+
+        self.lock_callbacks()
+            .values()
+            .any(|entry| !entry.sender.is_closed())
+    }
+
+    async fn closed(&self) {
+        loop {
+            let mut unsubscribed = pin!(self.unsubscribed.notified());
+            unsubscribed.as_mut().enable();
+            if !self.has_subscribers() {
+                return;
+            }
+
+            // dropped subscriptions are only removed when detected, so they're checked periodically as well
+            let _ = tokio::time::timeout(CLOSED_CHECK_INTERVAL, unsubscribed).await;
+        }
+    }
+
+    fn subscriber_count(&self) -> usize {
+        self.callbacks_len.load(Ordering::Relaxed)
+    }
+
+    fn handles(&self) -> Vec<CallbackHandle> {
+        self.lock_callbacks().keys().copied().collect()
+    }
+
+    fn subscriber_info(&self, handle: &CallbackHandle) -> Option<SubscriberInfo> {
+        self.lock_callbacks()
+            .get(handle)
+            .map(|entry| entry.info(*handle, Instant::now()))
+    }
+
+    fn invoke(&self, value: T) -> usize {
+        self.invoke_with(value, true, None)
+    }
+
+    fn invoke_untimed(&self, value: T) -> usize {
+        self.invoke_with(value, false, None)
+    }
+
+    async fn invoke_backpressure(
+        &self,
+        value: T,
+        timeout: Option<Duration>,
+    ) -> Vec<CallbackHandle> {
+        if !self.is_enabled() {
+            trace!("Callback holder is disabled, discarding {:?}", value);
+            return Vec::new();
+        }
+
+        let value = Arc::new(value);
+        let variant_bit = self
+            .variant_bit
+            .get()
+            .map(|variant_bit| variant_bit(&value));
+        // the bounded subscribers which accept backpressure are selected within the regular fan-out, which excludes them,
+        // so a subscriber which is registered concurrently is either awaited or delivered to by the fan-out
+        let bounded: Mutex<Vec<(CallbackHandle, Sender<Arc<T>>)>> = Mutex::new(Vec::new());
+        self.fan_out(
+            value.clone(),
+            true,
+            Some(&|handle: &CallbackHandle, entry: &CallbackEntry<T>| {
+                if !entry.accepts_backpressure(variant_bit) {
+                    return true;
+                }
+                if let CallbackSender::Bounded(sender) = &entry.sender {
+                    bounded
+                        .lock()
+                        .expect("failed to acquire lock")
+                        .push((*handle, sender.clone()));
+                }
+                false
+            }),
+        );
+        self.durable.invoke(&value);
+        let bounded = bounded.into_inner().expect("failed to acquire lock");
+
+        let deadline = timeout.map(|e| tokio::time::Instant::now() + e);
+        let mut skipped = Vec::new();
+        for (handle, sender) in bounded {
+            let send = sender.send(value.clone());
+            let result = match deadline {
+                Some(deadline) => tokio::time::timeout_at(deadline, send).await.ok(),
+                None => Some(send.await),
+            };
+            match result {
+                Some(Ok(_)) => trace!("Callback {} accepted the event", handle),
+                Some(Err(_)) => trace!("Callback {} has been dropped", handle),
+                None => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    if let Some(entry) = self.lock_callbacks().get(&handle) {
+                        entry.dropped.fetch_add(1, Ordering::Relaxed);
+                    }
+                    warn!(
+                        "Callback {} didn't accept the event within {:?}, skipping callback",
+                        handle, timeout
+                    );
+                    skipped.push(handle);
+                }
+            }
+        }
+
+        self.invoke_errors(
+            skipped
+                .iter()
+                .map(|handle| (*handle, DeliveryFailure::Timeout))
+                .collect(),
+            &value,
+        );
+        skipped
+    }
+
+    /// Invoke the callbacks with the given value, awaiting the confirmation of the subscribers which support it.
+    ///
+    /// # Returns
+    ///
+    /// It returns the handles of the subscribers which didn't confirm the value in time.
+    async fn invoke_confirmed(&self, value: T, timeout: Option<Duration>) -> Vec<CallbackHandle> {
+        if !self.is_enabled() {
+            trace!("Callback holder is disabled, discarding {:?}", value);
+            return Vec::new();
+        }
+
+        let value = Arc::new(value);
+        let variant_bit = self
+            .variant_bit
+            .get()
+            .map(|variant_bit| variant_bit(&value));
+        // the subscribers which confirm the value are delivered to within the regular fan-out, which excludes them,
+        // so a subscriber which is registered concurrently is either awaited or delivered to by the fan-out
+        let confirmations: Mutex<Vec<(CallbackHandle, oneshot::Receiver<()>)>> =
+            Mutex::new(Vec::new());
+        self.fan_out(
+            value.clone(),
+            true,
+            Some(&|handle: &CallbackHandle, entry: &CallbackEntry<T>| {
+                if !entry.accepts_confirmation(variant_bit) {
+                    return true;
+                }
+                match entry.sender.send_confirmed(&value) {
+                    Ok(confirmation) => confirmations
+                        .lock()
+                        .expect("failed to acquire lock")
+                        .push((*handle, confirmation)),
+                    Err(_) => trace!("Callback {} has been dropped", handle),
+                }
+                false
+            }),
+        );
+        self.durable.invoke(&value);
+        let confirmations = confirmations.into_inner().expect("failed to acquire lock");
+
+        let deadline = timeout.map(|e| tokio::time::Instant::now() + e);
+        let mut unconfirmed = Vec::new();
+        for (handle, confirmation) in confirmations {
+            let result = match deadline {
+                Some(deadline) => tokio::time::timeout_at(deadline, confirmation).await.ok(),
+                None => Some(confirmation.await),
+            };
+            match result {
+                Some(Ok(_)) => trace!("Callback {} confirmed the event", handle),
+                Some(Err(_)) => {
+                    debug!(
+                        "Callback {} dropped the event without confirming it",
+                        handle
+                    );
+                    unconfirmed.push(handle);
+                }
+                None => {
+                    warn!(
+                        "Callback {} didn't confirm the event within {:?}",
+                        handle, timeout
+                    );
+                    unconfirmed.push(handle);
+                }
+            }
+        }
+        unconfirmed
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    fn set_enabled(&self, enabled: bool) {
+        if self.enabled.swap(enabled, Ordering::Relaxed) != enabled {
+            debug!(
+                "Callback holder {:?} enabled state changed to {}",
+                self, enabled
+            );
+        }
+    }
+
+    fn begin_buffering(&self) {
+        let mut buffer = self.buffer.lock().expect("failed to acquire lock");
+        if buffer.is_none() {
+            *buffer = Some(Vec::new());
+        }
+    }
+
+    /// Stop buffering and take the buffered values.
+    fn take_buffer(&self) -> Vec<Arc<T>> {
+        self.buffer
+            .lock()
+            .expect("failed to acquire lock")
+            .take()
+            .unwrap_or_default()
+    }
+
+    fn flush_buffer(&self) -> usize {
+        let values = self.take_buffer();
+        let total = values.len();
+        for value in values {
+            self.fan_out(value, true, None);
+        }
+        total
+    }
+
+    /// Invoke the callbacks with each of the given values,
+    /// while yielding to the scheduler after every [FAN_OUT_YIELD_INTERVAL] deliveries.
+    /// A yield only happens in between two values, never during the fan-out of a single value.
+    async fn invoke_all_cooperative(&self, values: Vec<T>) {
+        let mut deliveries = 0;
+        for value in values {
+            deliveries += self.invoke_with(value, true, None);
+            if deliveries >= FAN_OUT_YIELD_INTERVAL {
+                deliveries = 0;
+                tokio::task::yield_now().await;
+            }
+        }
+    }
+
+    fn invoke_where_tag(&self, key: &str, tag: &str, value: T) -> usize {
+        self.invoke_with(
+            value,
+            true,
+            Some(&|_, entry: &CallbackEntry<T>| {
+                entry.tags.get(key).map(|e| e == tag).unwrap_or(false)
+            }),
+        )
+    }
+
+    /// Invoke the registered callbacks with the given value.
+    /// The delivery to each callback is only measured when `timed` is `true`.
+    ///
+    /// When a filter is given, the value is only delivered to the callbacks matching the filter,
+    /// and isn't delivered to the durable subscribers.
+    ///
+    /// # Returns
+    ///
+    /// It returns the number of callbacks to which the value has been delivered, which is 0 when the value has been buffered.
+    fn invoke_with(&self, value: T, timed: bool, filter: Option<&EntryFilter<T>>) -> usize {
+        if !self.is_enabled() {
+            trace!("Callback holder is disabled, discarding {:?}", value);
+            return 0;
+        }
+
+        self.invoke_shared_with(Arc::new(value), timed, filter)
+    }
+
+    /// Invoke the registered callbacks with the given shared value.
+    /// See [BaseCallback::invoke_with] for more info.
+    fn invoke_shared_with(
+        &self,
+        value: Arc<T>,
+        timed: bool,
+        filter: Option<&EntryFilter<T>>,
+    ) -> usize {
+        if !self.is_enabled() {
+            trace!("Callback holder is disabled, discarding {:?}", value);
+            return 0;
+        }
+        if filter.is_none() {
+            if let Some(buffer) = self.buffer.lock().expect("failed to acquire lock").as_mut() {
+                trace!("Buffering invocation {:?}", value);
+                buffer.push(value);
+                return 0;
+            }
+        }
+
+        self.fan_out(value, timed, filter)
+    }
+
+    /// Invoke the registered callbacks with the given value, requiring it to be delivered to at least one subscriber.
+    /// The subscribers are checked within the same fan-out as the delivery, based on the configured [NoSubscribersPolicy].
+    ///
+    /// # Returns
+    ///
+    /// It returns the number of callbacks to which the value has been delivered, which is 0 when the value has been buffered,
+    /// or a [NoSubscribersError] when the value hasn't been delivered to any subscriber.
+    fn invoke_required(&self, value: Arc<T>) -> Result<usize, NoSubscribersError<T>> {
+        if !self.is_enabled() {
+            trace!("Callback holder is disabled, discarding {:?}", value);
+            return Err(NoSubscribersError::new(value));
+        }
+        if let Some(buffer) = self.buffer.lock().expect("failed to acquire lock").as_mut() {
+            trace!("Buffering invocation {:?}", value);
+            buffer.push(value);
+            return Ok(0);
+        }
+
+        let policy = *self
+            .no_subscribers_policy
+            .lock()
+            .expect("failed to acquire lock");
+        match self.fan_out_with(value.clone(), true, None, policy) {
+            0 => Err(NoSubscribersError::new(value)),
+            delivered => Ok(delivered),
+        }
+    }
+
+    /// Deliver the given value to the registered callbacks, regardless of the buffering state of this holder.
+    ///
+    /// # Returns
+    ///
+    /// It returns the number of callbacks to which the value has been delivered.
+    fn fan_out(&self, value: Arc<T>, timed: bool, filter: Option<&EntryFilter<T>>) -> usize {
+        self.fan_out_with(value, timed, filter, NoSubscribersPolicy::FanOut)
+    }
+
+    /// Deliver the given value to the registered callbacks, regardless of the buffering state of this holder.
+    /// With [NoSubscribersPolicy::Reject], the value is only observed by the tap, the durable subscribers and the stats
+    /// when it has been delivered to at least one callback.
+    ///
+    /// # Returns
+    ///
+    /// It returns the number of callbacks to which the value has been delivered.
+    fn fan_out_with(
+        &self,
+        value: Arc<T>,
+        timed: bool,
+        filter: Option<&EntryFilter<T>>,
+        policy: NoSubscribersPolicy,
+    ) -> usize {
+        let reject = policy == NoSubscribersPolicy::Reject;
+        if !reject {
+            self.observe(&value);
+        }
+
+        let tracing = self
+            .invoke_trace
+            .lock()
+            .expect("failed to acquire lock")
+            .is_some();
+        if self.single_consumer && filter.is_none() {
+            if let Some(delivered) = self.fan_out_single(&value, timed, tracing, reject) {
+                return delivered;
+            }
+        }
+
+        let mut notified = Vec::new();
+        let mut mutex = self.lock_callbacks();
+
+        trace!(
+            "Invoking a total of {} callbacks for {:?}",
+            mutex.len(),
+            *value
+        );
+
+        let variant_bit = self
+            .variant_bit
+            .get()
+            .map(|variant_bit| variant_bit(&value));
+        let mut expired = Vec::new();
+        let mut closed = Vec::new();
+        let is_eligible = |(handle, entry): &(&CallbackHandle, &CallbackEntry<T>)| {
+            if entry.expires.is_some_and(|e| Instant::now() >= e) {
+                expired.push(**handle);
+                return false;
+            }
+            let skipped = if entry.paused {
+                trace!("Callback {} is paused, skipping invocation", handle);
+                true
+            } else if matches!((entry.variant_mask, variant_bit), (Some(mask), Some(bit)) if mask & bit == 0)
+            {
+                true
+            } else if entry
+                .gate
+                .as_ref()
+                .is_some_and(|gate| !gate.load(Ordering::Acquire))
+            {
+                trace!("Callback {} gate is closed, skipping invocation", handle);
+                true
+            } else {
+                !filter.map(|filter| filter(handle, entry)).unwrap_or(true)
+            };
+            if skipped {
+                // a skipped subscription isn't delivered to, so it would never be detected as closed otherwise
+                if entry.sender.is_closed() {
+                    closed.push(**handle);
+                }
+                return false;
+            }
+            // a closed subscription is still delivered to, so it's removed together with its buffer
+            if entry.buffering && !entry.sender.is_closed() {
+                if let Some(buffer) = entry
+                    .pause_buffer
+                    .lock()
+                    .expect("failed to acquire lock")
+                    .as_mut()
+                {
+                    if buffer.push(value.clone()) {
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        entry.dropped.fetch_add(1, Ordering::Relaxed);
+                        trace!(
+                            "Callback {} pause buffer is full, evicted the oldest event",
+                            handle
+                        );
+                    }
+                    return false;
+                }
+            }
+            true
+        };
+        let mut exhausted = Vec::new();
+        let deliver = |(handle, entry): (&CallbackHandle, &CallbackEntry<T>)| {
+            let result = if timed {
+                self.invoke_callback(handle, entry, value.clone())
+            } else {
+                self.invoke_callback_untimed(handle, entry, value.clone())
+            };
+            if result.is_ok() && entry.consume_delivery() {
+                exhausted.push(*handle);
+            }
+            result.err().map(|reason| (*handle, reason))
+        };
+
+        let mut total_entries = 0;
+        let failures: Vec<(CallbackHandle, DeliveryFailure)> = if mutex.len() == 1 {
+            // fast path for the common single subscriber case, which doesn't need to be ordered
+            mutex
+                .iter()
+                .filter(is_eligible)
+                .inspect(|(handle, _)| {
+                    total_entries += 1;
+                    if tracing {
+                        notified.push(**handle);
+                    }
+                })
+                .filter_map(deliver)
+                .collect()
+        } else {
+            let mut entries: Vec<(&CallbackHandle, &CallbackEntry<T>)> =
+                mutex.iter().filter(is_eligible).collect();
+            entries.sort_by_key(|(_, entry)| Reverse(entry.priority));
+            total_entries = entries.len();
+            if tracing {
+                notified = entries.iter().map(|(handle, _)| **handle).collect();
+            }
+            entries.into_iter().filter_map(deliver).collect()
+        };
+
+        let mut removed = Vec::new();
+        for (handle, reason) in failures.iter() {
+            if matches!(reason, DeliveryFailure::Closed | DeliveryFailure::Panicked) {
+                if let Some(entry) = mutex.shift_remove(handle) {
+                    trace!("Removed callback {}", entry.display(handle));
+                    removed.push(*handle);
+                }
+            }
+        }
+        for handle in closed {
+            if let Some(entry) = mutex.shift_remove(&handle) {
+                trace!("Removed callback {}", entry.display(&handle));
+                removed.push(handle);
+            }
+        }
+        for handle in exhausted {
+            if let Some(entry) = mutex.shift_remove(&handle) {
+                trace!(
+                    "Callback {} has reached its delivery limit",
+                    entry.display(&handle)
+                );
+                removed.push(handle);
+            }
+        }
+        for handle in expired {
+            if let Some(entry) = mutex.shift_remove(&handle) {
+                trace!("Callback {} has expired", entry.display(&handle));
+                removed.push(handle);
+            }
+        }
+        let count = mutex.len();
+        self.callbacks_len.store(count, Ordering::Relaxed);
+
+        if !removed.is_empty() {
+            debug!("Removed a total of {} callbacks", removed.len());
+        }
+        drop(mutex);
+        self.notify_unsubscribed(&removed, count);
+
+        // the subscribers to which the value couldn't be delivered haven't been notified
+        notified.retain(|handle| !failures.iter().any(|(e, _)| e == handle));
+        let delivered = total_entries - failures.len();
+        self.invoke_errors(failures, &value);
+        if reject {
+            if delivered == 0 {
+                trace!("Rejected {:?}, no subscriber received it", value);
+                return 0;
+            }
+            self.observe(&value);
+        }
+        if filter.is_none() {
+            self.durable.invoke(&value);
+        }
+
+        self.log_strong_count(&value);
+
+        if tracing {
+            if let Some(trace) = self
+                .invoke_trace
+                .lock()
+                .expect("failed to acquire lock")
+                .as_mut()
+            {
+                trace.record(value, notified);
+            }
+        }
+
+        delivered
+    }
+
+    /// Log the [Arc::strong_count] of the given value after its fan-out, when the strong count tracing is enabled.
+    ///
+    /// # Returns
+    ///
+    /// It returns the logged count, which excludes the reference held by the fan-out,
+    /// or [None] when the strong count tracing is disabled.
+    fn log_strong_count(&self, value: &Arc<T>) -> Option<usize> {
+        if !self.trace_strong_count.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        // exclude the reference which is held by this fan-out
+        let count = Arc::strong_count(value) - 1;
+        trace!(
+            "Event {:?} is still referenced {} times after the fan-out",
+            value,
+            count
+        );
+        Some(count)
+    }
+
+    /// Record the invocation of the given value and inform the tap of this holder about it.
+    fn observe(&self, value: &Arc<T>) {
+        self.stats.record_invocation();
+        let tap = self.tap.lock().expect("failed to acquire lock").clone();
+        if let Some(tap) = tap {
+            tap(value);
+        }
+    }
+
+    /// Deliver the given value to the single consumer of this holder, without locking the callbacks.
+    ///
+    /// # Returns
+    ///
+    /// It returns the number of consumers to which the value has been delivered,
+    /// or [None] when the single consumer isn't kept outside of the callbacks.
+    fn fan_out_single(
+        &self,
+        value: &Arc<T>,
+        timed: bool,
+        tracing: bool,
+        reject: bool,
+    ) -> Option<usize> {
+        let mut single = self.single.lock().expect("failed to acquire lock");
+        let consumer = single.as_ref()?;
+        let handle = consumer.handle;
+
+        trace!("Invoking single consumer {} for {:?}", handle, **value);
+        let start_time = timed.then(Instant::now);
+        let delivered = consumer.sender.send(value.clone()).is_ok();
+        match start_time {
+            Some(start_time) => self.stats.record_delivery(start_time.elapsed()),
+            None => self.stats.record_untimed_delivery(),
+        }
+        if !delivered {
+            *single = None;
+            self.callbacks_len.store(0, Ordering::Relaxed);
+        }
+        drop(single);
+
+        if !delivered {
+            trace!("Removed single consumer {}", handle);
+            self.notify_unsubscribed(&[handle], 0);
+            self.invoke_errors(vec![(handle, DeliveryFailure::Closed)], value);
+        }
+        if reject {
+            if !delivered {
+                trace!("Rejected {:?}, no subscriber received it", value);
+                return Some(0);
+            }
+            self.observe(value);
+        }
+        self.durable.invoke(value);
+
+        self.log_strong_count(value);
+        if tracing {
+            if let Some(trace) = self
+                .invoke_trace
+                .lock()
+                .expect("failed to acquire lock")
+                .as_mut()
+            {
+                let notified = if delivered { vec![handle] } else { Vec::new() };
+                trace.record(value.clone(), notified);
+            }
+        }
+
+        Some(delivered as usize)
+    }
+
+    /// Invoke the currently registered callbacks with their own value created by the given factory.
+    ///
+    /// The policies of the fan-out apply to each callback, being its expiry, gate, variant mask, delivery limit and priority.
+    /// Paused callbacks are skipped, including the ones which buffer their events, as they wouldn't receive the value in time.
+    /// A failed delivery is reported on the error subscriptions, but the value isn't offered to the durable subscribers nor the tap.
+    ///
+    /// # Returns
+    ///
+    /// It returns the handles of the callbacks to which the value has been offered, in the order of delivery.
+    /// A value created for a callback which doesn't accept its variant is discarded and the callback isn't included.
+    fn invoke_each<F>(&self, mut factory: F) -> Vec<CallbackHandle>
+    where
+        F: FnMut(&CallbackHandle) -> T,
+    {
+        self.stats.record_invocation();
+        let mut mutex = self.lock_callbacks();
+        let mut failures = Vec::new();
+        let mut offered = Vec::new();
+        let mut expired = Vec::new();
+        let mut closed = Vec::new();
+        let mut exhausted = Vec::new();
+
+        trace!("Invoking a total of {} callbacks individually", mutex.len());
+        let now = Instant::now();
+        let mut entries: Vec<(&CallbackHandle, &CallbackEntry<T>)> = mutex
+            .iter()
+            .filter(|(handle, entry)| {
+                if entry.expires.is_some_and(|e| now >= e) {
+                    expired.push(**handle);
+                    return false;
+                }
+                let skipped = entry.paused
+                    || entry.buffering
+                    || entry
+                        .gate
+                        .as_ref()
+                        .is_some_and(|gate| !gate.load(Ordering::Acquire));
+                if skipped && entry.sender.is_closed() {
+                    closed.push(**handle);
+                }
+                !skipped
+            })
+            .collect();
+        entries.sort_by_key(|(_, entry)| Reverse(entry.priority));
+        for (handle, entry) in entries {
+            let value = Arc::new(factory(handle));
+            let variant_bit = self
+                .variant_bit
+                .get()
+                .map(|variant_bit| variant_bit(&value));
+            if let (Some(mask), Some(bit)) = (entry.variant_mask, variant_bit) {
+                if mask & bit == 0 {
+                    continue;
+                }
+            }
+
+            offered.push(*handle);
+            match self.invoke_callback(handle, entry, value.clone()) {
+                Ok(_) => {
+                    if entry.consume_delivery() {
+                        exhausted.push(*handle);
+                    }
+                }
+                Err(reason) => failures.push((*handle, reason, value)),
+            }
+        }
+
+        let mut removed = Vec::new();
+        for (handle, reason, _) in failures.iter() {
+            if matches!(reason, DeliveryFailure::Closed | DeliveryFailure::Panicked)
+                && mutex.shift_remove(handle).is_some()
+            {
+                removed.push(*handle);
+            }
+        }
+        for handle in closed.into_iter().chain(exhausted).chain(expired) {
+            if mutex.shift_remove(&handle).is_some() {
+                removed.push(handle);
+            }
+        }
+        let count = mutex.len();
+        self.callbacks_len.store(count, Ordering::Relaxed);
+        drop(mutex);
+        self.notify_unsubscribed(&removed, count);
+
+        for (handle, reason, value) in failures {
+            self.invoke_errors(vec![(handle, reason)], &value);
+        }
+        offered
+    }
+
+    /// Try to invoke the callback for the given value and measure the time it took.
+    /// This is a convenience method for handling dropped callbacks.
+    ///
+    /// # Returns
+    ///
+    /// It returns the reason of the failure when the value couldn't be delivered to the callback.
+    fn invoke_callback(
+        &self,
+        handle: &CallbackHandle,
+        entry: &CallbackEntry<T>,
+        value: Arc<T>,
+    ) -> Result<(), DeliveryFailure> {
+        let start_time = Instant::now();
+        self.send_callback(handle, entry, value)?;
+        let elapsed = start_time.elapsed();
+        self.stats.record_delivery(elapsed);
+        let message = format!(
+            "Callback {} took {}.{:03}ms to process the invocation",
+            handle,
+            elapsed.as_millis(),
+            elapsed.subsec_micros() % 1000
+        );
+        if elapsed >= SLOW_CALLBACK_THRESHOLD
+            && entry.allow_slow_warning(
+                Instant::now(),
+                Duration::from_nanos(self.slow_warning_interval.load(Ordering::Relaxed)),
+            )
+        {
+            warn!("{}", message);
+        } else {
+            trace!("{}", message);
+        }
+
+        Ok(())
+    }
+
+    /// Try to invoke the callback for the given value without measuring the time it took.
+    ///
+    /// # Returns
+    ///
+    /// It returns the reason of the failure when the value couldn't be delivered to the callback.
+    fn invoke_callback_untimed(
+        &self,
+        handle: &CallbackHandle,
+        entry: &CallbackEntry<T>,
+        value: Arc<T>,
+    ) -> Result<(), DeliveryFailure> {
+        self.send_callback(handle, entry, value)?;
+        self.stats.record_untimed_delivery();
+        Ok(())
+    }
+
+    fn send_callback(
+        &self,
+        handle: &CallbackHandle,
+        entry: &CallbackEntry<T>,
+        value: Arc<T>,
+    ) -> Result<(), DeliveryFailure> {
+        let evicted = entry.sender.dropped_count();
+        match entry.sender.send(value) {
+            Ok(_) => {
+                // a sender which evicts its oldest event accepts the value, but the evicted event has been dropped
+                let evicted = entry.sender.dropped_count() - evicted;
+                if evicted > 0 {
+                    self.dropped.fetch_add(evicted, Ordering::Relaxed);
+                }
+                Ok(())
+            }
+            Err(DeliveryFailure::Full) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                let dropped = entry.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                if entry.allow_drop_warning(Instant::now()) {
+                    warn!(
+                        "Callback {} queue is full, dropped a total of {} events",
+                        entry.display(handle),
+                        dropped
+                    );
+                } else {
+                    trace!("Callback {} queue is full, event has been dropped", handle);
+                }
+                Err(DeliveryFailure::Full)
+            }
+            Err(DeliveryFailure::Panicked) => {
+                error!(
+                    "Callback {} panicked while handling the event, removing callback",
+                    handle
+                );
+                Err(DeliveryFailure::Panicked)
+            }
+            Err(e) => {
+                trace!("Callback {} has been dropped", handle);
+                Err(e)
+            }
+        }
+    }
+
+    fn error_subscription(&self) -> Subscription<DeliveryError<T>> {
+        let mut mutex = self.errors.lock().expect("failed to acquire lock");
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        mutex.push(tx);
+        rx
+    }
+
+    /// Inform the error subscriptions about the given delivery failures of the value.
+    fn invoke_errors(&self, failures: Vec<(CallbackHandle, DeliveryFailure)>, value: &Arc<T>) {
+        if failures.is_empty() {
+            return;
+        }
+
+        let mut mutex = self.errors.lock().expect("failed to acquire lock");
+        for (handle, reason) in failures {
+            let error = Arc::new(DeliveryError {
+                handle,
+                reason,
+                event: Some(value.clone()),
+            });
+            mutex.retain(|e| e.send(error.clone()).is_ok());
+        }
+    }
+}
+
+impl<T> Drop for BaseCallback<T>
+where
+    T: Debug + Send + Sync,
+{
+    fn drop(&mut self) {
+        // discard any buffered events, so the drop event is always delivered to the subscribers
+        let _ = self.take_buffer();
+        let drop_event = match self.drop_event.get_mut() {
+            Ok(e) => e.take(),
+            Err(e) => e.into_inner().take(),
+        };
+
+        if let Some(value_fn) = drop_event {
+            trace!("Invoking drop event for {:?}", self);
+            self.invoke(value_fn());
+        }
+    }
+}
+
+impl<T> Debug for BaseCallback<T>
+where
+    T: Debug + Send + Sync,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mutex = self.callbacks.lock().unwrap();
+        let names: Vec<&str> = mutex.values().filter_map(|e| e.name.as_deref()).collect();
+        f.debug_struct("BaseCallback")
+            .field("callbacks", &self.subscriber_count())
+            .field("names", &names)
+            .finish()
+    }
+}
+
+/// The bounded trace of the most recent invocations of a callback holder.
+struct InvokeTrace<T> {
+    capacity: usize,
+    records: VecDeque<InvokeRecord<T>>,
+}
+
+impl<T> InvokeTrace<T> {
+    /// Record the invocation of the given value, evicting the oldest record when the capacity has been reached.
+    fn record(&mut self, value: Arc<T>, notified: Vec<CallbackHandle>) {
+        if self.records.len() >= self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back((Instant::now(), value, notified));
+    }
+}
+
+/// The tap which observes each invoked event of a callback holder.
+type EventTap<T> = dyn Fn(&Arc<T>) + Send + Sync;
+
+/// The filter of the callback entries which should be invoked.
+type EntryFilter<'a, T> = dyn Fn(&CallbackHandle, &CallbackEntry<T>) -> bool + 'a;
+
+/// The only consumer of a single consumer holder, which is delivered to without locking the callbacks.
+struct SingleConsumer<T> {
+    handle: CallbackHandle,
+    sender: UnboundedSender<Arc<T>>,
+}
+
+/// The registered callback information of a subscriber.
+struct CallbackEntry<T> {
+    sender: CallbackSender<T>,
+    name: Option<String>,
+    created: Instant,
+    paused: bool,
+    tags: HashMap<String, String>,
+    priority: i32,
+    variant_mask: Option<u64>,
+    gate: Option<Arc<AtomicBool>>,
+    /// The buffer of a paused subscriber, which is only locked while `buffering` is set.
+    pause_buffer: Mutex<Option<PauseBuffer<T>>>,
+    buffering: bool,
+    /// The remaining number of deliveries after which the callback is removed, if limited.
+    remaining: Option<AtomicUsize>,
+    /// The moment after which the callback is removed, if limited.
+    expires: Option<Instant>,
+    last_slow_warning: Mutex<Option<Instant>>,
+    last_drop_warning: Mutex<Option<Instant>>,
+    /// The result of the last liveness probe of the callback, if probed.
+    liveness: Mutex<Option<LivenessStatus>>,
+    /// The number of events which have been dropped for the callback.
+    dropped: AtomicU64,
+}
+
+impl<T> CallbackEntry<T> {
+    fn new(sender: CallbackSender<T>) -> Self {
+        Self {
+            sender,
+            name: None,
+            created: Instant::now(),
+            paused: false,
+            tags: HashMap::new(),
+            priority: 0,
+            variant_mask: None,
+            gate: None,
+            pause_buffer: Mutex::new(None),
+            buffering: false,
+            remaining: None,
+            expires: None,
+            last_slow_warning: Mutex::new(None),
+            last_drop_warning: Mutex::new(None),
+            liveness: Mutex::new(None),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Check if this entry is a bounded subscription which is eligible for the given event and accepts backpressure,
+    /// meaning that the invoker can wait for it to accept the event.
+    fn accepts_backpressure(&self, variant_bit: Option<u64>) -> bool {
+        matches!(self.sender, CallbackSender::Bounded(_)) && self.is_awaitable(variant_bit)
+    }
+
+    /// Check if this entry is eligible for the given event and confirms the processing of it,
+    /// meaning that the invoker can wait for it to confirm the event.
+    fn accepts_confirmation(&self, variant_bit: Option<u64>) -> bool {
+        self.sender.is_confirmable() && self.is_awaitable(variant_bit)
+    }
+
+    /// Check if this entry is eligible for the given event when it's delivered outside of the regular fan-out.
+    fn is_awaitable(&self, variant_bit: Option<u64>) -> bool {
+        if self.paused || self.buffering {
+            return false;
+        }
+        if self.expires.is_some_and(|e| Instant::now() >= e) {
+            return false;
+        }
+        if let (Some(mask), Some(bit)) = (self.variant_mask, variant_bit) {
+            if mask & bit == 0 {
+                return false;
+            }
+        }
+        self.gate
+            .as_ref()
+            .map_or(true, |gate| gate.load(Ordering::Acquire))
+    }
+
+    /// Get the number of events which have been dropped for this entry, including the events which have been dropped by its sender.
+    fn dropped_count(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed) + self.sender.dropped_count()
+    }
+
+    /// Get the displayable identity of this entry, which includes its name when present.
+    fn display<'a>(&'a self, handle: &'a CallbackHandle) -> CallbackName<'a> {
+        CallbackName::new(handle, self.name.as_deref())
+    }
+
+    /// Consume one of the remaining deliveries of this entry, when its deliveries are limited.
+    ///
+    /// # Returns
+    ///
+    /// It returns `true` when the last remaining delivery has been consumed and the entry should be removed.
+    fn consume_delivery(&self) -> bool {
+        self.remaining
+            .as_ref()
+            .is_some_and(|e| e.fetch_sub(1, Ordering::Relaxed) == 1)
+    }
+
+    /// Check if a slow callback warning may be logged for this entry at the given moment.
+    /// When allowed, the moment is recorded as the last warning of this entry.
+    fn allow_slow_warning(&self, now: Instant, interval: Duration) -> bool {
+        Self::allow_warning(&self.last_slow_warning, now, interval)
+    }
+
+    /// Check if a dropped event warning may be logged for this entry at the given moment.
+    /// The first dropped event is always logged, after which the warnings are limited to one per [DROP_WARNING_INTERVAL].
+    fn allow_drop_warning(&self, now: Instant) -> bool {
+        Self::allow_warning(&self.last_drop_warning, now, DROP_WARNING_INTERVAL)
+    }
+
+    fn allow_warning(
+        last_warning: &Mutex<Option<Instant>>,
+        now: Instant,
+        interval: Duration,
+    ) -> bool {
+        let mut last_warning = last_warning.lock().expect("failed to acquire lock");
+        let allowed = last_warning
+            .map(|e| now.saturating_duration_since(e) >= interval)
+            .unwrap_or(true);
+        if allowed {
+            *last_warning = Some(now);
+        }
+        allowed
+    }
+
+    /// Create the subscriber information of this entry.
+    fn info(&self, handle: CallbackHandle, now: Instant) -> SubscriberInfo {
+        SubscriberInfo {
+            handle,
+            name: self.name.clone(),
+            created: self.created,
+            age: now.saturating_duration_since(self.created),
+            queue_depth: self.sender.queue_depth(),
+            paused: self.paused,
+            closed: self.sender.is_closed(),
+            liveness: *self.liveness.lock().expect("failed to acquire lock"),
+            dropped: self.dropped_count(),
+        }
+    }
+}
+
+/// The displayable identity of a callback within the logs, being its handle and optional name.
+struct CallbackName<'a> {
+    handle: &'a CallbackHandle,
+    name: Option<&'a str>,
+}
+
+impl<'a> CallbackName<'a> {
+    fn new(handle: &'a CallbackHandle, name: Option<&'a str>) -> Self {
+        Self { handle, name }
+    }
+}
+
+impl Display for CallbackName<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.name {
+            Some(name) => write!(f, "{} ({})", self.handle, name),
+            None => write!(f, "{}", self.handle),
+        }
+    }
+}
+
+/// The bounded buffer of the events which have been invoked while a subscriber was paused.
+struct PauseBuffer<T> {
+    capacity: usize,
+    events: VecDeque<Arc<T>>,
+}
+
+impl<T> PauseBuffer<T> {
+    /// Buffer the given event, evicting the oldest event when the capacity has been reached.
+    ///
+    /// # Returns
+    ///
+    /// It returns `true` when an event has been evicted from the buffer.
+    fn push(&mut self, value: Arc<T>) -> bool {
+        let evicted = self.events.len() >= self.capacity;
+        if evicted {
+            self.events.pop_front();
+        }
+        self.events.push_back(value);
+        evicted
+    }
+}
+
+/// The underlying sender of a registered callback.
+enum CallbackSender<T> {
+    Unbounded(UnboundedSender<Arc<T>>),
+    Bounded(Sender<Arc<T>>),
+    Closure(Box<dyn Fn(Arc<T>) + Send + Sync>),
+    Forward(Box<dyn ForwardSender<T>>),
+}
+
+impl<T> CallbackSender<T> {
+    /// Get the number of events which are queued for the callback, if known.
+    fn queue_depth(&self) -> Option<usize> {
+        match self {
+            CallbackSender::Bounded(sender) => Some(sender.max_capacity() - sender.capacity()),
+            CallbackSender::Forward(sender) => sender.queue_depth(),
+            _ => None,
+        }
+    }
+
+    /// Check if the given callback sends to the same channel as this callback.
+    /// Closures and forwarding callbacks are never considered to be the same.
+    fn same_channel(&self, other: &CallbackSender<T>) -> bool {
+        match (self, other) {
+            (CallbackSender::Unbounded(sender), CallbackSender::Unbounded(other)) => {
+                sender.same_channel(other)
+            }
+            (CallbackSender::Bounded(sender), CallbackSender::Bounded(other)) => {
+                sender.same_channel(other)
+            }
+            _ => false,
+        }
+    }
+
+    /// Get the number of events which have been dropped by the sender of the callback itself.
+    fn dropped_count(&self) -> u64 {
+        match self {
+            CallbackSender::Forward(sender) => sender.dropped_count(),
+            _ => 0,
+        }
+    }
+
+    /// Send a liveness probe to the callback, if supported.
+    fn probe(&self) -> Option<oneshot::Receiver<()>> {
+        match self {
+            CallbackSender::Forward(sender) => sender.probe(),
+            _ => None,
+        }
+    }
+
+    /// Check if the callback confirms the processing of the values, see [ForwardSender::send_confirmed].
+    fn is_confirmable(&self) -> bool {
+        match self {
+            CallbackSender::Forward(sender) => sender.is_confirmable(),
+            _ => false,
+        }
+    }
+
+    /// Send the given value to the callback, together with a confirmation of the processing of it.
+    fn send_confirmed(&self, value: &Arc<T>) -> Result<oneshot::Receiver<()>, DeliveryFailure> {
+        match self {
+            CallbackSender::Forward(sender) => sender.send_confirmed(value),
+            _ => {
+                self.send(value.clone())?;
+                let (tx, rx) = oneshot::channel();
+                let _ = tx.send(());
+                Ok(rx)
+            }
+        }
+    }
+
+    /// Check if the receiver of the callback has been dropped.
+    fn is_closed(&self) -> bool {
+        match self {
+            CallbackSender::Unbounded(sender) => sender.is_closed(),
+            CallbackSender::Bounded(sender) => sender.is_closed(),
+            CallbackSender::Closure(_) => false,
+            CallbackSender::Forward(sender) => sender.is_closed(),
+        }
+    }
+
+    /// Try to send the given value to the callback without blocking.
+    fn send(&self, value: Arc<T>) -> Result<(), DeliveryFailure> {
+        match self {
+            CallbackSender::Unbounded(sender) => {
+                sender.send(value).map_err(|_| DeliveryFailure::Closed)
+            }
+            CallbackSender::Bounded(sender) => sender.try_send(value).map_err(|e| match e {
+                TrySendError::Full(_) => DeliveryFailure::Full,
+                TrySendError::Closed(_) => DeliveryFailure::Closed,
+            }),
+            CallbackSender::Closure(closure) => {
+                panic::catch_unwind(AssertUnwindSafe(|| closure(value)))
+                    .map_err(|_| DeliveryFailure::Panicked)
+            }
+            CallbackSender::Forward(sender) => sender.send(&value),
+        }
+    }
+}
+
+/// A sender which forwards a value derived from the shared event to its receiver.
+/// The sender is type erased so the requirements of the derived value only apply when subscribing with such a sender.
+pub(crate) trait ForwardSender<T>: Send + Sync {
+    /// Send the value derived from the given event to the receiver.
+    fn send(&self, value: &Arc<T>) -> Result<(), DeliveryFailure>;
+
+    /// Check if the receiver has been dropped.
+    fn is_closed(&self) -> bool;
+
+    /// Get the number of values which are queued for the receiver, if known.
+    fn queue_depth(&self) -> Option<usize> {
+        None
+    }
+
+    /// Get the number of values which have been dropped by this sender, while still being delivered successfully.
+    fn dropped_count(&self) -> u64 {
+        0
+    }
+
+    /// Send a liveness probe to the receiver, which is answered once the receiver has received all values before it.
+    ///
+    /// # Returns
+    ///
+    /// It returns the receiver of the answer, or [None] when the receiver doesn't support liveness probes.
+    fn probe(&self) -> Option<oneshot::Receiver<()>> {
+        None
+    }
+
+    /// Check if the receiver confirms the processing of the values, see [ForwardSender::send_confirmed].
+    fn is_confirmable(&self) -> bool {
+        false
+    }
+
+    /// Send the value derived from the given event to the receiver, together with a confirmation
+    /// which is completed once the receiver has processed the value.
+    ///
+    /// # Returns
+    ///
+    /// It returns the receiver of the confirmation, which is completed on delivery when the receiver doesn't confirm its processing.
+    fn send_confirmed(&self, value: &Arc<T>) -> Result<oneshot::Receiver<()>, DeliveryFailure> {
+        self.send(value)?;
+        let (tx, rx) = oneshot::channel();
+        let _ = tx.send(());
+        Ok(rx)
+    }
+}
+
+/// Forwards an owned clone of the shared event.
+impl<T> ForwardSender<T> for UnboundedSender<T>
+where
+    T: Clone + Send,
+{
+    fn send(&self, value: &Arc<T>) -> Result<(), DeliveryFailure> {
+        UnboundedSender::send(self, (**value).clone()).map_err(|_| DeliveryFailure::Closed)
+    }
+
+    fn is_closed(&self) -> bool {
+        UnboundedSender::is_closed(self)
+    }
+}
+
+/// Forwards each value produced by the flat map function of the shared event as a separate event.
+struct FlatMapSender<U, F> {
+    sender: UnboundedSender<Arc<U>>,
+    function: F,
+}
+
+impl<T, U, I, F> ForwardSender<T> for FlatMapSender<U, F>
+where
+    U: Send + Sync,
+    I: IntoIterator<Item = U>,
+    F: Fn(Arc<T>) -> I + Send + Sync,
+{
+    fn send(&self, value: &Arc<T>) -> Result<(), DeliveryFailure> {
+        let values = panic::catch_unwind(AssertUnwindSafe(|| {
+            (self.function)(value.clone())
+                .into_iter()
+                .collect::<Vec<_>>()
+        }))
+        .map_err(|_| DeliveryFailure::Panicked)?;
+
+        for value in values {
+            self.sender
+                .send(Arc::new(value))
+                .map_err(|_| DeliveryFailure::Closed)?;
+        }
+        Ok(())
+    }
+
+    fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+}
+
+/// Forwards the first shared event to a oneshot receiver, after which the sender is closed.
+struct OnceSender<T> {
+    sender: Mutex<Option<oneshot::Sender<Arc<T>>>>,
+}
+
+impl<T> ForwardSender<T> for OnceSender<T>
+where
+    T: Send + Sync,
+{
+    fn send(&self, value: &Arc<T>) -> Result<(), DeliveryFailure> {
+        self.sender
+            .lock()
+            .expect("failed to acquire lock")
+            .take()
+            .ok_or(DeliveryFailure::Closed)?
+            .send(value.clone())
+            .map_err(|_| DeliveryFailure::Closed)
+    }
+
+    fn is_closed(&self) -> bool {
+        self.sender
+            .lock()
+            .expect("failed to acquire lock")
+            .as_ref()
+            .map_or(true, |e| e.is_closed())
+    }
+}
+
+/// The information of a subscriber of a callback holder.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubscriberInfo {
+    /// The handle of the subscriber.
+    pub handle: CallbackHandle,
+    /// The name of the subscriber, if any.
+    pub name: Option<String>,
+    /// The moment the subscriber has been registered.
+    pub created: Instant,
+    /// The time since the subscriber has been registered.
+    pub age: Duration,
+    /// The number of events which are queued for the subscriber.
+    /// This is only known for bounded and adaptive subscriptions.
+    pub queue_depth: Option<usize>,
+    /// Indicates if the delivery of events to the subscriber has been paused.
+    pub paused: bool,
+    /// Indicates if the subscription has been dropped, but not yet removed from the callback holder.
+    pub closed: bool,
+    /// The result of the last liveness probe of the subscriber, if probed.
+    /// Only acked subscriptions can be probed.
+    pub liveness: Option<LivenessStatus>,
+    /// The number of events which have been dropped for the subscriber,
+    /// because its bounded queue or its pause buffer was full.
+    pub dropped: u64,
+}
+
+/// The reason why an event couldn't be delivered to a subscriber.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeliveryFailure {
+    /// The subscription has been dropped.
+    Closed,
+    /// The queue of the bounded subscription is full.
+    Full,
+    /// The subscriber didn't accept the event in time.
+    Timeout,
+    /// The closure of the subscriber panicked while handling the event.
+    Panicked,
+}
+
+/// The error which is published on the error subscriptions of a callback holder
+/// when an event couldn't be delivered to one of its subscribers.
+#[derive(Debug)]
+pub struct DeliveryError<T> {
+    /// The handle of the subscriber to which the event couldn't be delivered.
+    pub handle: CallbackHandle,
+    /// The reason why the event couldn't be delivered.
+    pub reason: DeliveryFailure,
+    /// The event that couldn't be delivered, if available.
+    pub event: Option<Arc<T>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::init_logger;
+    use std::sync::mpsc::channel;
+    use std::time::Duration;
+    use tokio::{select, time};
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Event {
+        Foo,
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum NoneCloneEvent {
+        Bar,
+    }
+
+    #[tokio::test]
+    async fn test_multi_threaded_invoke() {
+        init_logger!();
+        let expected_result = Event::Foo;
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let callback = MultiThreadedCallback::<Event>::new();
+
+        let mut receiver = callback.subscribe();
+        tokio::spawn(async move {
+            if let Some(e) = receiver.recv().await {
+                let _ = tx.send(e).await;
+            }
+        });
+
+        callback.invoke(expected_result.clone());
+        let result = select! {
+            _ = time::sleep(Duration::from_millis(150)) => {
+                panic!("Callback invocation receiver timed out")
+            },
+            Some(result) = rx.recv() => result,
+        };
+
+        assert_eq!(expected_result, *result);
+    }
+
+    #[test]
+    fn test_multi_threaded_invoke_without_runtime() {
+        init_logger!();
+        let expected_result = Event::Foo;
+        let (tx, rx) = channel();
+        let runtime = Runtime::new().unwrap();
+        let callback = MultiThreadedCallback::<Event>::new();
+
+        let mut receiver = callback.subscribe();
+        runtime.spawn(async move {
+            if let Some(e) = receiver.recv().await {
+                tx.send(e).unwrap();
+            }
+        });
+
+        callback.invoke(expected_result.clone());
+        let result = rx.recv_timeout(Duration::from_millis(50)).unwrap();
+
+        assert_eq!(expected_result, *result);
+    }
+
+    #[tokio::test]
+    async fn test_invoke_dropped_receiver() {
+        init_logger!();
+        let expected_result = Event::Foo;
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let callback = MultiThreadedCallback::<Event>::new();
+
+        let _ = callback.subscribe();
+        let mut receiver = callback.subscribe();
+        tokio::spawn(async move {
+            if let Some(e) = receiver.recv().await {
+                let _ = tx.send(e).await;
+            }
+        });
+
+        callback.invoke(expected_result.clone());
+        let result = select! {
+            _ = time::sleep(Duration::from_millis(150)) => {
+                panic!("Callback invocation receiver timed out")
+            },
+            Some(result) = rx.recv() => result,
+        };
+
+        assert_eq!(expected_result, *result);
+    }
+
+    #[tokio::test]
+    async fn test_non_cloneable_type() {
+        init_logger!();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let callback = MultiThreadedCallback::<NoneCloneEvent>::new();
+
+        let mut receiver = callback.subscribe();
+        tokio::spawn(async move {
+            if let Some(e) = receiver.recv().await {
+                let _ = tx.send(e).await;
+            }
+        });
+
+        callback.invoke(NoneCloneEvent::Bar);
+        let result = select! {
+            _ = time::sleep(Duration::from_millis(150)) => {
+                panic!("Callback invocation receiver timed out")
+            },
+            Some(result) = rx.recv() => result,
+        };
+
+        assert_eq!(NoneCloneEvent::Bar, *result);
+    }
+
+    #[test]
+    fn test_single_threaded_invoke() {
+        init_logger!();
+        let expected_result = Event::Foo;
+        let runtime = Runtime::new().unwrap();
+        let (tx, rx) = channel();
+        let callback = SingleThreadedCallback::new();
+
+        let mut receiver = callback.subscribe();
+        runtime.spawn(async move {
+            if let Some(e) = receiver.recv().await {
+                tx.send(e).unwrap();
+            }
+        });
+
+        callback.invoke(expected_result.clone());
+        let result = rx.recv_timeout(Duration::from_millis(50)).unwrap();
+
+        assert_eq!(expected_result, *result);
+    }
+
+    #[test]
+    fn test_bounded_dropped_count() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<Event>::new();
+
+        let mut receiver = callback.subscribe_bounded(1);
+        callback.invoke(Event::Foo);
+        callback.invoke(Event::Foo);
+        callback.invoke(Event::Foo);
+
+        assert_eq!(2, callback.dropped_count());
+        assert_eq!(Event::Foo, *receiver.try_recv().unwrap());
+        assert!(
+            receiver.try_recv().is_err(),
+            "expected the other events to have been dropped"
+        );
+    }
+
+    #[test]
+    fn test_overflow_dropped_count() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let _subscription = callback.subscribe_overflow(1, OverflowPolicy::DropOldest);
+        let handle = callback.describe_subscribers()[0].handle;
+        for i in 0..3 {
+            callback.invoke(i);
+        }
+
+        assert_eq!(2, callback.dropped_count());
+        assert_eq!(Some(2), callback.dropped_count_of(&handle));
+    }
+
+    #[test]
+    fn test_bounded_dropped_count_closed_subscription() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<Event>::new();
+
+        let receiver = callback.subscribe_bounded(1);
+        drop(receiver);
+        callback.invoke(Event::Foo);
+
+        assert_eq!(
+            0,
+            callback.dropped_count(),
+            "expected closed subscriptions to not be counted as dropped"
+        );
+    }
+
+    #[test]
+    fn test_multi_threaded_invoke_from_drop() {
+        init_logger!();
+        struct Closeable {
+            callbacks: MultiThreadedCallback<Event>,
+        }
+
+        impl Drop for Closeable {
+            fn drop(&mut self) {
+                self.callbacks.invoke(Event::Foo);
+            }
+        }
+
+        let (tx, rx) = channel();
+        let runtime = Runtime::new().unwrap();
+        let callback = MultiThreadedCallback::new();
+        let closeable = Closeable {
+            callbacks: callback.clone(),
+        };
+
+        let mut receiver = callback.subscribe();
+        runtime.spawn(async move {
+            if let Some(e) = receiver.recv().await {
+                tx.send(e).unwrap();
+            }
+        });
+
+        drop(closeable);
+        let result = rx.recv_timeout(Duration::from_millis(50)).unwrap();
+
+        assert_eq!(Event::Foo, *result);
+    }
+
+    #[test]
+    fn test_ordered_dispatch_runtime_dropped() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<u32>::new();
+        let mut receiver = callback.subscribe();
+
+        // the fan-out task is spawned, but never polled before the runtime is dropped
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        runtime.block_on(async { callback.invoke(1) });
+        drop(runtime);
+        assert_eq!(1, *receiver.try_recv().unwrap());
+
+        callback.invoke(2);
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let result = runtime.block_on(callback.invoke_dispatched(3));
+
+        assert_eq!(1, result);
+        assert_eq!(2, *receiver.try_recv().unwrap());
+        assert_eq!(3, *receiver.try_recv().unwrap());
+    }
+
+    #[test]
+    fn test_invoke_dispatched_runtime_dropped() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<u32>::new();
+        let mut receiver = callback.subscribe();
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let future = {
+            let _guard = runtime.enter();
+            callback.invoke_dispatched(1)
+        };
+        drop(runtime);
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let result = runtime.block_on(async {
+            time::timeout(Duration::from_millis(200), future)
+                .await
+                .expect("expected the dispatched invocation to complete")
+        });
+
+        assert_eq!(1, result);
+        assert_eq!(1, *receiver.try_recv().unwrap());
+    }
+
+    #[test]
+    fn test_multi_threaded_invoke_from_drop_inline() {
+        init_logger!();
+        struct Closeable {
+            callbacks: MultiThreadedCallback<Event>,
+        }
+
+        impl Drop for Closeable {
+            fn drop(&mut self) {
+                self.callbacks.invoke(Event::Foo);
+            }
+        }
+
+        let callback = MultiThreadedCallback::new();
+        let closeable = Closeable {
+            callbacks: callback.clone(),
+        };
+        let mut receiver = callback.subscribe();
+
+        // poison the lock of the internal runtime, so it can't be created during the drop
+        let runtime = callback.runtime.clone();
+        let _ = std::thread::spawn(move || {
+            let _runtime = runtime.lock().unwrap();
+            panic!("poisoning the internal runtime lock");
+        })
+        .join();
+
+        std::thread::spawn(move || {
+            assert!(tokio::runtime::Handle::try_current().is_err());
+            drop(closeable);
+        })
+        .join()
+        .unwrap();
+
+        // the event has been delivered inline, before the dropping thread completed
+        assert_eq!(Event::Foo, *receiver.try_recv().unwrap());
+        assert!(receiver.try_recv().is_err(), "expected no other events");
+    }
+
+    #[test]
+    fn test_durable_redelivery() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let mut receiver = callback.subscribe_durable("consumer");
+        callback.invoke(1);
+        callback.invoke(2);
+        let first = receiver.try_recv().unwrap();
+        let second = receiver.try_recv().unwrap();
+        first.ack();
+        drop(second);
+        drop(receiver);
+        callback.invoke(3);
+        assert_eq!(2, callback.durable_pending("consumer"));
+
+        let mut receiver = callback.subscribe_durable("consumer");
+        let result = receiver.try_recv().unwrap();
+        assert_eq!(2, *result);
+        assert_eq!(1, result.sequence());
+        result.ack();
+        let result = receiver.try_recv().unwrap();
+        assert_eq!(3, *result);
+        result.ack();
+        assert!(receiver.try_recv().is_err(), "expected no other events");
+        assert_eq!(0, callback.durable_pending("consumer"));
+
+        assert!(callback.remove_durable("consumer"));
+        assert!(!callback.remove_durable("consumer"));
+    }
+
+    #[tokio::test]
+    async fn test_log_events() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<Event>::new();
+
+        let handle = callback.log_events(log::Level::Info);
+        callback.invoke(Event::Foo);
+        time::sleep(Duration::from_millis(50)).await;
+        assert!(
+            !handle.is_finished(),
+            "expected the logging task to be running"
+        );
+
+        handle.abort();
+        time::sleep(Duration::from_millis(50)).await;
+        assert!(
+            handle.is_finished(),
+            "expected the logging task to have been stopped"
+        );
+    }
+
+    #[test]
+    fn test_error_subscription() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<Event>::new();
+        let mut errors = callback.error_subscription();
+
+        let _receiver = callback.subscribe();
+        let bounded = callback.subscribe_bounded(1);
+        callback.invoke(Event::Foo);
+        assert!(errors.try_recv().is_err(), "expected no delivery errors");
+
+        callback.invoke(Event::Foo);
+        let result = errors.try_recv().unwrap();
+        assert_eq!(DeliveryFailure::Full, result.reason);
+        assert_eq!(Some(&Event::Foo), result.event.as_deref());
+
+        drop(bounded);
+        callback.invoke(Event::Foo);
+        let result = errors.try_recv().unwrap();
+        assert_eq!(DeliveryFailure::Closed, result.reason);
+    }
+
+    #[test]
+    fn test_pause_subscriber() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let (handle, mut receiver) = callback.subscribe_handle();
+        let mut other = callback.subscribe();
+        assert!(callback.pause_subscriber(&handle));
+        callback.invoke(1);
+        assert!(callback.resume_subscriber(&handle));
+        callback.invoke(2);
+
+        assert_eq!(2, *receiver.try_recv().unwrap());
+        assert!(receiver.try_recv().is_err(), "expected no other events");
+        assert_eq!(1, *other.try_recv().unwrap());
+        assert_eq!(2, *other.try_recv().unwrap());
+        assert!(!callback.pause_subscriber(&CallbackHandle::new()));
+    }
+
+    #[test]
+    fn test_pause_subscriber_dropped() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let (handle, receiver) = callback.subscribe_handle();
+        let (inactive, inactive_receiver) = callback.subscribe_inactive();
+        let _other = callback.subscribe();
+        assert!(callback.pause_subscriber(&handle));
+        drop(receiver);
+        drop(inactive_receiver);
+        callback.invoke(1);
+
+        assert_eq!(1, callback.subscriber_count());
+        assert!(!callback.resume_subscriber(&handle));
+        assert!(!callback.activate(&inactive));
+    }
+
+    #[test]
+    fn test_stats() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<Event>::new();
+
+        let _first = callback.subscribe();
+        let _second = callback.subscribe();
+        callback.invoke(Event::Foo);
+        callback.invoke(Event::Foo);
+        let result = callback.stats();
+
+        assert_eq!(2, result.invocations);
+        assert_eq!(4, result.deliveries);
+        assert_eq!(2.0, result.avg_subscribers_per_invoke);
+    }
+
+    #[tokio::test]
+    async fn test_managed_subscription_drop() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<Event>::new();
+
+        let subscription = callback.subscribe_managed();
+        let handle = subscription.handle();
+        drop(subscription);
+
+        assert!(
+            !callback.pause_subscriber(&handle),
+            "expected the subscription to have been removed"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_managed_subscription_close() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let subscription = callback.subscribe_managed();
+        let handle = subscription.handle();
+        callback.invoke(1);
+        callback.invoke(2);
+        let result = subscription.close().await;
+
+        assert_eq!(vec![Arc::new(1), Arc::new(2)], result);
+        assert!(
+            !callback.pause_subscriber(&handle),
+            "expected the subscription to have been removed"
+        );
+    }
+
+    #[test]
+    fn test_single_threaded_invoke_untimed() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<Event>::new();
+
+        let mut receiver = callback.subscribe();
+        let result = callback.invoke_untimed(Event::Foo);
+        let stats = callback.stats();
+
+        assert_eq!(1, result);
+        assert_eq!(Event::Foo, *receiver.try_recv().unwrap());
+        assert_eq!(1, stats.deliveries);
+        assert_eq!(Duration::ZERO, stats.max_latency);
+    }
+
+    #[test]
+    fn test_on_drop_emit() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<Event>::new();
+
+        let mut receiver = callback.subscribe();
+        callback.on_drop_emit(|| Event::Foo);
+        let clone = callback.clone();
+        drop(callback);
+        assert!(
+            receiver.try_recv().is_err(),
+            "expected the holder to still be alive"
+        );
+
+        drop(clone);
+        assert_eq!(Event::Foo, *receiver.try_recv().unwrap());
+    }
+
+    #[test]
+    fn test_invoke_where_tag() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let mut eu = callback.subscribe_tagged([("region", "eu"), ("role", "audit")]);
+        let mut us = callback.subscribe_tagged([("region", "us")]);
+        let mut untagged = callback.subscribe();
+        callback.invoke_where_tag("region", "eu", 1);
+        callback.invoke(2);
+
+        assert_eq!(1, *eu.try_recv().unwrap());
+        assert_eq!(2, *eu.try_recv().unwrap());
+        assert_eq!(2, *us.try_recv().unwrap());
+        assert_eq!(2, *untagged.try_recv().unwrap());
+        assert!(us.try_recv().is_err(), "expected no other events");
+        assert!(untagged.try_recv().is_err(), "expected no other events");
+    }
+
+    #[test]
+    fn test_subscribe_fn_panic() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let (tx, rx) = channel();
+        let mut errors = callback.error_subscription();
+
+        let mut receiver = callback.subscribe();
+        let handle = callback.subscribe_fn(|_| panic!("faulty subscriber"));
+        callback.subscribe_fn(move |e| tx.send(*e).unwrap());
+        callback.invoke(1);
+        callback.invoke(2);
+
+        assert_eq!(1, *receiver.try_recv().unwrap());
+        assert_eq!(2, *receiver.try_recv().unwrap());
+        assert_eq!(vec![1, 2], rx.try_iter().collect::<Vec<_>>());
+        let result = errors.try_recv().unwrap();
+        assert_eq!(handle, result.handle);
+        assert_eq!(DeliveryFailure::Panicked, result.reason);
+        assert!(
+            errors.try_recv().is_err(),
+            "expected the closure to have been removed"
+        );
+    }
+
+    #[test]
+    fn test_describe_subscribers() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<Event>::new();
+
+        let (handle, _receiver) = callback.subscribe_handle();
+        let _bounded = callback.subscribe_bounded(2);
+        callback.pause_subscriber(&handle);
+        callback.invoke(Event::Foo);
+        let result = callback.describe_subscribers();
+
+        assert_eq!(2, result.len());
+        let info = result.iter().find(|e| e.handle == handle).unwrap();
+        assert!(info.paused, "expected the subscriber to be paused");
+        assert!(!info.closed, "expected the subscriber to be open");
+        assert_eq!(None, info.queue_depth);
+        let info = result.iter().find(|e| e.handle != handle).unwrap();
+        assert!(!info.paused, "expected the subscriber to not be paused");
+        assert_eq!(Some(1), info.queue_depth);
+    }
+
+    #[test]
+    fn test_set_tap() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let (tx, rx) = channel();
+
+        let _first = callback.subscribe();
+        let _second = callback.subscribe();
+        callback.set_tap(move |e| tx.send(**e).unwrap());
+        callback.invoke(1);
+        callback.clear_tap();
+        callback.invoke(2);
+
+        assert_eq!(vec![1], rx.try_iter().collect::<Vec<_>>());
+        assert_eq!(2, callback.describe_subscribers().len());
+    }
+
+    #[test]
+    fn test_subscribe_owned() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<String>::new();
+
+        let mut shared = callback.subscribe();
+        let mut owned = callback.subscribe_owned();
+        callback.invoke("Foo".to_string());
+
+        assert_eq!("Foo", shared.try_recv().unwrap().as_str());
+        let result: String = owned.try_recv().unwrap();
+        assert_eq!("Foo", result);
+
+        drop(owned);
+        callback.invoke("Bar".to_string());
+        assert_eq!(1, callback.describe_subscribers().len());
+    }
+
+    #[test]
+    fn test_subscribe_handle_collision() {
+        init_logger!();
+        static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+        fn colliding_handle() -> CallbackHandle {
+            let value = SEQUENCE.fetch_add(1, Ordering::Relaxed).min(2) as i64;
+            CallbackHandle::from(value.max(1))
+        }
+        let callback = SingleThreadedCallback::<u32> {
+            base: Arc::new(BaseCallback::with_handle_factory(colliding_handle)),
+        };
+
+        let (first_handle, mut first) = callback.subscribe_handle();
+        let (second_handle, mut second) = callback.subscribe_handle();
+        callback.invoke(1);
+
+        assert_ne!(first_handle, second_handle);
+        assert_eq!(1, *first.try_recv().unwrap());
+        assert_eq!(1, *second.try_recv().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_invoke_all_ordered() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<u32>::new();
+
+        let mut first = callback.subscribe();
+        let mut second = callback.subscribe();
+        callback.invoke_all_ordered(vec![1, 2, 3]).await;
+
+        for receiver in [&mut first, &mut second] {
+            let result: Vec<u32> = std::iter::from_fn(|| receiver.try_recv().ok())
+                .map(|e| *e)
+                .collect();
+            assert_eq!(vec![1, 2, 3], result);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_on_holder_dropped() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<u32>::new();
+        let clone = callback.clone();
+
+        let dropped = tokio::spawn(callback.on_holder_dropped());
+        let mut receiver = callback.subscribe();
+        drop(callback);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(
+            !dropped.is_finished(),
+            "expected the holder to still be alive"
+        );
+
+        let late = clone.on_holder_dropped();
+        drop(clone);
+        tokio::time::timeout(Duration::from_millis(250), dropped)
+            .await
+            .expect("expected the holder dropped future to resolve")
+            .unwrap();
+        tokio::time::timeout(Duration::from_millis(250), late)
+            .await
+            .expect("expected the holder dropped future to resolve immediately");
+        assert_eq!(None, receiver.recv().await);
+    }
+
+    #[tokio::test]
+    async fn test_invoke_request_all() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<Request<u32, u32>>::new();
+
+        let (replying_handle, mut replying) = callback.subscribe_handle();
+        let (silent_handle, _silent) = callback.subscribe_handle();
+        tokio::spawn(async move {
+            while let Some(request) = replying.recv().await {
+                request.reply(**request * 2);
+            }
+        });
+        let result = callback
+            .invoke_request_all(21, Duration::from_millis(100))
+            .await;
+
+        assert_eq!(2, result.len());
+        assert_eq!(Some(Arc::new(42)), result[&replying_handle]);
+        assert_eq!(None, result[&silent_handle]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_invoke_request_all_ordered() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<Request<u32, u32>>::new();
+
+        let mut receiver = callback.subscribe();
+        for i in 0..100 {
+            callback.invoke(Request::new(Arc::new(i)).0);
+        }
+        let result = callback
+            .invoke_request_all(100, Duration::from_millis(10))
+            .await;
+
+        assert_eq!(1, result.len());
+        let received: Vec<u32> = (0..=100).map(|_| **receiver.try_recv().unwrap()).collect();
+        assert_eq!((0..=100).collect::<Vec<_>>(), received);
+    }
+
+    #[tokio::test]
+    async fn test_invoke_request_all_policies() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<Request<u32, u32>>::new();
+
+        let (handle, _subscription) = callback.subscribe_handle();
+        let _gated = callback.subscribe_gated(Arc::new(AtomicBool::new(false)));
+        let _limited = callback.subscribe_limited(1);
+        let _expired = callback.subscribe_for(Duration::ZERO);
+        let result = callback
+            .invoke_request_all(1, Duration::from_millis(10))
+            .await;
+
+        assert_eq!(2, result.len());
+        assert!(result.contains_key(&handle));
+        assert_eq!(2, callback.subscriber_count());
+    }
+
+    #[test]
+    fn test_allow_slow_warning() {
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel::<Arc<u32>>();
+        let entry = CallbackEntry::new(CallbackSender::Unbounded(tx));
+        let now = Instant::now();
+        let interval = Duration::from_secs(30);
+
+        assert!(entry.allow_slow_warning(now, interval));
+        assert!(!entry.allow_slow_warning(now + Duration::from_secs(10), interval));
+        assert!(entry.allow_slow_warning(now + interval, interval));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_ordered_delivery_priority() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<u32>::new();
+        let (tx, rx) = channel();
+
+        for priority in [-1, 10, 0] {
+            let tx = tx.clone();
+            let mut entry =
+                CallbackEntry::new(CallbackSender::Closure(Box::new(move |e: Arc<u32>| {
+                    tx.send((priority, *e)).unwrap()
+                })));
+            entry.priority = priority;
+            callback.base.insert_entry(entry);
+        }
+        for i in 0..100 {
+            callback.invoke(i);
+        }
+        callback.invoke_all_ordered(Vec::new()).await;
+
+        let expected: Vec<(i32, u32)> = (0..100).flat_map(|i| [(10, i), (0, i), (-1, i)]).collect();
+        assert_eq!(expected, rx.try_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_flush_buffer() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let mut receiver = callback.subscribe();
+        callback.begin_buffering();
+        callback.invoke(1);
+        callback.invoke(2);
+        assert!(
+            receiver.try_recv().is_err(),
+            "expected the events to be buffered"
+        );
+
+        assert_eq!(2, callback.flush_buffer());
+        callback.invoke(3);
+        assert_eq!(1, *receiver.try_recv().unwrap());
+        assert_eq!(2, *receiver.try_recv().unwrap());
+        assert_eq!(3, *receiver.try_recv().unwrap());
+    }
+
+    #[test]
+    fn test_discard_buffer() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let mut receiver = callback.subscribe();
+        callback.begin_buffering();
+        callback.invoke(1);
+        assert_eq!(1, callback.discard_buffer());
+        callback.invoke(2);
+
+        assert_eq!(2, *receiver.try_recv().unwrap());
+        assert!(receiver.try_recv().is_err(), "expected no other events");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_subscribe_fn_with_timeout() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let mut receiver = callback.subscribe();
+        callback.subscribe_fn_with_timeout(
+            move |e| {
+                std::thread::sleep(Duration::from_millis(200));
+                tx.send(*e).unwrap();
+            },
+            Duration::from_millis(50),
+        );
+        let start_time = Instant::now();
+        callback.invoke(1);
+
+        assert!(
+            start_time.elapsed() < Duration::from_millis(150),
+            "expected the fan-out to not wait for the closure"
+        );
+        assert_eq!(1, *receiver.try_recv().unwrap());
+        let result = time::timeout(Duration::from_millis(500), rx.recv())
+            .await
+            .unwrap();
+        assert_eq!(Some(1), result);
+    }
+
+    #[test]
+    fn test_subscribe_flat_map() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<Vec<u32>>::new();
+
+        let mut receiver = callback.subscribe_flat_map(|e: Arc<Vec<u32>>| e.to_vec());
+        callback.invoke(vec![1, 2]);
+        callback.invoke(Vec::new());
+        callback.invoke(vec![3]);
+
+        let result: Vec<u32> = std::iter::from_fn(|| receiver.try_recv().ok())
+            .map(|e| *e)
+            .collect();
+        assert_eq!(vec![1, 2, 3], result);
+    }
+
+    #[test]
+    fn test_log_strong_count() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let value = Arc::new(1);
+
+        assert_eq!(None, callback.base.log_strong_count(&value));
+
+        callback.set_strong_count_tracing(true);
+        assert_eq!(Some(0), callback.base.log_strong_count(&value));
+
+        let first = value.clone();
+        let second = value.clone();
+        assert_eq!(Some(2), callback.base.log_strong_count(&value));
+
+        drop(first);
+        assert_eq!(Some(1), callback.base.log_strong_count(&value));
+        drop(second);
+        assert_eq!(Some(0), callback.base.log_strong_count(&value));
+    }
+
+    #[test]
+    fn test_log_strong_count_subscriptions() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        callback.set_strong_count_tracing(true);
+
+        let mut first = callback.subscribe();
+        let mut second = callback.subscribe();
+        callback.invoke(1);
+
+        // the event is still held by the queue of the second subscription
+        let event = first.try_recv().unwrap();
+        assert_eq!(Some(1), callback.base.log_strong_count(&event));
+        drop(second.try_recv().unwrap());
+        assert_eq!(Some(0), callback.base.log_strong_count(&event));
+    }
+
+    #[test]
+    fn test_callback_try_subscribe_with() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::with_max_subscribers(1);
+        let holder: &dyn Callback<u32> = &callback;
+        let (first, _first_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (second, _second_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let handle = holder.try_subscribe_with(first).unwrap();
+        let result = holder.try_subscribe_with(second);
+
+        assert_eq!(
+            Err(CallbackError::TooManySubscribers {
+                limit: 1,
+                current: 1
+            }),
+            result
+        );
+        assert!(holder.unsubscribe(handle));
+    }
+
+    #[test]
+    fn test_subscribe_with_limited_zero() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let result = callback.subscribe_with_limited(tx, 0);
+
+        assert_eq!(None, result);
+        assert_eq!(0, callback.subscriber_count());
+        assert_eq!(
+            Err(tokio::sync::mpsc::error::TryRecvError::Disconnected),
+            rx.try_recv(),
+            "expected the subscriber to have been closed"
+        );
+    }
+
+    #[test]
+    fn test_invoke_require_subscribers() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let result = callback.invoke_require_subscribers(1);
+        assert_eq!(Err(NoSubscribersError::new(Arc::new(1))), result);
+
+        let _first = callback.subscribe();
+        let _second = callback.subscribe();
+        let result = callback.invoke_require_subscribers(2);
+        assert_eq!(Ok(2), result);
+    }
+
+    #[test]
+    fn test_invoke_require_subscribers_dropped() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let (tx, rx) = channel();
+
+        let subscription = callback.subscribe();
+        callback.set_tap(move |e| tx.send(**e).unwrap());
+        drop(subscription);
+        let result = callback.invoke_require_subscribers(1);
+
+        assert_eq!(Err(NoSubscribersError::new(Arc::new(1))), result);
+        assert_eq!(
+            0,
+            rx.try_iter().count(),
+            "expected the tap to not have been invoked"
+        );
+        assert_eq!(0, callback.subscriber_count());
+    }
+
+    #[test]
+    fn test_invoke_require_subscribers_fan_out() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let (tx, rx) = channel();
+
+        callback.set_no_subscribers_policy(NoSubscribersPolicy::FanOut);
+        callback.set_tap(move |e| tx.send(**e).unwrap());
+        let result = callback.invoke_require_subscribers(1);
+        assert_eq!(Err(NoSubscribersError::new(Arc::new(1))), result);
+
+        let mut subscription = callback.subscribe();
+        let result = callback.invoke_require_subscribers(2);
+        assert_eq!(Ok(1), result);
+
+        assert_eq!(vec![1, 2], rx.try_iter().collect::<Vec<_>>());
+        assert_eq!(2, *subscription.try_recv().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_invoke_all_ordered_cooperative() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let values: Vec<u32> = (0..1000).collect();
+
+        let mut receiver = callback.subscribe();
+        callback.invoke_all_ordered(values.clone()).await;
+
+        let result: Vec<u32> = std::iter::from_fn(|| receiver.try_recv().ok())
+            .map(|e| *e)
+            .collect();
+        assert_eq!(values, result);
+    }
+
+    #[tokio::test]
+    async fn test_filtered_view() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<u32>::new();
+        let view = callback.filtered_view(|e| e % 2 == 0);
+
+        let mut receiver = view.subscribe();
+        callback.invoke(1);
+        callback.invoke(2);
+
+        let result = time::timeout(Duration::from_millis(200), receiver.recv())
+            .await
+            .unwrap();
+        assert_eq!(Some(Arc::new(2)), result);
+        assert!(receiver.try_recv().is_err(), "expected no other events");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_filtered_view_ordered() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<u32>::new();
+        let view = callback.filtered_view(|_| true);
+
+        let mut receiver = view.subscribe();
+        for i in 0..100 {
+            view.invoke(i);
+        }
+        view.invoke_dispatched(100).await;
+
+        let result: Vec<u32> = (0..=100).map(|_| *receiver.try_recv().unwrap()).collect();
+        assert_eq!((0..=100).collect::<Vec<_>>(), result);
+    }
+
+    #[test]
+    fn test_invoke_insertion_order() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let (tx, rx) = channel();
+
+        for index in 0..3 {
+            let tx = tx.clone();
+            callback.subscribe_fn(move |_| tx.send(index).unwrap());
+        }
+        callback.invoke(1);
+        callback.invoke(2);
+
+        assert_eq!(vec![0, 1, 2, 0, 1, 2], rx.try_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_subscribe_many() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let mut result = callback.subscribe_many(3);
+        callback.invoke(1);
+
+        assert_eq!(3, result.len());
+        assert_eq!(3, callback.describe_subscribers().len());
+        for receiver in result.iter_mut() {
+            assert_eq!(1, *receiver.try_recv().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_invoke_spawned() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut receiver = callback.subscribe();
+
+        callback.invoke_spawned(1);
+        assert_eq!(1, *receiver.try_recv().unwrap());
+
+        let runtime = Runtime::new().unwrap();
+        runtime.block_on(async {
+            callback.invoke_spawned(2);
+            let result = time::timeout(Duration::from_millis(200), receiver.recv())
+                .await
+                .unwrap();
+            assert_eq!(Some(Arc::new(2)), result);
+        });
+    }
+
+    #[test]
+    fn test_subscribe_variant_mask() {
+        #[derive(Debug, PartialEq)]
+        enum MaskEvent {
+            Foo,
+            Bar,
+            Baz,
+        }
+
+        impl VariantMask for MaskEvent {
+            fn variant_bit(&self) -> u64 {
+                match self {
+                    MaskEvent::Foo => 1 << 0,
+                    MaskEvent::Bar => 1 << 1,
+                    MaskEvent::Baz => 1 << 2,
+                }
+            }
+        }
+
+        init_logger!();
+        let callback = SingleThreadedCallback::<MaskEvent>::new();
+
+        let mut masked = callback.subscribe_variant_mask(1 << 0 | 1 << 2);
+        let mut receiver = callback.subscribe();
+        callback.invoke(MaskEvent::Foo);
+        callback.invoke(MaskEvent::Bar);
+        callback.invoke(MaskEvent::Baz);
+
+        assert_eq!(MaskEvent::Foo, *masked.try_recv().unwrap());
+        assert_eq!(MaskEvent::Baz, *masked.try_recv().unwrap());
+        assert!(masked.try_recv().is_err(), "expected no other events");
+        assert_eq!(MaskEvent::Foo, *receiver.try_recv().unwrap());
+        assert_eq!(MaskEvent::Bar, *receiver.try_recv().unwrap());
+    }
+
+    #[test]
+    fn test_recent_invokes() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let (handle, _receiver) = callback.subscribe_handle();
+        let dropped = callback.subscribe();
+        callback.invoke(1);
+        callback.set_invoke_trace_capacity(2);
+        drop(dropped);
+        callback.invoke(2);
+        callback.invoke(3);
+        callback.invoke(4);
+
+        let result = callback.recent_invokes();
+        assert_eq!(2, result.len());
+        assert_eq!(3, *result[0].1);
+        assert_eq!(vec![handle], result[0].2);
+        assert_eq!(4, *result[1].1);
+        assert!(result[0].0 <= result[1].0);
+
+        callback.set_invoke_trace_capacity(0);
+        assert!(callback.recent_invokes().is_empty());
+    }
+
+    #[test]
+    fn test_subscribe_adaptive() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let mut subscription = callback.subscribe_adaptive(2);
+        for i in 0..5 {
+            callback.invoke(i);
+        }
+
+        assert_eq!(Some(2), callback.describe_subscribers()[0].queue_depth);
+        assert_eq!(3, subscription.dropped_count());
+        assert_eq!(Some(Arc::new(3)), subscription.try_recv());
+        assert_eq!(Some(Arc::new(4)), subscription.try_recv());
+        assert_eq!(None, subscription.try_recv());
+
+        drop(subscription);
+        callback.invoke(5);
+        assert!(callback.describe_subscribers().is_empty());
+    }
+
+    #[test]
+    fn test_unsubscribe_by_tag() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let mut first = callback.subscribe_tagged([("tenant", "foo")]);
+        let mut second = callback.subscribe_tagged([("tenant", "foo"), ("region", "eu")]);
+        let mut third = callback.subscribe_tagged([("tenant", "bar")]);
+        let result = callback.unsubscribe_by_tag("tenant", "foo");
+        callback.invoke(1);
+
+        assert_eq!(2, result);
+        assert!(
+            first.try_recv().is_err(),
+            "expected the receiver to be closed"
+        );
+        assert!(
+            second.try_recv().is_err(),
+            "expected the receiver to be closed"
+        );
+        assert_eq!(1, *third.try_recv().unwrap());
+        assert_eq!(1, callback.describe_subscribers().len());
+    }
+
+    #[test]
+    fn test_record_latency() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<Timestamped<u32>>::new();
+
+        let mut receiver = callback.subscribe();
+        callback.invoke_timestamped(1);
+        let event = receiver.try_recv().unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+        callback.record_latency(&event);
+        let result = callback.latency_stats();
+
+        assert_eq!(1, event.value);
+        assert_eq!(1, result.samples);
+        assert!(
+            result.max_latency >= Duration::from_millis(5),
+            "expected the latency to include the time until receipt"
+        );
+    }
+
+    #[test]
+    fn test_invoke_try() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let mut receiver = callback.subscribe();
+        let result = callback.invoke_try(|| "1".parse::<u32>());
+        assert_eq!(Ok(()), result);
+        let result = callback.invoke_try(|| "foo".parse::<u32>());
+        assert!(
+            result.is_err(),
+            "expected the producer error to be returned"
+        );
+
+        assert_eq!(1, *receiver.try_recv().unwrap());
+        assert!(receiver.try_recv().is_err(), "expected no other events");
+    }
+
+    #[test]
+    fn test_subscribe_gated() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let gate = Arc::new(AtomicBool::new(true));
+
+        let mut receiver = callback.subscribe_gated(gate.clone());
+        callback.invoke(1);
+        gate.store(false, Ordering::Release);
+        callback.invoke(2);
+        gate.store(true, Ordering::Release);
+        callback.invoke(3);
+
+        assert_eq!(1, *receiver.try_recv().unwrap());
+        assert_eq!(3, *receiver.try_recv().unwrap());
+        assert!(receiver.try_recv().is_err(), "expected no other events");
+    }
+
+    #[test]
+    fn test_subscribe_into() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let (first_tx, mut first_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (second_tx, mut second_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let result = callback.subscribe_into(vec![first_tx, second_tx]);
+        callback.invoke(1);
+
+        assert_eq!(2, result.len());
+        assert_eq!(
+            result,
+            callback
+                .describe_subscribers()
+                .into_iter()
+                .map(|e| e.handle)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(1, *first_rx.try_recv().unwrap());
+        assert_eq!(1, *second_rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn test_start_capture() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let mut receiver = callback.subscribe();
+        let capture = callback.start_capture(Duration::from_millis(50));
+        callback.invoke(1);
+        callback.invoke(2);
+        std::thread::sleep(Duration::from_millis(60));
+        callback.invoke(3);
+
+        assert!(capture.is_finished());
+        assert_eq!(vec![Arc::new(1), Arc::new(2)], capture.collect());
+        assert_eq!(1, callback.describe_subscribers().len());
+        assert_eq!(1, *receiver.try_recv().unwrap());
+    }
+
+    #[test]
+    fn test_unsubscribe() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let handle = callback.subscribe_with(tx);
+        assert!(
+            callback.unsubscribe(handle),
+            "expected the subscriber to be removed"
+        );
+        assert!(
+            !callback.unsubscribe(handle),
+            "expected the subscriber to be already removed"
+        );
+        assert!(
+            !callback.unsubscribe(CallbackHandle::new()),
+            "expected the handle to be unknown"
+        );
+        callback.invoke(1);
+
+        assert!(
+            rx.try_recv().is_err(),
+            "expected the subscription to be closed"
+        );
+        assert!(callback.describe_subscribers().is_empty());
+    }
+
+    #[test]
+    fn test_subscribe_guarded() {
+        fn assert_send<S: Send>(_: &S) {}
+
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let (guard, mut receiver) = callback.subscribe_guarded();
+        assert_send(&guard);
+        drop(guard);
+        callback.invoke(1);
+        assert!(callback.describe_subscribers().is_empty());
+        assert!(
+            receiver.try_recv().is_err(),
+            "expected the subscription to be closed"
+        );
+
+        let (guard, _receiver) = callback.subscribe_guarded();
+        drop(callback);
+        drop(guard);
+    }
+
+    #[test]
+    fn test_subscriber_count() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let (handle, receiver) = callback.subscribe_handle();
+        let _receiver = callback.subscribe();
+        assert_eq!(2, callback.subscriber_count());
+        assert_eq!(2, callback.handles().len());
+        assert_eq!(handle, callback.handles()[0]);
+
+        drop(receiver);
+        assert!(callback.subscriber_info(&handle).unwrap().closed);
+        callback.invoke(1);
+        assert_eq!(1, callback.subscriber_count());
+        assert_eq!(None, callback.subscriber_info(&handle));
+    }
+
+    #[test]
+    fn test_clear() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let mut receiver = callback.subscribe();
+        callback.invoke(1);
+        let result = callback.clear();
+        callback.invoke(2);
+
+        assert_eq!(1, result);
+        assert_eq!(0, callback.subscriber_count());
+        assert_eq!(1, *receiver.try_recv().unwrap());
+        assert_eq!(
+            Err(tokio::sync::mpsc::error::TryRecvError::Disconnected),
+            receiver.try_recv()
+        );
+    }
+
+    #[test]
+    fn test_clear_concurrent_subscribe() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let subscriber = callback.clone();
+        let thread = std::thread::spawn(move || {
+            (0..1000)
+                .map(|_| subscriber.subscribe())
+                .collect::<Vec<_>>()
+        });
+        let mut removed = 0;
+        while !thread.is_finished() {
+            removed += callback.clear();
+        }
+        let mut receivers = thread.join().unwrap();
+        let remaining = callback.subscriber_count();
+        callback.invoke(1);
+
+        assert_eq!(1000, removed + remaining);
+        let received = receivers
+            .iter_mut()
+            .map(|e| e.try_recv())
+            .filter(|e| e.is_ok())
+            .count();
+        assert_eq!(remaining, received);
+    }
+
+    #[test]
+    fn test_pause_resume() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let (handle, mut receiver) = callback.subscribe_handle();
+        let mut other = callback.subscribe();
+        callback.set_pause_buffer_capacity(2);
+        assert!(callback.pause(&handle));
+        callback.invoke(1);
+        callback.invoke(2);
+        callback.invoke(3);
+        assert!(
+            receiver.try_recv().is_err(),
+            "expected no events while paused"
+        );
+        assert_eq!(1, *other.try_recv().unwrap());
+
+        assert!(callback.resume(&handle));
+        callback.invoke(4);
+        assert_eq!(2, *receiver.try_recv().unwrap());
+        assert_eq!(3, *receiver.try_recv().unwrap());
+        assert_eq!(4, *receiver.try_recv().unwrap());
+        assert_eq!(1, callback.dropped_count());
+        assert!(!callback.resume(&CallbackHandle::new()));
+    }
+
+    #[test]
+    fn test_pause_dropped_subscription() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let (handle, receiver) = callback.subscribe_handle();
+        callback.pause(&handle);
+        drop(receiver);
+        callback.invoke(1);
+
+        assert_eq!(0, callback.subscriber_count());
+    }
+
+    #[test]
+    fn test_subscribe_limited() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let mut receiver = callback.subscribe_limited(2);
+        callback.invoke(1);
+        callback.invoke(2);
+        callback.invoke(3);
+
+        assert_eq!(0, callback.subscriber_count());
+        assert_eq!(1, *receiver.try_recv().unwrap());
+        assert_eq!(2, *receiver.try_recv().unwrap());
+        assert_eq!(
+            Err(tokio::sync::mpsc::error::TryRecvError::Disconnected),
+            receiver.try_recv()
+        );
+    }
+
+    #[test]
+    fn test_subscribe_limited_concurrent() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let mut receiver = callback.subscribe_limited(100);
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let callback = callback.clone();
+                std::thread::spawn(move || {
+                    for i in 0..50 {
+                        callback.invoke(i);
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let mut total = 0;
+        while receiver.try_recv().is_ok() {
+            total += 1;
+        }
+        assert_eq!(100, total);
+    }
+
+    #[test]
+    fn test_single_threaded_subscribe_for() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let mut receiver = callback.subscribe_for(Duration::from_millis(50));
+        callback.invoke(1);
+        std::thread::sleep(Duration::from_millis(60));
+        callback.invoke(2);
+
+        assert_eq!(0, callback.subscriber_count());
+        assert_eq!(1, *receiver.try_recv().unwrap());
+        assert_eq!(
+            Err(tokio::sync::mpsc::error::TryRecvError::Disconnected),
+            receiver.try_recv()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_multi_threaded_subscribe_for() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<u32>::new();
+
+        let mut receiver = callback.subscribe_for(Duration::from_millis(50));
+        let result = time::timeout(Duration::from_millis(250), receiver.recv())
+            .await
+            .expect("expected the subscription to have been closed");
+
+        assert_eq!(None, result);
+        assert_eq!(0, callback.subscriber_count());
+    }
+
+    #[test]
+    fn test_prune() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let receiver = callback.subscribe();
+        let _receiver = callback.subscribe();
+        let _handle = callback.subscribe_fn(|_| {});
+        drop(receiver);
+        let result = callback.prune();
+
+        assert_eq!(1, result);
+        assert_eq!(2, callback.subscriber_count());
+    }
+
+    #[tokio::test]
+    async fn test_prune_interval() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<u32>::new();
+
+        let _task = callback.prune_interval(Duration::from_millis(10));
+        drop(callback.subscribe());
+        time::timeout(Duration::from_millis(250), async {
+            while callback.subscriber_count() > 0 {
+                time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("expected the subscriber to have been pruned");
+    }
+
+    #[test]
+    fn test_with_max_subscribers() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::with_max_subscribers(2);
+
+        let (handle, _receiver) = callback.subscribe_handle();
+        let receiver = callback.try_subscribe().unwrap();
+        let result = callback.try_subscribe();
+        assert_eq!(
+            Some(CallbackError::TooManySubscribers {
+                limit: 2,
+                current: 2
+            }),
+            result.err()
+        );
+
+        let mut rejected = callback.subscribe();
+        assert!(
+            rejected.try_recv().is_err(),
+            "expected the subscription to be closed"
+        );
+        assert!(
+            rejected.is_closed(),
+            "expected the subscription to be closed"
+        );
+
+        drop(receiver);
+        let _receiver = callback
+            .try_subscribe()
+            .expect("expected the dropped capacity to be freed");
+        callback.unsubscribe(handle);
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        callback
+            .try_subscribe_with(tx)
+            .expect("expected the unsubscribed capacity to be freed");
+        assert_eq!(2, callback.subscriber_count());
+    }
+
+    #[test]
+    fn test_with_max_subscribers_concurrent() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<u32>::with_max_subscribers(10);
+
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let callback = callback.clone();
+                std::thread::spawn(move || {
+                    (0..5)
+                        .filter_map(|_| callback.try_subscribe().ok())
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        let receivers: Vec<_> = threads
+            .into_iter()
+            .flat_map(|e| e.join().unwrap())
+            .collect();
+
+        assert_eq!(10, receivers.len());
+        assert_eq!(10, callback.subscriber_count());
+    }
+
+    #[test]
+    fn test_replace_subscriber() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let (handle, mut receiver) = callback.subscribe_handle();
+        let created = callback.subscriber_info(&handle).unwrap().created;
+        callback.invoke(1);
+        callback.replace_subscriber(handle, tx).unwrap();
+        callback.invoke(2);
+
+        assert_eq!(1, *receiver.try_recv().unwrap());
+        assert!(receiver.try_recv().is_err(), "expected no other events");
+        assert_eq!(2, *rx.try_recv().unwrap());
+        assert_eq!(vec![handle], callback.handles());
+        assert_eq!(created, callback.subscriber_info(&handle).unwrap().created);
+
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let unknown = CallbackHandle::new();
+        assert_eq!(
+            Err(CallbackError::UnknownHandle(unknown)),
+            callback.replace_subscriber(unknown, tx)
+        );
+        assert_eq!(1, callback.subscriber_count());
+    }
+
+    #[test]
+    fn test_subscribe_named() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let mut receiver = callback.subscribe_named("audit");
+        let handle = callback.subscribe_named_with("audit", tx);
+        callback.invoke(1);
+
+        assert_eq!(1, *receiver.try_recv().unwrap());
+        assert_eq!(
+            Some("audit".to_string()),
+            callback.subscriber_info(&handle).unwrap().name
+        );
+        let result = format!("{:?}", callback.base);
+        assert!(
+            result.contains("[\"audit\", \"audit\"]"),
+            "expected the names within {}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_set_enabled() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let mut receiver = callback.subscribe();
+        callback.set_enabled(false);
+        callback.invoke(1);
+        assert!(
+            !callback.is_enabled(),
+            "expected the callback to be disabled"
+        );
+        callback.set_enabled(true);
+        callback.invoke(2);
+
+        assert_eq!(2, *receiver.try_recv().unwrap());
+        assert!(receiver.try_recv().is_err(), "expected no other events");
+    }
+
+    #[tokio::test]
+    async fn test_multi_threaded_set_enabled() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<u32>::new();
+
+        let mut receiver = callback.subscribe();
+        callback.set_enabled(false);
+        callback.invoke(1);
+        callback.set_enabled(true);
+        callback.invoke(2);
+
+        let result = select! {
+            _ = time::sleep(Duration::from_millis(250)) => panic!("receiver timed-out"),
+            result = receiver.recv() => result.unwrap(),
+        };
+        assert_eq!(2, *result);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_until() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<u32>::new();
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+
+        let mut receiver = callback.subscribe_until(async move {
+            let _ = rx.await;
+        });
+        callback.invoke(1);
+        let result = select! {
+            _ = time::sleep(Duration::from_millis(250)) => panic!("receiver timed-out"),
+            result = receiver.recv() => result.unwrap(),
+        };
+        assert_eq!(1, *result);
+
+        tx.send(()).unwrap();
+        let result = select! {
+            _ = time::sleep(Duration::from_millis(250)) => panic!("receiver timed-out"),
+            result = receiver.recv() => result,
+        };
+        assert_eq!(None, result);
+        assert_eq!(0, callback.subscriber_count());
+    }
+
+    #[test]
+    fn test_subscribe_until_dropped_subscription() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<u32>::new();
+
+        let receiver = callback.subscribe_until(std::future::pending());
+        drop(receiver);
+        let start = Instant::now();
+        while callback.subscriber_count() > 0 && start.elapsed() < Duration::from_millis(250) {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        assert_eq!(0, callback.subscriber_count());
+    }
+
+    #[test]
+    fn test_set_listener() {
+        init_logger!();
+        struct Poller(Arc<AtomicBool>);
+        impl SubscriptionListener for Poller {
+            fn on_subscribed(&self, _: CallbackHandle, count: usize) {
+                if count == 1 {
+                    self.0.store(true, Ordering::SeqCst);
+                }
+            }
+
+            fn on_unsubscribed(&self, _: CallbackHandle, count: usize) {
+                if count == 0 {
+                    self.0.store(false, Ordering::SeqCst);
+                }
+            }
+        }
+        let callback = SingleThreadedCallback::<u32>::new();
+        let running = Arc::new(AtomicBool::new(false));
+
+        callback.set_listener(Box::new(Poller(running.clone())));
+        let (handle, _receiver) = callback.subscribe_handle();
+        let receiver = callback.subscribe();
+        assert!(
+            running.load(Ordering::SeqCst),
+            "expected the poller to be started"
+        );
+
+        callback.unsubscribe(handle);
+        assert!(
+            running.load(Ordering::SeqCst),
+            "expected the poller to be running"
+        );
+        drop(receiver);
+        callback.invoke(1);
+        assert!(
+            !running.load(Ordering::SeqCst),
+            "expected the poller to be stopped"
+        );
+    }
 
-        assert_eq!(expected_result, *result);
+    #[test]
+    fn test_set_listener_reentrant() {
+        init_logger!();
+        struct Resubscribe(SingleThreadedCallback<u32>, Arc<Mutex<Vec<usize>>>);
+        impl SubscriptionListener for Resubscribe {
+            fn on_subscribed(&self, _: CallbackHandle, count: usize) {
+                self.1.lock().unwrap().push(count);
+            }
+
+            fn on_unsubscribed(&self, _: CallbackHandle, count: usize) {
+                self.1.lock().unwrap().push(count);
+                if count == 0 {
+                    self.0.subscribe_fn(|_| {});
+                }
+            }
+        }
+        let callback = SingleThreadedCallback::<u32>::new();
+        let counts = Arc::new(Mutex::new(Vec::new()));
+
+        callback.set_listener(Box::new(Resubscribe(callback.clone(), counts.clone())));
+        drop(callback.subscribe());
+        callback.invoke(1);
+
+        assert_eq!(vec![1, 0, 1], *counts.lock().unwrap());
+        assert_eq!(1, callback.subscriber_count());
+        // break the reference cycle between the holder and its listener
+        callback.clear_listener();
+    }
+
+    #[test]
+    fn test_duplicate_policy() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        callback.set_duplicate_policy(DuplicatePolicy::IgnoreDuplicates);
+        let handle = callback.subscribe_with(tx.clone());
+        let result = callback.subscribe_with(tx.clone());
+        assert_eq!(handle, result);
+        callback.invoke(1);
+        assert_eq!(1, *rx.try_recv().unwrap());
+        assert!(
+            rx.try_recv().is_err(),
+            "expected the event to be delivered once"
+        );
+
+        callback.set_duplicate_policy(DuplicatePolicy::ErrorOnDuplicate);
+        let result = callback.try_subscribe_with(tx.clone());
+        assert_eq!(Err(CallbackError::DuplicateSubscriber(handle)), result);
+
+        callback.set_duplicate_policy(DuplicatePolicy::AllowDuplicates);
+        let result = callback.try_subscribe_with(tx).unwrap();
+        assert_ne!(handle, result);
+        assert_eq!(2, callback.subscriber_count());
+    }
+
+    #[test]
+    fn test_new_exclusive() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new_exclusive();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let mut first = callback.subscribe();
+        callback.invoke(1);
+        callback.subscribe_with(tx);
+        callback.invoke(2);
+
+        assert_eq!(1, *first.try_recv().unwrap());
+        assert_eq!(
+            Err(tokio::sync::mpsc::error::TryRecvError::Disconnected),
+            first.try_recv()
+        );
+        assert_eq!(2, *rx.try_recv().unwrap());
+        assert_eq!(1, callback.subscriber_count());
+    }
+
+    #[test]
+    fn test_new_exclusive_concurrent() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<u32>::new_exclusive();
+
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let callback = callback.clone();
+                std::thread::spawn(move || callback.subscribe())
+            })
+            .collect();
+        let receivers: Vec<_> = threads.into_iter().map(|e| e.join().unwrap()).collect();
+
+        assert_eq!(1, callback.subscriber_count());
+        assert_eq!(1, receivers.iter().filter(|e| !e.is_closed()).count());
     }
 
     #[tokio::test]
-    async fn test_invoke_dropped_receiver() {
+    async fn test_closed() {
         init_logger!();
-        let expected_result = Event::Foo;
-        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
-        let callback = MultiThreadedCallback::<Event>::new();
+        let callback = MultiThreadedCallback::<u32>::new();
 
-        let _ = callback.subscribe();
-        let mut receiver = callback.subscribe();
-        tokio::spawn(async move {
-            if let Some(e) = receiver.recv().await {
-                let _ = tx.send(e).await;
+        callback.closed().await;
+        let (handle, _receiver) = callback.subscribe_handle();
+        let receiver = callback.subscribe();
+        assert!(callback.has_subscribers(), "expected subscribers");
+
+        callback.unsubscribe(handle);
+        drop(receiver);
+        assert!(!callback.has_subscribers(), "expected no subscribers");
+        select! {
+            _ = time::sleep(Duration::from_millis(500)) => panic!("closed timed-out"),
+            _ = callback.closed() => {},
+        }
+    }
+
+    #[tokio::test]
+    async fn test_closed_unsubscribe() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let (handle, _receiver) = callback.subscribe_handle();
+
+        let closed = callback.clone();
+        let task = tokio::spawn(async move { closed.closed().await });
+        time::sleep(Duration::from_millis(10)).await;
+        assert!(!task.is_finished(), "expected the subscriber to be open");
+
+        callback.unsubscribe(handle);
+        select! {
+            _ = time::sleep(Duration::from_millis(50)) => panic!("closed timed-out"),
+            _ = task => {},
+        }
+    }
+
+    #[tokio::test]
+    async fn test_probe() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let mut subscription = callback.subscribe_acked();
+        let handle = subscription.handle();
+        callback.invoke(1);
+        callback.invoke(2);
+        let result = callback.probe(handle, Duration::from_millis(20)).await;
+        assert_eq!(Some(LivenessStatus::Stalled(2)), result);
+        assert_eq!(
+            Some(LivenessStatus::Stalled(2)),
+            callback.subscriber_info(&handle).unwrap().liveness
+        );
+
+        let consumer = tokio::spawn(async move {
+            let mut events = Vec::new();
+            while let Some(event) = subscription.recv().await {
+                events.push(*event);
             }
+            events
         });
+        let result = callback.probe(handle, Duration::from_millis(250)).await;
+        assert_eq!(Some(LivenessStatus::Alive), result);
 
-        callback.invoke(expected_result.clone());
-        let result = select! {
-            _ = time::sleep(Duration::from_millis(150)) => {
-                panic!("Callback invocation receiver timed out")
-            },
-            Some(result) = rx.recv() => result,
+        consumer.abort();
+        let _ = consumer.await;
+        let result = callback.probe(handle, Duration::from_millis(250)).await;
+        assert_eq!(Some(LivenessStatus::Closed), result);
+    }
+
+    #[tokio::test]
+    async fn test_probe_plain_subscription() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let (handle, _receiver) = callback.subscribe_handle();
+        let result = callback.probe(handle, Duration::from_millis(20)).await;
+
+        assert_eq!(None, result);
+    }
+
+    #[test]
+    fn test_subscribe_inactive() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let (handle, mut receiver) = callback.subscribe_inactive();
+        let (buffered_handle, mut buffered) = callback.subscribe_inactive_buffered();
+        callback.invoke(1);
+        assert!(
+            receiver.try_recv().is_err(),
+            "expected no events before activation"
+        );
+        assert!(
+            buffered.try_recv().is_err(),
+            "expected no events before activation"
+        );
+
+        assert!(callback.activate(&handle));
+        assert!(callback.activate(&buffered_handle));
+        callback.invoke(2);
+
+        assert_eq!(2, *receiver.try_recv().unwrap());
+        assert!(receiver.try_recv().is_err(), "expected no other events");
+        assert_eq!(1, *buffered.try_recv().unwrap());
+        assert_eq!(2, *buffered.try_recv().unwrap());
+        assert!(!callback.activate(&CallbackHandle::new()));
+    }
+
+    #[test]
+    fn test_activate_concurrent_invoke() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<u32>::new();
+
+        let (handle, mut receiver) = callback.subscribe_inactive_buffered();
+        let invoker = {
+            let callback = callback.clone();
+            std::thread::spawn(move || {
+                for i in 0..1000 {
+                    callback.base.invoke(i);
+                }
+            })
         };
+        callback.activate(&handle);
+        invoker.join().unwrap();
 
-        assert_eq!(expected_result, *result);
+        let mut events = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            events.push(*event);
+        }
+        assert_eq!((0..1000).collect::<Vec<_>>(), events);
     }
 
     #[tokio::test]
-    async fn test_non_cloneable_type() {
+    async fn test_subscribe_once() {
         init_logger!();
-        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
-        let callback = MultiThreadedCallback::<NoneCloneEvent>::new();
+        let callback = MultiThreadedCallback::<u32>::new();
+
+        let future = callback.subscribe_once();
+        assert_eq!(1, callback.subscriber_count());
+        callback.invoke(1);
+        callback.invoke(2);
+
+        let result = tokio::time::timeout(Duration::from_millis(250), future)
+            .await
+            .expect("expected the event to have been received");
+        assert_eq!(Some(Arc::new(1)), result);
+        assert_eq!(0, callback.subscriber_count());
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_once_dropped_holder() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let future = callback.subscribe_once();
+        callback.invoke(1);
+        assert_eq!(0, callback.subscriber_count());
+        assert_eq!(Some(Arc::new(1)), future.await);
+
+        let future = callback.subscribe_once();
+        drop(callback);
+        assert_eq!(None, future.await);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_latest() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe_latest();
+
+        for i in 1..=3 {
+            callback.invoke(i);
+        }
+        let result = tokio::time::timeout(Duration::from_millis(250), subscription.recv())
+            .await
+            .expect("expected to receive the newest event");
+        assert_eq!(Some(Arc::new(3)), result);
+        assert_eq!(2, subscription.overwritten());
+
+        drop(callback);
+        assert_eq!(None, subscription.recv().await);
+    }
+
+    #[test]
+    fn test_subscribe_latest_dropped_subscription() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        let subscription = callback.subscribe_latest();
+        callback.invoke(1);
+        assert_eq!(1, callback.subscriber_count());
+
+        drop(subscription);
+        callback.invoke(2);
+        assert_eq!(0, callback.subscriber_count());
+    }
+
+    #[test]
+    fn test_subscribe_bounded_with() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(2);
+        let mut other = callback.subscribe_bounded(1);
+
+        let handle = callback.subscribe_bounded_with(tx);
+        for i in 1..=3 {
+            callback.invoke(i);
+        }
+
+        assert_eq!(Some(1), callback.dropped_count_of(&handle));
+        assert_eq!(3, callback.dropped_count());
+        assert_eq!(1, *rx.try_recv().unwrap());
+        assert_eq!(2, *rx.try_recv().unwrap());
+        assert_eq!(1, *other.try_recv().unwrap());
+        let info = callback
+            .describe_subscribers()
+            .into_iter()
+            .find(|e| e.handle == handle)
+            .unwrap();
+        assert_eq!(1, info.dropped);
+        assert_eq!(None, callback.dropped_count_of(&CallbackHandle::new()));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_overflow_drop_oldest() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe_overflow(2, OverflowPolicy::DropOldest);
+        let handle = callback.handles()[0];
+
+        for i in 1..=5 {
+            callback.invoke(i);
+        }
+
+        assert_eq!(Some(3), callback.dropped_count_of(&handle));
+        assert_eq!(Some(Arc::new(4)), subscription.recv().await);
+        assert_eq!(Some(Arc::new(5)), subscription.recv().await);
+
+        drop(callback);
+        assert_eq!(None, subscription.recv().await);
+    }
+
+    #[test]
+    fn test_subscribe_overflow_drop_newest() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe_overflow(2, OverflowPolicy::DropNewest);
+        let mut other = callback.subscribe();
+        let handle = callback.handles()[0];
+
+        for i in 1..=4 {
+            callback.invoke(i);
+        }
+
+        assert_eq!(Some(2), callback.dropped_count_of(&handle));
+        assert_eq!(2, callback.dropped_count());
+        let info = callback.subscriber_info(&handle).unwrap();
+        assert_eq!(2, info.dropped);
+        assert_eq!(Some(Arc::new(1)), subscription.try_recv());
+        assert_eq!(Some(Arc::new(2)), subscription.try_recv());
+        assert_eq!(None, subscription.try_recv());
+        for i in 1..=4 {
+            assert_eq!(i, *other.try_recv().unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invoke_async() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<u32>::new();
+        let mut bounded = callback.subscribe_bounded(1);
+        let mut unbounded = callback.subscribe();
+
+        callback.invoke_async(1, None).await;
+        let invoker = callback.clone();
+        let task = tokio::spawn(async move { invoker.invoke_async(2, None).await });
+
+        assert_eq!(Some(Arc::new(1)), unbounded.recv().await);
+        assert_eq!(Some(Arc::new(2)), unbounded.recv().await);
+        assert_eq!(1, *bounded.recv().await.unwrap());
+        let skipped = tokio::time::timeout(Duration::from_millis(250), task)
+            .await
+            .expect("expected the invocation to complete")
+            .unwrap();
+        assert_eq!(Vec::<CallbackHandle>::new(), skipped);
+        assert_eq!(2, *bounded.recv().await.unwrap());
+        assert_eq!(0, callback.dropped_count());
+    }
+
+    #[tokio::test]
+    async fn test_invoke_async_timeout() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut errors = callback.error_subscription();
+        let mut bounded = callback.subscribe_bounded(1);
+        let handle = callback.handles()[0];
+
+        callback
+            .invoke_async(1, Some(Duration::from_millis(50)))
+            .await;
+        // the consumer awaits the same holder while its queue is full, which would deadlock without a timeout
+        let skipped = callback
+            .invoke_async(2, Some(Duration::from_millis(50)))
+            .await;
+
+        assert_eq!(vec![handle], skipped);
+        assert_eq!(Some(1), callback.dropped_count_of(&handle));
+        let error = errors.recv().await.unwrap();
+        assert_eq!(DeliveryFailure::Timeout, error.reason);
+        assert_eq!(1, *bounded.try_recv().unwrap());
+        assert!(
+            bounded.try_recv().is_err(),
+            "expected the event to be skipped"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_invoke_ordered_stress() {
+        init_logger!();
+        let total = 5000u32;
+        let callback = MultiThreadedCallback::<u32>::new();
+        let subscriptions = vec![callback.subscribe(), callback.subscribe()];
+
+        for i in 0..total {
+            callback.invoke(i);
+        }
+
+        for mut subscription in subscriptions {
+            for expected in 0..total {
+                let result = time::timeout(Duration::from_secs(5), subscription.recv())
+                    .await
+                    .expect("expected to receive an event")
+                    .unwrap();
+                assert_eq!(
+                    expected, *result,
+                    "expected the events to be received in order"
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invoke_counted() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<u32>::new();
+
+        let result = callback.invoke_counted(1).await;
+        assert_eq!(0, result);
+
+        let mut subscription = callback.subscribe();
+        let dropped = callback.subscribe();
+        drop(dropped);
+        let result = callback.invoke_counted(2).await;
+
+        assert_eq!(1, result);
+        assert_eq!(2, *subscription.recv().await.unwrap());
+    }
+
+    #[test]
+    fn test_single_threaded_invoke_count() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+
+        assert_eq!(0, callback.invoke(1));
+
+        let mut subscription = callback.subscribe();
+        let _second = callback.subscribe();
+        let dropped = callback.subscribe();
+        drop(dropped);
+
+        assert_eq!(2, callback.invoke(2));
+        assert_eq!(2, *subscription.try_recv().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_invoke_dispatched() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe();
+        let _second = callback.subscribe();
+
+        let result = callback.invoke_dispatched(1).await;
+
+        assert_eq!(2, result);
+        assert_eq!(1, *subscription.try_recv().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_multi_threaded_invoke_require_subscribers() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<u32>::new();
+
+        let result = callback.invoke_require_subscribers(1).await;
+        assert_eq!(Err(NoSubscribersError::new(Arc::new(1))), result);
+
+        let mut subscription = callback.subscribe();
+        let result = callback.invoke_require_subscribers(2).await;
+        assert_eq!(Ok(1), result);
+        assert_eq!(2, *subscription.try_recv().unwrap());
+
+        drop(subscription);
+        let result = callback.invoke_require_subscribers(3).await;
+        assert_eq!(Err(NoSubscribersError::new(Arc::new(3))), result);
+    }
+
+    #[test]
+    fn test_invoke_dispatched_internal_runtime() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe();
+
+        // the invocation is dispatched on the internal runtime, as no runtime is available on this thread
+        let future = callback.invoke_dispatched(1);
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        let result = runtime.block_on(future);
+
+        assert_eq!(1, result);
+        assert_eq!(1, *subscription.try_recv().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_invoke_and_confirm() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<u32>::new();
+        let mut acked = callback.subscribe_acked();
+        let mut subscription = callback.subscribe();
 
-        let mut receiver = callback.subscribe();
         tokio::spawn(async move {
-            if let Some(e) = receiver.recv().await {
-                let _ = tx.send(e).await;
+            while let Some(event) = acked.recv_acked().await {
+                assert_eq!(1, **event.value());
+                event.ack();
             }
         });
+        let result = callback
+            .invoke_and_confirm(1, Some(Duration::from_secs(1)))
+            .await;
 
-        callback.invoke(NoneCloneEvent::Bar);
-        let result = select! {
-            _ = time::sleep(Duration::from_millis(150)) => {
-                panic!("Callback invocation receiver timed out")
-            },
-            Some(result) = rx.recv() => result,
-        };
+        assert_eq!(Vec::<CallbackHandle>::new(), result);
+        assert_eq!(1, *subscription.recv().await.unwrap());
+    }
 
-        assert_eq!(NoneCloneEvent::Bar, *result);
+    #[tokio::test]
+    async fn test_invoke_and_confirm_unconfirmed() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let stalled = callback.subscribe_acked();
+        let mut dropping = callback.subscribe_acked();
+        let mut receiving = callback.subscribe_acked();
+
+        tokio::spawn(async move {
+            let event = dropping.recv_acked().await.unwrap();
+            drop(event);
+            let _ = receiving.recv().await;
+        });
+        let result = callback
+            .invoke_and_confirm(1, Some(Duration::from_millis(100)))
+            .await;
+
+        assert_eq!(2, result.len(), "expected 2 unconfirmed subscriptions");
+        assert!(result.contains(&stalled.handle()));
+    }
+
+    #[tokio::test]
+    async fn test_single_consumer() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<u32>::single_consumer();
+        let mut subscription = callback.subscribe();
+
+        let result = callback.invoke_dispatched(1).await;
+        assert_eq!(1, result);
+        assert_eq!(1, *subscription.recv().await.unwrap());
+
+        let result = callback.try_subscribe();
+        assert_eq!(
+            Some(CallbackError::TooManySubscribers {
+                limit: 1,
+                current: 1
+            }),
+            result.err()
+        );
     }
 
     #[test]
-    fn test_single_threaded_invoke() {
+    fn test_single_consumer_dropped() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::single_consumer();
+
+        let subscription = callback.subscribe();
+        drop(subscription);
+        assert_eq!(0, callback.invoke(1));
+        assert_eq!(0, callback.subscriber_count());
+
+        let mut subscription = callback.subscribe();
+        assert_eq!(1, callback.invoke(2));
+        assert_eq!(2, *subscription.try_recv().unwrap());
+    }
+
+    #[test]
+    fn test_single_consumer_inspected() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::single_consumer();
+        let (handle, mut subscription) = callback.subscribe_handle();
+
+        // describing the consumer moves it into the regular subscribers
+        assert_eq!(handle, callback.describe_subscribers()[0].handle);
+        assert_eq!(1, callback.invoke(1));
+        assert_eq!(1, *subscription.try_recv().unwrap());
+
+        assert!(callback.unsubscribe(handle));
+        assert_eq!(0, callback.subscriber_count());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_drop_with_pending_invocations() {
         init_logger!();
-        let expected_result = Event::Foo;
-        let runtime = Runtime::new().unwrap();
         let (tx, rx) = channel();
-        let callback = SingleThreadedCallback::new();
+        let callback = MultiThreadedCallback::<u32>::new();
+        callback.subscribe_fn(move |e| {
+            let _ = tx.send((*e, std::thread::current().id()));
+        });
+        callback.on_drop_emit(|| 0);
 
-        let mut receiver = callback.subscribe();
-        runtime.spawn(async move {
-            if let Some(e) = receiver.recv().await {
-                tx.send(e).unwrap();
+        for i in 1..=100 {
+            callback.invoke(i);
+        }
+        drop(callback);
+
+        let result: Vec<(u32, std::thread::ThreadId)> = rx.try_iter().collect();
+        let values: Vec<u32> = result.iter().map(|(value, _)| *value).collect();
+        let expected: Vec<u32> = (1..=100).chain(std::iter::once(0)).collect();
+        assert_eq!(expected, values);
+        assert_eq!(
+            Some(std::thread::current().id()),
+            result.last().map(|(_, thread)| *thread),
+            "expected the drop event to be invoked on the dropping thread"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invoke_all_ordered_cooperative_ordered_delivery() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<u32>::new();
+        let ticks = Arc::new(AtomicUsize::new(0));
+        let (tx, rx) = channel();
+        let values: Vec<u32> = (0..1000).collect();
+
+        let subscriber_ticks = ticks.clone();
+        callback.subscribe_fn(move |e| {
+            let _ = tx.send((*e, subscriber_ticks.load(Ordering::Relaxed)));
+        });
+        let ticker_ticks = ticks.clone();
+        let ticker = tokio::spawn(async move {
+            loop {
+                ticker_ticks.fetch_add(1, Ordering::Relaxed);
+                tokio::task::yield_now().await;
             }
         });
+        callback.invoke_all_ordered(values.clone()).await;
+        ticker.abort();
 
-        callback.invoke(expected_result.clone());
-        let result = rx.recv_timeout(Duration::from_millis(50)).unwrap();
-
-        assert_eq!(expected_result, *result);
+        let result: Vec<(u32, usize)> = rx.try_iter().collect();
+        let received: Vec<u32> = result.iter().map(|(value, _)| *value).collect();
+        assert_eq!(values, received);
+        let (first, last) = (result[0].1, result[result.len() - 1].1);
+        assert!(
+            last > first,
+            "expected the fan-out task to yield during the batch, ticked {} times",
+            last - first
+        );
     }
 }
@@ -0,0 +1,123 @@
+use crate::callback::ForwardSender;
+use crate::DeliveryFailure;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// The handle of a running event capture of a callback holder, see [crate::MultiThreadedCallback::start_capture].
+///
+/// The capture stops when its window has elapsed, when the captured events are collected, or when this handle is dropped.
+/// The capture subscriber is removed from the holder on the first invocation after the capture has stopped.
+pub struct CaptureHandle<T> {
+    capture: Arc<Capture<T>>,
+}
+
+impl<T> CaptureHandle<T> {
+    /// Check if the capture window has elapsed.
+    pub fn is_finished(&self) -> bool {
+        self.capture.is_finished()
+    }
+
+    /// Get the time which is remaining within the capture window.
+    pub fn remaining(&self) -> Duration {
+        self.capture
+            .deadline
+            .saturating_duration_since(Instant::now())
+    }
+
+    /// Stop the capture and collect the captured events, in the order in which they've been delivered to the capture.
+    /// When the capture window hasn't elapsed yet, only the events which have been captured so far are returned.
+    pub fn collect(self) -> Vec<Arc<T>> {
+        self.capture
+            .events
+            .lock()
+            .expect("failed to acquire lock")
+            .take()
+            .unwrap_or_default()
+    }
+}
+
+impl<T> Debug for CaptureHandle<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CaptureHandle")
+            .field("remaining", &self.remaining())
+            .finish()
+    }
+}
+
+impl<T> Drop for CaptureHandle<T> {
+    fn drop(&mut self) {
+        if let Ok(mut events) = self.capture.events.lock() {
+            *events = None;
+        }
+    }
+}
+
+/// The sender side of a [CaptureHandle], which is registered as a callback of the holder.
+pub(crate) struct CaptureSender<T> {
+    capture: Arc<Capture<T>>,
+}
+
+impl<T> CaptureSender<T> {
+    /// Create a new capture sender and handle pair which captures the events for the given window.
+    pub(crate) fn new(window: Duration) -> (Self, CaptureHandle<T>) {
+        let capture = Arc::new(Capture {
+            deadline: Instant::now() + window,
+            events: Mutex::new(Some(Vec::new())),
+        });
+
+        (
+            Self {
+                capture: capture.clone(),
+            },
+            CaptureHandle { capture },
+        )
+    }
+}
+
+impl<T> ForwardSender<T> for CaptureSender<T>
+where
+    T: Send + Sync,
+{
+    fn send(&self, value: &Arc<T>) -> Result<(), DeliveryFailure> {
+        if self.capture.is_finished() {
+            return Err(DeliveryFailure::Closed);
+        }
+
+        match self
+            .capture
+            .events
+            .lock()
+            .expect("failed to acquire lock")
+            .as_mut()
+        {
+            Some(events) => {
+                events.push(value.clone());
+                Ok(())
+            }
+            None => Err(DeliveryFailure::Closed),
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.capture.is_finished()
+            || self
+                .capture
+                .events
+                .lock()
+                .map(|e| e.is_none())
+                .unwrap_or(true)
+    }
+}
+
+/// The state of an event capture which is shared between the [CaptureSender] and its [CaptureHandle].
+struct Capture<T> {
+    deadline: Instant,
+    events: Mutex<Option<Vec<Arc<T>>>>,
+}
+
+impl<T> Capture<T> {
+    fn is_finished(&self) -> bool {
+        Instant::now() >= self.deadline
+    }
+}
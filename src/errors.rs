@@ -0,0 +1,65 @@
+use crate::CallbackHandle;
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::Arc;
+
+/// The error which is returned when an event is invoked on a callback holder without any registered subscribers.
+/// The error hands back the event which hasn't been delivered to any subscriber.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoSubscribersError<T> {
+    value: Arc<T>,
+}
+
+impl<T> NoSubscribersError<T> {
+    pub(crate) fn new(value: Arc<T>) -> Self {
+        Self { value }
+    }
+
+    /// Get the event which hasn't been delivered to any subscriber.
+    pub fn into_inner(self) -> Arc<T> {
+        self.value
+    }
+}
+
+impl<T> Display for NoSubscribersError<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no subscribers are registered for the event")
+    }
+}
+
+impl<T> std::error::Error for NoSubscribersError<T> where T: Debug {}
+
+/// The error which is returned when a subscriber couldn't be registered or modified on a callback holder.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CallbackError {
+    /// The callback holder already has the maximum number of subscribers registered.
+    TooManySubscribers {
+        /// The maximum number of subscribers of the callback holder.
+        limit: usize,
+        /// The number of subscribers which are currently registered.
+        current: usize,
+    },
+    /// No subscriber is registered for the given handle.
+    UnknownHandle(CallbackHandle),
+    /// The subscriber is already registered with the given handle.
+    DuplicateSubscriber(CallbackHandle),
+}
+
+impl Display for CallbackError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CallbackError::TooManySubscribers { limit, current } => write!(
+                f,
+                "too many subscribers, {} of the maximum {} subscribers are registered",
+                current, limit
+            ),
+            CallbackError::UnknownHandle(handle) => {
+                write!(f, "no subscriber is registered for handle {}", handle)
+            }
+            CallbackError::DuplicateSubscriber(handle) => {
+                write!(f, "the subscriber is already registered as {}", handle)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CallbackError {}
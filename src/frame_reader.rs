@@ -0,0 +1,181 @@
+use crate::{MultiThreadedCallback, TaskHandle};
+use log::{debug, trace, warn};
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
+use std::future::poll_fn;
+use std::io;
+use std::pin::Pin;
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// The maximum length of a single frame payload which is accepted by the frame reader.
+const MAX_FRAME_LENGTH: usize = 16 * 1024 * 1024;
+
+/// Spawn a background task which reads the serialized events from the given byte source and invokes them on the given callback holder.
+/// This is the inverse of the `DebugTap` of the `debug-tap` feature, allowing the events of a callback holder to be shared between processes.
+///
+/// Each event is expected as a frame consisting of a 4 byte big-endian length prefix followed by the JSON encoded event,
+/// which is the same framing as written by the `DebugTap`.
+///
+/// A frame of which the payload can't be deserialized is logged and skipped, as the framing is still intact.
+/// The reader stops when the byte source ends, a read error occurs, a frame is truncated,
+/// or a frame exceeds the maximum length of 16 MiB, as the framing can no longer be trusted in those cases.
+///
+/// The task keeps the callback holder alive until it stops, and is aborted when the returned handle is dropped.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use fx_callback::{spawn_frame_reader, Callback, MultiThreadedCallback};
+///
+/// let callback = MultiThreadedCallback::<String>::new();
+/// let mut receiver = callback.subscribe();
+///
+/// let source: &'static [u8] = &[0, 0, 0, 5, b'"', b'F', b'o', b'o', b'"'];
+/// let handle = spawn_frame_reader(source, &callback);
+/// ```
+pub fn spawn_frame_reader<T, R>(reader: R, callback: &MultiThreadedCallback<T>) -> TaskHandle
+where
+    T: Debug + DeserializeOwned + Send + Sync + 'static,
+    R: AsyncRead + Send + Unpin + 'static,
+{
+    let holder = callback.clone();
+    TaskHandle::new(callback.spawn(async move {
+        let mut reader = reader;
+        loop {
+            match read_frame(&mut reader).await {
+                Ok(Some(payload)) => match serde_json::from_slice::<T>(&payload) {
+                    Ok(event) => holder.invoke(event),
+                    Err(e) => warn!("Frame reader failed to deserialize frame, {}", e),
+                },
+                Ok(None) => {
+                    debug!("Frame reader source has ended");
+                    break;
+                }
+                Err(e) => {
+                    warn!("Frame reader failed to read frame, {}", e);
+                    break;
+                }
+            }
+        }
+        trace!("Frame reader has stopped");
+    }))
+}
+
+/// Read the payload of the next frame from the given reader.
+///
+/// # Returns
+///
+/// It returns [None] when the reader has ended before the start of a new frame.
+async fn read_frame<R>(reader: &mut R) -> io::Result<Option<Vec<u8>>>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut length = [0u8; 4];
+    if !read_exact(reader, &mut length).await? {
+        return Ok(None);
+    }
+
+    let length = u32::from_be_bytes(length) as usize;
+    if length > MAX_FRAME_LENGTH {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("frame length {} exceeds the maximum length", length),
+        ));
+    }
+
+    let mut payload = vec![0u8; length];
+    if !read_exact(reader, &mut payload).await? {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(Some(payload))
+}
+
+/// Fill the given buffer completely from the given reader.
+///
+/// # Returns
+///
+/// It returns `false` when the reader has ended before any byte has been read,
+/// or an [io::ErrorKind::UnexpectedEof] error when it ended after the buffer was partially filled.
+async fn read_exact<R>(reader: &mut R, buffer: &mut [u8]) -> io::Result<bool>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut filled = 0;
+    while filled < buffer.len() {
+        let read = poll_fn(|cx| {
+            let mut buf = ReadBuf::new(&mut buffer[filled..]);
+            Pin::new(&mut *reader)
+                .poll_read(cx, &mut buf)
+                .map_ok(|_| buf.filled().len())
+        })
+        .await?;
+
+        if read == 0 {
+            if filled == 0 {
+                return Ok(false);
+            }
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        filled += read;
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{init_logger, Callback};
+    use std::time::Duration;
+    use tokio::time;
+
+    fn frame(payload: &[u8]) -> Vec<u8> {
+        let mut frame = (payload.len() as u32).to_be_bytes().to_vec();
+        frame.extend_from_slice(payload);
+        frame
+    }
+
+    #[tokio::test]
+    async fn test_spawn_frame_reader() {
+        init_logger!();
+        let callback = MultiThreadedCallback::<String>::new();
+        let mut receiver = callback.subscribe();
+        let mut source = frame(b"\"Foo\"");
+        source.extend(frame(b"{malformed"));
+        source.extend(frame(b"\"Bar\""));
+
+        let handle = spawn_frame_reader(io::Cursor::new(source), &callback);
+
+        let result = time::timeout(Duration::from_millis(250), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!("Foo", result.as_str());
+        let result = time::timeout(Duration::from_millis(250), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!("Bar", result.as_str());
+        time::timeout(Duration::from_millis(250), async {
+            while !handle.is_finished() {
+                time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("expected the frame reader to have stopped");
+    }
+
+    #[tokio::test]
+    async fn test_read_frame_truncated() {
+        init_logger!();
+        let mut source: &[u8] = &[0, 0, 0, 5, b'"', b'F'];
+
+        let result = read_frame(&mut source).await;
+
+        assert_eq!(
+            io::ErrorKind::UnexpectedEof,
+            result.unwrap_err().kind(),
+            "expected a truncated frame error"
+        );
+    }
+}
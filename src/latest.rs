@@ -0,0 +1,211 @@
+use crate::callback::ForwardSender;
+use crate::DeliveryFailure;
+use std::fmt::{Debug, Formatter};
+use std::future::poll_fn;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+/// A conflating subscription which only holds the newest event.
+/// A newer event overwrites the event which hasn't been received yet,
+/// so a lagging consumer always receives the freshest event instead of processing a backlog of stale events.
+///
+/// Dropping this subscription removes the callback on the next invocation.
+pub struct LatestSubscription<T> {
+    slot: Arc<LatestSlot<T>>,
+}
+
+impl<T> LatestSubscription<T> {
+    /// Receive the newest event of this subscription.
+    ///
+    /// # Returns
+    ///
+    /// It returns [None] when the callback holder has been dropped and the held event has been received.
+    pub async fn recv(&mut self) -> Option<Arc<T>> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Poll to receive the newest event of this subscription.
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Arc<T>>> {
+        let mut state = self.slot.lock();
+        if let Some(event) = state.take() {
+            return Poll::Ready(Some(event));
+        }
+        if state.sender_closed {
+            return Poll::Ready(None);
+        }
+
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+
+    /// Try to receive the newest event of this subscription without waiting.
+    ///
+    /// # Returns
+    ///
+    /// It returns [None] when no new event is currently held.
+    pub fn try_recv(&mut self) -> Option<Arc<T>> {
+        self.slot.lock().take()
+    }
+
+    /// Get the number of events which have been overwritten before the last received event.
+    pub fn overwritten(&self) -> usize {
+        self.slot.lock().last_overwritten
+    }
+
+    /// Get the total number of events which have been overwritten for this subscription.
+    pub fn overwritten_total(&self) -> u64 {
+        self.slot.overwritten.load(Ordering::Relaxed)
+    }
+}
+
+impl<T> Debug for LatestSubscription<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LatestSubscription")
+            .field("pending", &self.slot.lock().event.is_some())
+            .field("overwritten", &self.overwritten_total())
+            .finish()
+    }
+}
+
+impl<T> Drop for LatestSubscription<T> {
+    fn drop(&mut self) {
+        let mut state = self.slot.lock();
+        state.receiver_closed = true;
+        state.event = None;
+    }
+}
+
+/// The sender side of a [LatestSubscription], which is registered as a callback of the holder.
+pub(crate) struct LatestSender<T> {
+    slot: Arc<LatestSlot<T>>,
+}
+
+impl<T> LatestSender<T> {
+    /// Create a new latest sender and subscription pair.
+    pub(crate) fn new() -> (Self, LatestSubscription<T>) {
+        let slot = Arc::new(LatestSlot {
+            overwritten: AtomicU64::new(0),
+            state: Mutex::new(LatestState {
+                event: None,
+                pending_overwritten: 0,
+                last_overwritten: 0,
+                waker: None,
+                receiver_closed: false,
+                sender_closed: false,
+            }),
+        });
+
+        (Self { slot: slot.clone() }, LatestSubscription { slot })
+    }
+}
+
+impl<T> ForwardSender<T> for LatestSender<T>
+where
+    T: Send + Sync,
+{
+    fn send(&self, value: &Arc<T>) -> Result<(), DeliveryFailure> {
+        let mut state = self.slot.lock();
+        if state.receiver_closed {
+            return Err(DeliveryFailure::Closed);
+        }
+
+        if state.event.replace(value.clone()).is_some() {
+            state.pending_overwritten += 1;
+            self.slot.overwritten.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let waker = state.waker.take();
+        drop(state);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        Ok(())
+    }
+
+    fn is_closed(&self) -> bool {
+        self.slot.lock().receiver_closed
+    }
+
+    fn queue_depth(&self) -> Option<usize> {
+        Some(self.slot.lock().event.is_some() as usize)
+    }
+}
+
+impl<T> Drop for LatestSender<T> {
+    fn drop(&mut self) {
+        let mut state = self.slot.lock();
+        state.sender_closed = true;
+        let waker = state.waker.take();
+        drop(state);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// The slot which is shared between a [LatestSender] and its [LatestSubscription].
+struct LatestSlot<T> {
+    overwritten: AtomicU64,
+    state: Mutex<LatestState<T>>,
+}
+
+impl<T> LatestSlot<T> {
+    fn lock(&self) -> std::sync::MutexGuard<'_, LatestState<T>> {
+        self.state.lock().expect("failed to acquire lock")
+    }
+}
+
+struct LatestState<T> {
+    event: Option<Arc<T>>,
+    pending_overwritten: usize,
+    last_overwritten: usize,
+    waker: Option<Waker>,
+    receiver_closed: bool,
+    sender_closed: bool,
+}
+
+impl<T> LatestState<T> {
+    /// Take the held event, moving its overwritten count to the last received event.
+    fn take(&mut self) -> Option<Arc<T>> {
+        let event = self.event.take()?;
+        self.last_overwritten = std::mem::take(&mut self.pending_overwritten);
+        Some(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::init_logger;
+
+    #[test]
+    fn test_send_overwrites() {
+        init_logger!();
+        let (sender, mut subscription) = LatestSender::<u32>::new();
+
+        for i in 0..3 {
+            sender.send(&Arc::new(i)).unwrap();
+        }
+
+        assert_eq!(Some(Arc::new(2)), subscription.try_recv());
+        assert_eq!(2, subscription.overwritten());
+        assert_eq!(None, subscription.try_recv());
+
+        sender.send(&Arc::new(3)).unwrap();
+        assert_eq!(Some(Arc::new(3)), subscription.try_recv());
+        assert_eq!(0, subscription.overwritten());
+        assert_eq!(2, subscription.overwritten_total());
+    }
+
+    #[test]
+    fn test_send_closed_subscription() {
+        init_logger!();
+        let (sender, subscription) = LatestSender::<u32>::new();
+
+        drop(subscription);
+
+        assert!(sender.is_closed());
+        assert_eq!(Err(DeliveryFailure::Closed), sender.send(&Arc::new(1)));
+    }
+}
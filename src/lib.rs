@@ -11,7 +11,7 @@ It is mainly used within the FX landscape to allow events to be published betwee
 ## Example
 
 ```rust
-use fx_callback::{Callback, MultiThreadedCallback, Subscriber, Subscription};
+use fx_callback::{Callback, CallbackHandle, MultiThreadedCallback, Subscriber, Subscription};
 
 /// The events of the struct that informs subscribers about changes to the data within the struct.
 #[derive(Debug, Clone, PartialEq)]
@@ -36,9 +36,17 @@ impl Callback<MyEvent> for Example {
         self.callbacks.subscribe()
     }
 
-    fn subscribe_with(&self, subscriber: Subscriber<MyEvent>) {
+    fn subscribe_with(&self, subscriber: Subscriber<MyEvent>) -> CallbackHandle {
         self.callbacks.subscribe_with(subscriber)
     }
+
+    fn unsubscribe(&self, handle: CallbackHandle) -> bool {
+        self.callbacks.unsubscribe(handle)
+    }
+
+    fn subscriber_count(&self) -> usize {
+        self.callbacks.subscriber_count()
+    }
 }
 ```
 
@@ -49,7 +57,7 @@ impl Callback<MyEvent> for Example {
 To get started with adding callbacks to your structs, add one of the implementations of the `Callback` trait.
 Make sure that the struct implements the `Debug` trait.
 
-```rust
+```rust,ignore
 use fx_callback::{Callback, MultiThreadedCallback};
 
 #[derive(Debug)]
@@ -60,21 +68,29 @@ pub struct MyStruct {
 
 Add the `Callback` trait implementation to your struct to allow adding callbacks.
 
-```rust
+```rust,ignore
 impl Callback<MyEvent> for MyStruct {
     fn subscribe(&self) -> Subscription<MyEvent> {
         self.callbacks.subscribe()
     }
 
-    fn subscribe_with(&self, subscriber: Subscriber<MyEvent>) {
+    fn subscribe_with(&self, subscriber: Subscriber<MyEvent>) -> CallbackHandle {
         self.callbacks.subscribe_with(subscriber)
     }
+
+    fn unsubscribe(&self, handle: CallbackHandle) -> bool {
+        self.callbacks.unsubscribe(handle)
+    }
+
+    fn subscriber_count(&self) -> usize {
+        self.callbacks.subscriber_count()
+    }
 }
 ```
 
 When you want to inform subscribers about a certain event, call the `invoke` method.
 
-```rust
+```rust,ignore
 impl MyStruct {
     pub fn invoke_event(&self) {
         self.callbacks.invoke(MyEvent::Foo);
@@ -86,7 +102,7 @@ impl MyStruct {
 
 The interested subscriber can subscribe to the interested event of a struct that implements the `Callback` trait.
 
-```rust
+```rust,ignore
 use fx_callback::{Callback, MultiThreadedCallback, Subscriber, Subscription};
 use tokio::runtime::Runtime;
 
@@ -110,10 +126,65 @@ fn main() {
 ```
 */
 
+#[doc(inline)]
+pub use acked::*;
+#[doc(inline)]
+pub use adaptive::*;
+#[doc(inline)]
+pub use any_bus::*;
+#[doc(inline)]
+pub use builder::*;
 #[doc(inline)]
 pub use callback::*;
+#[doc(inline)]
+pub use capture::*;
+#[doc(inline)]
+pub use errors::*;
+#[cfg(feature = "serde")]
+#[doc(inline)]
+pub use frame_reader::*;
+#[doc(inline)]
+pub use latest::*;
+#[doc(inline)]
+pub use load_balanced::*;
+#[doc(inline)]
+pub use overflow::*;
+#[doc(inline)]
+pub use replay::*;
+#[doc(inline)]
+pub use request::*;
+#[doc(inline)]
+pub use state::*;
+#[doc(inline)]
+pub use stats::*;
+#[doc(inline)]
+pub use stream::*;
+#[doc(inline)]
+pub use subscription::*;
+#[cfg(feature = "debug-tap")]
+#[doc(inline)]
+pub use tap::*;
 
+mod acked;
+mod adaptive;
+mod any_bus;
+mod builder;
 mod callback;
+mod capture;
+mod errors;
+#[cfg(feature = "serde")]
+mod frame_reader;
+mod latest;
+mod load_balanced;
+mod overflow;
+mod replay;
+mod request;
+mod state;
+mod stats;
+mod stream;
+mod subscription;
+#[cfg(feature = "debug-tap")]
+mod tap;
 
 #[cfg(test)]
 pub(crate) mod tests {
@@ -130,10 +201,10 @@ pub(crate) mod tests {
     #[macro_export]
     macro_rules! init_logger {
         ($level:expr) => {
-            crate::tests::init_logger_level($level)
+            $crate::tests::init_logger_level($level)
         };
         () => {
-            crate::tests::init_logger_level(log::LevelFilter::Trace)
+            $crate::tests::init_logger_level(log::LevelFilter::Trace)
         };
     }
 
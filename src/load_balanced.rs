@@ -0,0 +1,257 @@
+use crate::{Callback, CallbackHandle, Subscriber, Subscription};
+use log::{debug, trace, warn};
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex};
+
+/// A load balancing callback holder which delivers each event to exactly one of its subscribers.
+///
+/// Unlike the broadcasting [crate::MultiThreadedCallback] and [crate::SingleThreadedCallback] holders,
+/// which deliver each event to all subscribers, this holder distributes the events over its subscribers
+/// to share the load between them, e.g. between multiple workers.
+/// The subscriber is selected through a smooth weighted round-robin,
+/// where each subscriber receives a share of the events proportional to its weight.
+///
+/// The events are delivered on the caller thread.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use fx_callback::{Callback, LoadBalancedCallback};
+///
+/// #[derive(Debug)]
+/// pub struct Job(u32);
+///
+/// let callback = LoadBalancedCallback::<Job>::new();
+/// let mut worker1 = callback.subscribe();
+/// let mut worker2 = callback.subscribe_weighted(2);
+///
+/// callback.invoke(Job(1));
+/// ```
+#[derive(Clone)]
+pub struct LoadBalancedCallback<T>
+where
+    T: Debug + Send + Sync,
+{
+    inner: Arc<Mutex<Balancer<T>>>,
+}
+
+impl<T> LoadBalancedCallback<T>
+where
+    T: Debug + Send + Sync,
+{
+    /// Create a new load balancing callback holder.
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Balancer {
+                subscribers: Vec::new(),
+            })),
+        }
+    }
+
+    /// Subscribe to the interested event with the given weight.
+    /// A subscriber with a higher weight receives a proportionally larger share of the events.
+    ///
+    /// A weight of 0 is treated as a weight of 1.
+    pub fn subscribe_weighted(&self, weight: u32) -> Subscription<T> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        self.insert(tx, weight);
+        rx
+    }
+
+    /// Invoke exactly one of the currently registered subscribers with the given value.
+    ///
+    /// When the selected subscriber has been dropped, it's removed and another subscriber is selected instead.
+    ///
+    /// # Returns
+    ///
+    /// It returns the handle of the subscriber which received the value, or [None] when there are no subscribers.
+    pub fn invoke(&self, value: T) -> Option<CallbackHandle> {
+        let mut mutex = self.inner.lock().expect("failed to acquire lock");
+        let mut value = Arc::new(value);
+
+        while let Some(index) = mutex.next() {
+            let subscriber = &mutex.subscribers[index];
+            let handle = subscriber.handle;
+            match subscriber.sender.send(value) {
+                Ok(_) => {
+                    trace!("Invoked load balanced callback {}", handle);
+                    return Some(handle);
+                }
+                Err(e) => {
+                    debug!("Load balanced callback {} has been dropped", handle);
+                    value = e.0;
+                    mutex.subscribers.remove(index);
+                }
+            }
+        }
+
+        warn!("No load balanced callbacks available for {:?}", value);
+        None
+    }
+
+    fn insert(&self, sender: Subscriber<T>, weight: u32) -> CallbackHandle {
+        let mut mutex = self.inner.lock().expect("failed to acquire lock");
+        let handle = CallbackHandle::new();
+        mutex.subscribers.push(WeightedSubscriber {
+            handle,
+            sender,
+            weight: weight.max(1) as i64,
+            current: 0,
+        });
+        trace!(
+            "Added load balanced callback {} with weight {}",
+            handle,
+            weight
+        );
+        handle
+    }
+}
+
+impl<T> Callback<T> for LoadBalancedCallback<T>
+where
+    T: Debug + Send + Sync,
+{
+    fn subscribe(&self) -> Subscription<T> {
+        self.subscribe_weighted(1)
+    }
+
+    fn subscribe_with(&self, subscriber: Subscriber<T>) -> CallbackHandle {
+        self.insert(subscriber, 1)
+    }
+
+    fn unsubscribe(&self, handle: CallbackHandle) -> bool {
+        let mut mutex = self.inner.lock().expect("failed to acquire lock");
+        let total = mutex.subscribers.len();
+        mutex.subscribers.retain(|e| e.handle != handle);
+        let removed = mutex.subscribers.len() < total;
+        if removed {
+            trace!("Removed load balanced callback {}", handle);
+        }
+        removed
+    }
+
+    fn subscriber_count(&self) -> usize {
+        self.inner.lock().map(|e| e.subscribers.len()).unwrap_or(0)
+    }
+}
+
+impl<T> Default for LoadBalancedCallback<T>
+where
+    T: Debug + Send + Sync,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Debug for LoadBalancedCallback<T>
+where
+    T: Debug + Send + Sync,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoadBalancedCallback")
+            .field(
+                "subscribers",
+                &self.inner.lock().map(|e| e.subscribers.len()).unwrap_or(0),
+            )
+            .finish()
+    }
+}
+
+struct Balancer<T> {
+    subscribers: Vec<WeightedSubscriber<T>>,
+}
+
+impl<T> Balancer<T> {
+    /// Select the index of the next subscriber through a smooth weighted round-robin.
+    fn next(&mut self) -> Option<usize> {
+        let total: i64 = self.subscribers.iter().map(|e| e.weight).sum();
+        let mut selected: Option<(usize, i64)> = None;
+
+        for (index, subscriber) in self.subscribers.iter_mut().enumerate() {
+            subscriber.current += subscriber.weight;
+            if selected.map_or(true, |(_, current)| subscriber.current > current) {
+                selected = Some((index, subscriber.current));
+            }
+        }
+
+        selected.map(|(index, _)| {
+            self.subscribers[index].current -= total;
+            index
+        })
+    }
+}
+
+struct WeightedSubscriber<T> {
+    handle: CallbackHandle,
+    sender: Subscriber<T>,
+    weight: i64,
+    current: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::init_logger;
+
+    #[test]
+    fn test_invoke_distribution() {
+        init_logger!();
+        let callback = LoadBalancedCallback::<u32>::new();
+
+        let mut first = callback.subscribe();
+        let mut second = callback.subscribe_weighted(3);
+        for i in 0..8 {
+            callback.invoke(i);
+        }
+
+        let mut first_total = 0;
+        while first.try_recv().is_ok() {
+            first_total += 1;
+        }
+        let mut second_total = 0;
+        while second.try_recv().is_ok() {
+            second_total += 1;
+        }
+        assert_eq!(2, first_total);
+        assert_eq!(6, second_total);
+    }
+
+    #[test]
+    fn test_invoke_closed_subscriber() {
+        init_logger!();
+        let callback = LoadBalancedCallback::<u32>::new();
+
+        let first = callback.subscribe();
+        let mut second = callback.subscribe();
+        drop(first);
+        callback.invoke(1);
+        callback.invoke(2);
+
+        assert_eq!(1, *second.try_recv().unwrap());
+        assert_eq!(2, *second.try_recv().unwrap());
+    }
+
+    #[test]
+    fn test_invoke_no_subscribers() {
+        init_logger!();
+        let callback = LoadBalancedCallback::<u32>::new();
+
+        let result = callback.invoke(1);
+
+        assert_eq!(None, result);
+    }
+
+    #[test]
+    fn test_unsubscribe() {
+        init_logger!();
+        let callback = LoadBalancedCallback::<u32>::new();
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let handle = callback.subscribe_with(tx);
+        let result = callback.unsubscribe(handle);
+
+        assert!(result, "expected the subscriber to be removed");
+        assert_eq!(None, callback.invoke(1));
+    }
+}
@@ -0,0 +1,375 @@
+use crate::callback::ForwardSender;
+use crate::DeliveryFailure;
+use std::collections::VecDeque;
+use std::fmt::{Debug, Formatter};
+use std::future::poll_fn;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+/// The policy which is applied when an event is invoked while the queue of an [OverflowSubscription] is full.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// The new event isn't delivered to the subscription, keeping the oldest queued events intact.
+    /// The dropped events are counted per subscriber by the callback holder.
+    #[default]
+    DropNewest,
+    /// The oldest queued event is evicted to make room for the new event,
+    /// so the consumer always receives the most recent window of events.
+    DropOldest,
+}
+
+/// The item which is received through [OverflowSubscription::recv_lagged].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Received<T> {
+    /// The next event of the subscription.
+    Event(Arc<T>),
+    /// The given number of events have been dropped for the subscription at this position,
+    /// allowing the consumer to resynchronize its state.
+    Lagged(u64),
+}
+
+/// A bounded subscription which applies its [OverflowPolicy] when an event is invoked while its queue is full.
+///
+/// Dropping this subscription removes the callback on the next invocation.
+pub struct OverflowSubscription<T> {
+    queue: Arc<OverflowQueue<T>>,
+}
+
+impl<T> OverflowSubscription<T> {
+    /// Receive the next event of this subscription.
+    ///
+    /// # Returns
+    ///
+    /// It returns [None] when the callback holder has been dropped and all queued events have been received.
+    pub async fn recv(&mut self) -> Option<Arc<T>> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Poll to receive the next event of this subscription.
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Arc<T>>> {
+        let mut state = self.queue.lock();
+        if let Some((event, _)) = state.events.pop_front() {
+            return Poll::Ready(Some(event));
+        }
+        if state.sender_closed {
+            return Poll::Ready(None);
+        }
+
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+
+    /// Receive the next event of this subscription, or the number of events which have been dropped before it.
+    /// The dropped events are reported exactly once at the position at which they have been dropped,
+    /// so the consumer knows that it missed events instead of silently skipping them.
+    ///
+    /// The drops which are only preceding the events which are received through [OverflowSubscription::recv] aren't reported.
+    ///
+    /// # Returns
+    ///
+    /// It returns [None] when the callback holder has been dropped and all queued events have been received.
+    pub async fn recv_lagged(&mut self) -> Option<Received<T>> {
+        poll_fn(|cx| self.poll_recv_lagged(cx)).await
+    }
+
+    /// Poll to receive the next event of this subscription, or the number of events which have been dropped before it.
+    /// See [OverflowSubscription::recv_lagged] for more info.
+    pub fn poll_recv_lagged(&mut self, cx: &mut Context<'_>) -> Poll<Option<Received<T>>> {
+        let mut state = self.queue.lock();
+        if let Some((_, lagged)) = state.events.front_mut() {
+            if *lagged > 0 {
+                return Poll::Ready(Some(Received::Lagged(std::mem::take(lagged))));
+            }
+        }
+        if let Some((event, _)) = state.events.pop_front() {
+            return Poll::Ready(Some(Received::Event(event)));
+        }
+        if state.tail_lagged > 0 {
+            return Poll::Ready(Some(Received::Lagged(std::mem::take(
+                &mut state.tail_lagged,
+            ))));
+        }
+        if state.sender_closed {
+            return Poll::Ready(None);
+        }
+
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+
+    /// Try to receive the next event of this subscription without waiting.
+    ///
+    /// # Returns
+    ///
+    /// It returns [None] when no event is currently queued.
+    pub fn try_recv(&mut self) -> Option<Arc<T>> {
+        self.queue.lock().events.pop_front().map(|(event, _)| event)
+    }
+
+    /// Get the number of events which are currently queued for this subscription.
+    pub fn len(&self) -> usize {
+        self.queue.lock().events.len()
+    }
+
+    /// Check if no events are currently queued for this subscription.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the capacity of the queue of this subscription.
+    pub fn capacity(&self) -> usize {
+        self.queue.capacity
+    }
+
+    /// Get the overflow policy of this subscription.
+    pub fn policy(&self) -> OverflowPolicy {
+        self.queue.policy
+    }
+
+    /// Get the total number of events which have been evicted from the queue of this subscription.
+    pub fn evicted_count(&self) -> u64 {
+        self.queue.evicted.load(Ordering::Relaxed)
+    }
+}
+
+impl<T> Debug for OverflowSubscription<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OverflowSubscription")
+            .field("capacity", &self.queue.capacity)
+            .field("policy", &self.queue.policy)
+            .field("len", &self.len())
+            .field("evicted", &self.evicted_count())
+            .finish()
+    }
+}
+
+impl<T> Drop for OverflowSubscription<T> {
+    fn drop(&mut self) {
+        let mut state = self.queue.lock();
+        state.receiver_closed = true;
+        state.events.clear();
+    }
+}
+
+/// The sender side of an [OverflowSubscription], which is registered as a callback of the holder.
+pub(crate) struct OverflowSender<T> {
+    queue: Arc<OverflowQueue<T>>,
+}
+
+impl<T> OverflowSender<T> {
+    /// Create a new overflow sender and subscription pair with the given capacity and policy.
+    ///
+    /// # Panics
+    ///
+    /// It panics when the given capacity is 0.
+    pub(crate) fn new(capacity: usize, policy: OverflowPolicy) -> (Self, OverflowSubscription<T>) {
+        assert!(capacity > 0, "capacity must be greater than 0");
+        let queue = Arc::new(OverflowQueue {
+            capacity,
+            policy,
+            evicted: AtomicU64::new(0),
+            state: Mutex::new(OverflowState {
+                events: VecDeque::with_capacity(capacity),
+                tail_lagged: 0,
+                waker: None,
+                receiver_closed: false,
+                sender_closed: false,
+            }),
+        });
+
+        (
+            Self {
+                queue: queue.clone(),
+            },
+            OverflowSubscription { queue },
+        )
+    }
+}
+
+impl<T> ForwardSender<T> for OverflowSender<T>
+where
+    T: Send + Sync,
+{
+    fn send(&self, value: &Arc<T>) -> Result<(), DeliveryFailure> {
+        let mut state = self.queue.lock();
+        if state.receiver_closed {
+            return Err(DeliveryFailure::Closed);
+        }
+
+        if state.events.len() >= self.queue.capacity {
+            match self.queue.policy {
+                OverflowPolicy::DropNewest => {
+                    state.tail_lagged += 1;
+                    return Err(DeliveryFailure::Full);
+                }
+                OverflowPolicy::DropOldest => {
+                    if let Some((_, lagged)) = state.events.pop_front() {
+                        if let Some((_, next)) = state.events.front_mut() {
+                            *next += lagged + 1;
+                        } else {
+                            state.tail_lagged += lagged + 1;
+                        }
+                    }
+                    self.queue.evicted.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+        let lagged = std::mem::take(&mut state.tail_lagged);
+        state.events.push_back((value.clone(), lagged));
+
+        let waker = state.waker.take();
+        drop(state);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        Ok(())
+    }
+
+    fn is_closed(&self) -> bool {
+        self.queue.lock().receiver_closed
+    }
+
+    fn queue_depth(&self) -> Option<usize> {
+        Some(self.queue.lock().events.len())
+    }
+
+    fn dropped_count(&self) -> u64 {
+        self.queue.evicted.load(Ordering::Relaxed)
+    }
+}
+
+impl<T> Drop for OverflowSender<T> {
+    fn drop(&mut self) {
+        let mut state = self.queue.lock();
+        state.sender_closed = true;
+        let waker = state.waker.take();
+        drop(state);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// The queue which is shared between an [OverflowSender] and its [OverflowSubscription].
+struct OverflowQueue<T> {
+    capacity: usize,
+    policy: OverflowPolicy,
+    evicted: AtomicU64,
+    state: Mutex<OverflowState<T>>,
+}
+
+impl<T> OverflowQueue<T> {
+    fn lock(&self) -> std::sync::MutexGuard<'_, OverflowState<T>> {
+        self.state.lock().expect("failed to acquire lock")
+    }
+}
+
+struct OverflowState<T> {
+    /// The queued events, together with the number of events which have been dropped right before each event.
+    events: VecDeque<(Arc<T>, u64)>,
+    /// The number of events which have been dropped after the last queued event.
+    tail_lagged: u64,
+    waker: Option<Waker>,
+    receiver_closed: bool,
+    sender_closed: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::init_logger;
+
+    #[test]
+    fn test_send_drop_oldest() {
+        init_logger!();
+        let (sender, mut subscription) = OverflowSender::<u32>::new(2, OverflowPolicy::DropOldest);
+
+        for i in 0..4 {
+            sender.send(&Arc::new(i)).unwrap();
+        }
+
+        assert_eq!(2, subscription.evicted_count());
+        assert_eq!(2, sender.dropped_count());
+        assert_eq!(Some(Arc::new(2)), subscription.try_recv());
+        assert_eq!(Some(Arc::new(3)), subscription.try_recv());
+        assert_eq!(None, subscription.try_recv());
+    }
+
+    #[test]
+    fn test_send_closed_subscription() {
+        init_logger!();
+        let (sender, subscription) = OverflowSender::<u32>::new(2, OverflowPolicy::DropOldest);
+
+        drop(subscription);
+
+        assert!(sender.is_closed());
+        assert_eq!(Err(DeliveryFailure::Closed), sender.send(&Arc::new(1)));
+    }
+
+    #[test]
+    fn test_send_drop_newest() {
+        init_logger!();
+        let (sender, mut subscription) = OverflowSender::<u32>::new(2, OverflowPolicy::DropNewest);
+
+        for i in 0..2 {
+            sender.send(&Arc::new(i)).unwrap();
+        }
+        let result = sender.send(&Arc::new(2));
+
+        assert_eq!(Err(DeliveryFailure::Full), result);
+        assert_eq!(0, subscription.evicted_count());
+        assert_eq!(Some(Arc::new(0)), subscription.try_recv());
+        assert_eq!(Some(Arc::new(1)), subscription.try_recv());
+        assert_eq!(None, subscription.try_recv());
+    }
+
+    #[tokio::test]
+    async fn test_recv_lagged_drop_newest() {
+        init_logger!();
+        let (sender, mut subscription) = OverflowSender::<u32>::new(2, OverflowPolicy::DropNewest);
+
+        for i in 0..5 {
+            let _ = sender.send(&Arc::new(i));
+        }
+        assert_eq!(
+            Some(Received::Event(Arc::new(0))),
+            subscription.recv_lagged().await
+        );
+        sender.send(&Arc::new(5)).unwrap();
+        drop(sender);
+
+        assert_eq!(
+            Some(Received::Event(Arc::new(1))),
+            subscription.recv_lagged().await
+        );
+        assert_eq!(Some(Received::Lagged(3)), subscription.recv_lagged().await);
+        assert_eq!(
+            Some(Received::Event(Arc::new(5))),
+            subscription.recv_lagged().await
+        );
+        assert_eq!(None, subscription.recv_lagged().await);
+    }
+
+    #[tokio::test]
+    async fn test_recv_lagged_drop_oldest() {
+        init_logger!();
+        let (sender, mut subscription) = OverflowSender::<u32>::new(2, OverflowPolicy::DropOldest);
+
+        for i in 0..5 {
+            sender.send(&Arc::new(i)).unwrap();
+        }
+        drop(sender);
+
+        assert_eq!(Some(Received::Lagged(3)), subscription.recv_lagged().await);
+        assert_eq!(
+            Some(Received::Event(Arc::new(3))),
+            subscription.recv_lagged().await
+        );
+        assert_eq!(
+            Some(Received::Event(Arc::new(4))),
+            subscription.recv_lagged().await
+        );
+        assert_eq!(None, subscription.recv_lagged().await);
+    }
+}
@@ -0,0 +1,313 @@
+use crate::{
+    AdaptiveSubscription, Callback, CallbackHandle, SingleThreadedCallback, Subscriber,
+    Subscription,
+};
+use indexmap::IndexMap;
+use log::debug;
+use std::fmt::{Debug, Formatter};
+use std::future::poll_fn;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+/// The extractor of the replay key of an event.
+type KeyExtractor<K, T> = dyn Fn(&T) -> K + Send + Sync;
+
+/// A callback holder which replays the latest event of each key to late subscribers.
+///
+/// Unlike replaying the last N raw events, which delivers the full history including stale updates of the same key,
+/// the replay buffer only keeps the latest event per key, giving late subscribers a snapshot of the current state per key.
+/// At most `capacity` keys are retained, when a new key exceeds the capacity,
+/// the key which has been updated the longest ago is evicted.
+///
+/// The retained events are replayed in the order in which their keys have last been updated, from oldest to newest,
+/// so the most recent update is always replayed last.
+/// The events are delivered on the caller thread.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use fx_callback::{Callback, KeyedReplayCallback};
+///
+/// #[derive(Debug)]
+/// pub struct Progress {
+///     id: u32,
+///     percentage: u8,
+/// }
+///
+/// let callback = KeyedReplayCallback::new(100, |e: &Progress| e.id);
+/// callback.invoke(Progress { id: 1, percentage: 10 });
+/// callback.invoke(Progress { id: 1, percentage: 20 });
+///
+/// // the late subscriber only receives the latest progress of id 1
+/// let mut receiver = callback.subscribe();
+/// ```
+#[derive(Clone)]
+pub struct KeyedReplayCallback<K, T>
+where
+    T: Debug + Send + Sync,
+{
+    cache: Arc<Mutex<IndexMap<K, Arc<T>>>>,
+    capacity: usize,
+    key_extractor: Arc<KeyExtractor<K, T>>,
+    callback: SingleThreadedCallback<T>,
+}
+
+impl<K, T> KeyedReplayCallback<K, T>
+where
+    K: Eq + Hash + Send + Sync,
+    T: Debug + Send + Sync,
+{
+    /// Create a new keyed replay callback holder which retains the latest event of at most `capacity` keys.
+    ///
+    /// # Panics
+    ///
+    /// It panics when the given capacity is 0.
+    pub fn new<F>(capacity: usize, key_extractor: F) -> Self
+    where
+        F: Fn(&T) -> K + Send + Sync + 'static,
+    {
+        assert!(capacity > 0, "capacity must be greater than 0");
+        Self {
+            cache: Arc::new(Mutex::new(IndexMap::with_capacity(capacity))),
+            capacity,
+            key_extractor: Arc::new(key_extractor),
+            callback: SingleThreadedCallback::new(),
+        }
+    }
+
+    /// Get the latest event of the given key, if retained.
+    pub fn latest(&self, key: &K) -> Option<Arc<T>> {
+        self.cache
+            .lock()
+            .expect("failed to acquire lock")
+            .get(key)
+            .cloned()
+    }
+
+    /// Get the retained latest event of each key, in the order in which they would be replayed.
+    pub fn snapshot(&self) -> Vec<Arc<T>> {
+        self.cache
+            .lock()
+            .expect("failed to acquire lock")
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Subscribe to the interested event with recovery for when the subscriber falls too far behind.
+    ///
+    /// The subscription queues its events up to the given high watermark, see [SingleThreadedCallback::subscribe_adaptive].
+    /// Once events have been dropped because the watermark was exceeded, the subscription yields a [ResyncEvent::Resync] marker
+    /// carrying the latest event of each key instead of the queued events, allowing the consumer to recover its state.
+    /// The first received item is also a resync marker when the replay buffer isn't empty at the moment of subscribing.
+    ///
+    /// The resync marker relies on the latest events which are retained by this holder,
+    /// so keys which have been evicted from the replay buffer aren't part of it.
+    ///
+    /// # Panics
+    ///
+    /// It panics when the given high watermark is 0.
+    pub fn subscribe_resync(&self, high_watermark: usize) -> ResyncSubscription<K, T>
+    where
+        T: 'static,
+    {
+        let cache = self.cache.lock().expect("failed to acquire lock");
+        let subscription = self.callback.subscribe_adaptive(high_watermark);
+        let resync_pending = !cache.is_empty();
+        drop(cache);
+
+        ResyncSubscription {
+            subscription,
+            cache: self.cache.clone(),
+            resync_pending,
+            reported_dropped: 0,
+        }
+    }
+
+    /// Replace the retained event of its key with the given value and invoke the subscribers with it.
+    ///
+    /// The replay buffer is updated and the value is fanned out while the buffer is locked,
+    /// so a concurrent subscriber either receives the value through the replay or through the invocation, but never both.
+    pub fn invoke(&self, value: T) {
+        let value = Arc::new(value);
+        let key = (self.key_extractor)(&value);
+        let mut cache = self.cache.lock().expect("failed to acquire lock");
+
+        // move the key to the back, as it's now the most recently updated key
+        cache.shift_remove(&key);
+        cache.insert(key, value.clone());
+        if cache.len() > self.capacity {
+            cache.shift_remove_index(0);
+        }
+
+        self.callback.invoke_shared(value);
+    }
+}
+
+impl<K, T> Callback<T> for KeyedReplayCallback<K, T>
+where
+    K: Eq + Hash + Send + Sync,
+    T: Debug + Send + Sync,
+{
+    fn subscribe(&self) -> Subscription<T> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        self.subscribe_with(tx);
+        rx
+    }
+
+    fn subscribe_with(&self, subscriber: Subscriber<T>) -> CallbackHandle {
+        let cache = self.cache.lock().expect("failed to acquire lock");
+        for value in cache.values() {
+            let _ = subscriber.send(value.clone());
+        }
+        self.callback.subscribe_with(subscriber)
+    }
+
+    fn unsubscribe(&self, handle: CallbackHandle) -> bool {
+        self.callback.unsubscribe(handle)
+    }
+
+    fn subscriber_count(&self) -> usize {
+        self.callback.subscriber_count()
+    }
+}
+
+impl<K, T> Debug for KeyedReplayCallback<K, T>
+where
+    T: Debug + Send + Sync,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyedReplayCallback")
+            .field("capacity", &self.capacity)
+            .field("keys", &self.cache.lock().map(|e| e.len()).unwrap_or(0))
+            .field("callback", &self.callback)
+            .finish()
+    }
+}
+
+/// The item which is received by a [ResyncSubscription].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResyncEvent<T> {
+    /// A regular event which has been invoked on the holder.
+    Event(Arc<T>),
+    /// A synthetic resync marker carrying the latest event of each key, ordered from the oldest to the most recently updated key.
+    /// It replaces the events which were still queued, as they're already covered by the marker.
+    Resync(Vec<Arc<T>>),
+}
+
+/// A subscription of a [KeyedReplayCallback] which resynchronizes the consumer with the latest state when it has fallen behind.
+pub struct ResyncSubscription<K, T> {
+    subscription: AdaptiveSubscription<T>,
+    cache: Arc<Mutex<IndexMap<K, Arc<T>>>>,
+    resync_pending: bool,
+    reported_dropped: u64,
+}
+
+impl<K, T> ResyncSubscription<K, T> {
+    /// Receive the next event, or a resync marker when events have been dropped since the last receipt.
+    ///
+    /// # Returns
+    ///
+    /// It returns [None] when the callback holder has been dropped and all queued events have been received.
+    pub async fn recv(&mut self) -> Option<ResyncEvent<T>> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Poll to receive the next event, or a resync marker when events have been dropped since the last receipt.
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<ResyncEvent<T>>> {
+        if self.resync_pending || self.subscription.dropped_count() > self.reported_dropped {
+            // the fan-out of the holder happens while the cache is locked,
+            // so no event can be queued between discarding the queue and taking the snapshot
+            let cache = self.cache.lock().expect("failed to acquire lock");
+            let dropped = self.subscription.dropped_count();
+            self.subscription.clear();
+            if dropped > self.reported_dropped {
+                debug!(
+                    "Resync subscription fell behind by {} events, resynchronizing",
+                    dropped - self.reported_dropped
+                );
+            }
+            self.reported_dropped = dropped;
+            self.resync_pending = false;
+            return Poll::Ready(Some(ResyncEvent::Resync(cache.values().cloned().collect())));
+        }
+
+        self.subscription
+            .poll_recv(cx)
+            .map(|event| event.map(ResyncEvent::Event))
+    }
+}
+
+impl<K, T> Debug for ResyncSubscription<K, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResyncSubscription")
+            .field("subscription", &self.subscription)
+            .field("resync_pending", &self.resync_pending)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::init_logger;
+
+    #[derive(Debug, PartialEq)]
+    struct Progress(u32, u8);
+
+    #[test]
+    fn test_subscribe_replay() {
+        init_logger!();
+        let callback = KeyedReplayCallback::new(2, |e: &Progress| e.0);
+
+        callback.invoke(Progress(1, 10));
+        callback.invoke(Progress(2, 10));
+        callback.invoke(Progress(1, 20));
+        callback.invoke(Progress(3, 10));
+        let mut receiver = callback.subscribe();
+        callback.invoke(Progress(3, 20));
+
+        assert_eq!(Progress(1, 20), *receiver.try_recv().unwrap());
+        assert_eq!(Progress(3, 10), *receiver.try_recv().unwrap());
+        assert_eq!(Progress(3, 20), *receiver.try_recv().unwrap());
+        assert!(receiver.try_recv().is_err(), "expected no other events");
+        assert_eq!(None, callback.latest(&2));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_resync() {
+        init_logger!();
+        let callback = KeyedReplayCallback::new(10, |e: &Progress| e.0);
+
+        callback.invoke(Progress(1, 10));
+        let mut subscription = callback.subscribe_resync(2);
+        assert_eq!(
+            Some(ResyncEvent::Resync(vec![Arc::new(Progress(1, 10))])),
+            subscription.recv().await
+        );
+
+        callback.invoke(Progress(2, 10));
+        assert_eq!(
+            Some(ResyncEvent::Event(Arc::new(Progress(2, 10)))),
+            subscription.recv().await
+        );
+
+        callback.invoke(Progress(1, 20));
+        callback.invoke(Progress(2, 20));
+        callback.invoke(Progress(2, 30));
+        assert_eq!(
+            Some(ResyncEvent::Resync(vec![
+                Arc::new(Progress(1, 20)),
+                Arc::new(Progress(2, 30))
+            ])),
+            subscription.recv().await
+        );
+
+        callback.invoke(Progress(1, 30));
+        assert_eq!(
+            Some(ResyncEvent::Event(Arc::new(Progress(1, 30)))),
+            subscription.recv().await
+        );
+    }
+}
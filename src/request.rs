@@ -0,0 +1,93 @@
+use crate::CallbackHandle;
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// A request event which can be replied to by the subscriber that received it.
+///
+/// Each subscriber receives its own request for the same shared value,
+/// allowing the reply of each subscriber to be tracked individually.
+/// Only the first reply to a request is retained.
+pub struct Request<T, R> {
+    value: Arc<T>,
+    reply: Mutex<Option<oneshot::Sender<Arc<R>>>>,
+}
+
+impl<T, R> Request<T, R> {
+    /// Create a new request for the given value.
+    ///
+    /// # Returns
+    ///
+    /// It returns the request together with the receiver of its reply.
+    pub(crate) fn new(value: Arc<T>) -> (Self, oneshot::Receiver<Arc<R>>) {
+        let (tx, rx) = oneshot::channel();
+        (
+            Self {
+                value,
+                reply: Mutex::new(Some(tx)),
+            },
+            rx,
+        )
+    }
+
+    /// Get the value of the request.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Reply to the request with the given value.
+    ///
+    /// # Returns
+    ///
+    /// It returns `false` when the request has already been replied to, or the requester is no longer waiting for the reply.
+    pub fn reply(&self, reply: R) -> bool {
+        self.reply
+            .lock()
+            .expect("failed to acquire lock")
+            .take()
+            .map(|e| e.send(Arc::new(reply)).is_ok())
+            .unwrap_or(false)
+    }
+}
+
+impl<T, R> Deref for Request<T, R> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T, R> Debug for Request<T, R>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Request")
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+/// Await the replies of the given pending requests until the given timeout expires.
+/// The timeout is shared between all pending requests, it's not applied per request.
+pub(crate) async fn collect_replies<R>(
+    pending: Vec<(CallbackHandle, oneshot::Receiver<Arc<R>>)>,
+    timeout: Duration,
+) -> HashMap<CallbackHandle, Option<Arc<R>>> {
+    let deadline = tokio::time::Instant::now() + timeout;
+    let mut replies = HashMap::with_capacity(pending.len());
+
+    for (handle, receiver) in pending {
+        let reply = tokio::time::timeout_at(deadline, receiver)
+            .await
+            .ok()
+            .and_then(|e| e.ok());
+        replies.insert(handle, reply);
+    }
+
+    replies
+}
@@ -0,0 +1,130 @@
+use crate::{
+    Callback, CallbackError, CallbackHandle, SingleThreadedCallback, Subscriber, Subscription,
+};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+/// A callback holder which keeps track of the current state value and informs its subscribers about each new state.
+///
+/// The state is updated and fanned out to the subscribers while the state is locked,
+/// so all subscribers observe the state changes in the exact order in which they've been applied.
+/// The events are delivered on the caller thread.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use fx_callback::{Callback, StateCallback};
+///
+/// let state = StateCallback::new(0u32);
+/// let mut receiver = state.subscribe();
+///
+/// if state.compare_and_invoke(&0, 1) {
+///     // the state has been updated by this producer
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct StateCallback<T>
+where
+    T: Debug + Send + Sync,
+{
+    state: Arc<Mutex<T>>,
+    callback: SingleThreadedCallback<T>,
+}
+
+impl<T> StateCallback<T>
+where
+    T: Debug + Clone + Send + Sync,
+{
+    /// Create a new state callback holder with the given initial state.
+    /// The initial state isn't invoked on the subscribers.
+    pub fn new(initial: T) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(initial)),
+            callback: SingleThreadedCallback::new(),
+        }
+    }
+
+    /// Get a copy of the current state.
+    pub fn get(&self) -> T {
+        self.state.lock().expect("failed to acquire lock").clone()
+    }
+
+    /// Replace the current state with the given value and invoke the subscribers with it.
+    pub fn invoke(&self, value: T) {
+        let mut state = self.state.lock().expect("failed to acquire lock");
+        *state = value.clone();
+        self.callback.invoke(value);
+    }
+
+    /// Replace the current state with the given new value and invoke the subscribers with it,
+    /// but only when the current state equals the expected value.
+    ///
+    /// The comparison, the update and the fan-out happen atomically while the state is locked,
+    /// so no other update of this holder can happen in between.
+    /// Updates of concurrent producers are therefore never lost, the losing producer is informed through the return value instead.
+    ///
+    /// # Returns
+    ///
+    /// It returns `true` when the state has been replaced and invoked, else `false` without invoking the subscribers.
+    pub fn compare_and_invoke(&self, expected: &T, new: T) -> bool
+    where
+        T: PartialEq,
+    {
+        let mut state = self.state.lock().expect("failed to acquire lock");
+        if *state != *expected {
+            return false;
+        }
+
+        *state = new.clone();
+        self.callback.invoke(new);
+        true
+    }
+}
+
+impl<T> Callback<T> for StateCallback<T>
+where
+    T: Debug + Send + Sync,
+{
+    fn subscribe(&self) -> Subscription<T> {
+        self.callback.subscribe()
+    }
+
+    fn subscribe_with(&self, subscriber: Subscriber<T>) -> CallbackHandle {
+        self.callback.subscribe_with(subscriber)
+    }
+
+    fn try_subscribe_with(
+        &self,
+        subscriber: Subscriber<T>,
+    ) -> Result<CallbackHandle, CallbackError> {
+        self.callback.try_subscribe_with(subscriber)
+    }
+
+    fn unsubscribe(&self, handle: CallbackHandle) -> bool {
+        self.callback.unsubscribe(handle)
+    }
+
+    fn subscriber_count(&self) -> usize {
+        self.callback.subscriber_count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::init_logger;
+
+    #[test]
+    fn test_compare_and_invoke() {
+        init_logger!();
+        let state = StateCallback::new(0u32);
+        let mut receiver = state.subscribe();
+
+        assert!(state.compare_and_invoke(&0, 1));
+        assert!(!state.compare_and_invoke(&0, 2));
+
+        assert_eq!(1, state.get());
+        assert_eq!(1, *receiver.try_recv().unwrap());
+        assert!(receiver.try_recv().is_err(), "expected no other events");
+    }
+}
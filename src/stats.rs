@@ -0,0 +1,270 @@
+use std::ops::Deref;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// The total number of buckets within the latency histogram, one bucket per power of 2 nanoseconds.
+const HISTOGRAM_BUCKETS: usize = 65;
+
+/// The accumulated fan-out statistics of a callback holder.
+///
+/// The statistics cover the whole lifetime of the callback holder, there is no rolling window.
+/// Rates and averages are calculated over the lifetime of the holder at the moment the statistics have been retrieved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallbackStats {
+    /// The time since the callback holder has been created.
+    pub uptime: Duration,
+    /// The total number of invocations of the callback holder.
+    pub invocations: u64,
+    /// The total number of events which have been delivered to subscribers.
+    pub deliveries: u64,
+    /// The average number of invocations per second over the lifetime of the callback holder.
+    pub events_per_second: f64,
+    /// The average number of subscribers the events have been delivered to per invocation.
+    pub avg_subscribers_per_invoke: f64,
+    /// The estimated median latency of delivering an event to a single subscriber.
+    pub p50_latency: Duration,
+    /// The estimated 99th percentile latency of delivering an event to a single subscriber.
+    pub p99_latency: Duration,
+    /// The highest latency of delivering an event to a single subscriber.
+    pub max_latency: Duration,
+}
+
+/// An event which has been timestamped at the moment it was created, allowing its end-to-end delivery latency to be measured.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use fx_callback::{Callback, MultiThreadedCallback, Timestamped};
+///
+/// async fn example(callback: MultiThreadedCallback<Timestamped<u32>>) {
+///     let mut receiver = callback.subscribe();
+///     callback.invoke_timestamped(1);
+///
+///     if let Some(event) = receiver.recv().await {
+///         callback.record_latency(&event);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timestamped<T> {
+    /// The moment the event has been timestamped.
+    pub at: Instant,
+    /// The timestamped event.
+    pub value: T,
+}
+
+impl<T> Timestamped<T> {
+    /// Timestamp the given event with the current moment.
+    pub fn new(value: T) -> Self {
+        Self {
+            at: Instant::now(),
+            value,
+        }
+    }
+}
+
+impl<T> Deref for Timestamped<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+/// The end-to-end delivery latency statistics of the timestamped events of a callback holder.
+/// The latencies are only known for the events which have been recorded by the consumers on receipt.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatencyStats {
+    /// The total number of recorded events.
+    pub samples: u64,
+    /// The estimated median latency between timestamping an event and its receipt by a consumer.
+    pub p50_latency: Duration,
+    /// The estimated 99th percentile latency between timestamping an event and its receipt by a consumer.
+    pub p99_latency: Duration,
+    /// The highest latency between timestamping an event and its receipt by a consumer.
+    pub max_latency: Duration,
+}
+
+/// A histogram of latencies, with one bucket per power of 2 nanoseconds.
+///
+/// Recording uses a few relaxed atomic operations per latency.
+#[derive(Debug)]
+pub(crate) struct LatencyHistogram {
+    max: AtomicU64,
+    buckets: [AtomicU64; HISTOGRAM_BUCKETS],
+}
+
+impl LatencyHistogram {
+    pub(crate) fn new() -> Self {
+        Self {
+            max: AtomicU64::new(0),
+            buckets: std::array::from_fn(|_| AtomicU64::new(0)),
+        }
+    }
+
+    /// Record the given latency.
+    pub(crate) fn record(&self, elapsed: Duration) {
+        let nanos = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX);
+        self.max.fetch_max(nanos, Ordering::Relaxed);
+        self.buckets[Self::bucket(nanos)].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Create a snapshot of the recorded latencies.
+    pub(crate) fn snapshot(&self) -> LatencyStats {
+        let buckets: Vec<u64> = self
+            .buckets
+            .iter()
+            .map(|e| e.load(Ordering::Relaxed))
+            .collect();
+
+        LatencyStats {
+            samples: buckets.iter().sum(),
+            p50_latency: Self::percentile(&buckets, 0.50),
+            p99_latency: Self::percentile(&buckets, 0.99),
+            max_latency: Duration::from_nanos(self.max.load(Ordering::Relaxed)),
+        }
+    }
+
+    /// Get the histogram bucket of the given nanoseconds.
+    fn bucket(nanos: u64) -> usize {
+        (u64::BITS - nanos.leading_zeros()) as usize
+    }
+
+    /// Estimate the given percentile from the histogram buckets.
+    /// The estimate is the upper bound of the bucket which contains the percentile.
+    fn percentile(buckets: &[u64], percentile: f64) -> Duration {
+        let total: u64 = buckets.iter().sum();
+        if total == 0 {
+            return Duration::ZERO;
+        }
+
+        let target = ((total as f64 * percentile).ceil() as u64).max(1);
+        let mut cumulative = 0;
+        for (index, count) in buckets.iter().enumerate() {
+            cumulative += count;
+            if cumulative >= target {
+                return Duration::from_nanos(match index {
+                    0 => 0,
+                    64 => u64::MAX,
+                    _ => (1u64 << index) - 1,
+                });
+            }
+        }
+
+        Duration::from_nanos(u64::MAX)
+    }
+}
+
+/// The always-on recorder of the fan-out statistics of a callback holder.
+///
+/// Recording uses a few relaxed atomic operations per invocation and per delivery.
+#[derive(Debug)]
+pub(crate) struct StatsRecorder {
+    created: Instant,
+    invocations: AtomicU64,
+    deliveries: AtomicU64,
+    histogram: LatencyHistogram,
+}
+
+impl StatsRecorder {
+    pub(crate) fn new() -> Self {
+        Self {
+            created: Instant::now(),
+            invocations: AtomicU64::new(0),
+            deliveries: AtomicU64::new(0),
+            histogram: LatencyHistogram::new(),
+        }
+    }
+
+    /// Record a new invocation of the callback holder.
+    pub(crate) fn record_invocation(&self) {
+        self.invocations.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a successful delivery to a subscriber which took the given time.
+    pub(crate) fn record_delivery(&self, elapsed: Duration) {
+        self.deliveries.fetch_add(1, Ordering::Relaxed);
+        self.histogram.record(elapsed);
+    }
+
+    /// Record a successful delivery to a subscriber which hasn't been measured.
+    pub(crate) fn record_untimed_delivery(&self) {
+        self.deliveries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Create a snapshot of the recorded statistics.
+    pub(crate) fn snapshot(&self) -> CallbackStats {
+        let uptime = self.created.elapsed();
+        let invocations = self.invocations.load(Ordering::Relaxed);
+        let deliveries = self.deliveries.load(Ordering::Relaxed);
+        let latency = self.histogram.snapshot();
+
+        CallbackStats {
+            uptime,
+            invocations,
+            deliveries,
+            events_per_second: if uptime.is_zero() {
+                0.0
+            } else {
+                invocations as f64 / uptime.as_secs_f64()
+            },
+            avg_subscribers_per_invoke: if invocations == 0 {
+                0.0
+            } else {
+                deliveries as f64 / invocations as f64
+            },
+            p50_latency: latency.p50_latency,
+            p99_latency: latency.p99_latency,
+            max_latency: latency.max_latency,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot() {
+        let recorder = StatsRecorder::new();
+
+        recorder.record_invocation();
+        recorder.record_invocation();
+        for _ in 0..99 {
+            recorder.record_delivery(Duration::from_nanos(100));
+        }
+        recorder.record_delivery(Duration::from_micros(10));
+        let result = recorder.snapshot();
+
+        assert_eq!(2, result.invocations);
+        assert_eq!(100, result.deliveries);
+        assert_eq!(50.0, result.avg_subscribers_per_invoke);
+        assert_eq!(Duration::from_nanos(127), result.p50_latency);
+        assert_eq!(Duration::from_nanos(127), result.p99_latency);
+        assert_eq!(Duration::from_micros(10), result.max_latency);
+    }
+
+    #[test]
+    fn test_snapshot_empty() {
+        let recorder = StatsRecorder::new();
+
+        let result = recorder.snapshot();
+
+        assert_eq!(0, result.invocations);
+        assert_eq!(0.0, result.avg_subscribers_per_invoke);
+        assert_eq!(Duration::ZERO, result.p99_latency);
+    }
+
+    #[test]
+    fn test_latency_histogram_snapshot() {
+        let histogram = LatencyHistogram::new();
+
+        histogram.record(Duration::from_nanos(100));
+        histogram.record(Duration::from_millis(1));
+        let result = histogram.snapshot();
+
+        assert_eq!(2, result.samples);
+        assert_eq!(Duration::from_nanos(127), result.p50_latency);
+        assert_eq!(Duration::from_millis(1), result.max_latency);
+    }
+}
@@ -0,0 +1,2235 @@
+use crate::{
+    AckedSubscription, AdaptiveSubscription, BoundedSubscription, LatestSubscription,
+    ManagedSubscription, OverflowSubscription, Subscription, SubscriptionSet,
+};
+use std::collections::VecDeque;
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::{Instant, Interval, MissedTickBehavior, Sleep, Timeout};
+
+/// A source of events which can be polled by a consumer, such as a [Subscription] or any of its combinators.
+///
+/// Polling an event stream is cancel safe, no event is lost when [EventStream::recv] is used within `select!`.
+pub trait EventStream {
+    /// The type of the events of the stream.
+    type Item;
+
+    /// Poll to receive the next event of the stream.
+    ///
+    /// # Returns
+    ///
+    /// It returns [None] when the stream has been closed and all its events have been received.
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>>;
+
+    /// Receive the next event of the stream.
+    ///
+    /// # Returns
+    ///
+    /// It returns [None] when the stream has been closed and all its events have been received.
+    fn recv(&mut self) -> Recv<'_, Self>
+    where
+        Self: Sized,
+    {
+        Recv { stream: self }
+    }
+
+    /// Receive the next event of the stream within the given timeout.
+    /// The stream can still be used after the timeout has elapsed, no event is lost when the timeout elapses.
+    ///
+    /// # Returns
+    ///
+    /// It returns `Ok(None)` when the stream has been closed, or an [tokio::time::error::Elapsed] error when the timeout elapsed.
+    fn recv_timeout(&mut self, timeout: Duration) -> Timeout<Recv<'_, Self>>
+    where
+        Self: Sized,
+    {
+        tokio::time::timeout(timeout, self.recv())
+    }
+
+    /// Only receive the first given number of events, after which the stream is closed.
+    /// The underlying stream is dropped as soon as the last event has been received,
+    /// so the subscriber is removed by its holder on its next invocation.
+    fn take(self, n: usize) -> TakeSubscription<Self>
+    where
+        Self: Sized,
+    {
+        TakeSubscription {
+            stream: if n > 0 { Some(self) } else { None },
+            remaining: n,
+        }
+    }
+
+    /// Skip the first given number of events, after which all events are received.
+    fn skip(self, n: usize) -> SkipSubscription<Self>
+    where
+        Self: Sized,
+    {
+        SkipSubscription {
+            stream: self,
+            remaining: n,
+        }
+    }
+
+    /// Only receive an event once no newer event has been received within the given window,
+    /// the intermediate events of a burst are replaced by the newer ones.
+    ///
+    /// The pending event is received immediately when the underlying stream is closed.
+    fn debounce(self, window: Duration) -> DebouncedSubscription<Self>
+    where
+        Self: Sized,
+    {
+        DebouncedSubscription {
+            stream: self,
+            window,
+            pending: None,
+            sleep: None,
+            closed: false,
+        }
+    }
+
+    /// Receive at most one event per given interval, dropping the events which are received within the interval.
+    /// The first event is received immediately, after which the interval starts.
+    fn throttle(self, interval: Duration) -> ThrottledSubscription<Self>
+    where
+        Self: Sized,
+    {
+        self.throttle_with(interval, ThrottleMode::default())
+    }
+
+    /// Receive at most one event per given interval, handling the events within the interval according to the given mode.
+    /// See [ThrottleMode] for more info.
+    fn throttle_with(self, interval: Duration, mode: ThrottleMode) -> ThrottledSubscription<Self>
+    where
+        Self: Sized,
+    {
+        ThrottledSubscription {
+            stream: self,
+            interval,
+            mode,
+            latest: None,
+            window: None,
+            closed: false,
+        }
+    }
+
+    /// Receive the most recent event on each tick of the given period.
+    /// The ticks on which no new event has been received are skipped, so the same event is never received twice.
+    ///
+    /// The most recent event is received immediately when the underlying stream is closed.
+    fn sample(self, period: Duration) -> SampledSubscription<Self>
+    where
+        Self: Sized,
+    {
+        SampledSubscription {
+            stream: self,
+            period,
+            latest: None,
+            interval: None,
+            closed: false,
+        }
+    }
+
+    /// Monitor the stream for inactivity, receiving a [WatchdogItem::Silence] each time no event has been received within the given window.
+    /// The window is restarted on each event and after each silence, so the stream keeps being monitored.
+    ///
+    /// The window starts when the stream is first polled, and no silence is received once the underlying stream is closed.
+    fn watchdog(self, window: Duration) -> WatchdogSubscription<Self>
+    where
+        Self: Sized,
+    {
+        WatchdogSubscription {
+            stream: self,
+            window,
+            sleep: None,
+        }
+    }
+
+    /// Receive the events in batches, which are received once either the batch contains the given maximum number of events,
+    /// or the given maximum wait has elapsed since the first event of the batch has been received.
+    /// Empty batches are never received.
+    ///
+    /// The partial batch is retained when the receiving future is dropped,
+    /// and is received immediately when the underlying stream is closed.
+    ///
+    /// # Panics
+    ///
+    /// It panics when the maximum number of events is 0.
+    fn chunks(self, max_len: usize, max_wait: Duration) -> ChunkedSubscription<Self>
+    where
+        Self: Sized,
+    {
+        assert!(
+            max_len > 0,
+            "the maximum length of a chunk must be at least 1"
+        );
+        ChunkedSubscription {
+            stream: self,
+            max_len,
+            max_wait,
+            buffer: Vec::with_capacity(max_len),
+            sleep: None,
+            closed: false,
+        }
+    }
+
+    /// Delay each event by the given duration, measured from the moment the event has been received from the underlying stream.
+    /// The order of the events is preserved, and only the events within the delay are held in memory.
+    ///
+    /// The held events are still received after their delay when the underlying stream is closed.
+    fn delay(self, delay: Duration) -> DelayedSubscription<Self>
+    where
+        Self: Sized,
+    {
+        DelayedSubscription {
+            stream: self,
+            delay,
+            events: VecDeque::new(),
+            sleep: None,
+            closed: false,
+        }
+    }
+
+    /// Invoke the given closure with a reference to each event, before the event is received unchanged.
+    /// This allows side effects such as logging or metrics without changing the consumer of the stream.
+    ///
+    /// The closure is invoked on the receiving task, a panic within the closure is propagated to the consumer
+    /// and the event for which the closure panicked is lost.
+    fn inspect<F>(self, f: F) -> InspectSubscription<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&Self::Item),
+    {
+        InspectSubscription { stream: self, f }
+    }
+}
+
+/// The item of a [WatchdogSubscription].
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatchdogItem<T> {
+    /// An event of the underlying stream has been received.
+    Event(T),
+    /// No event has been received within the window of the watchdog.
+    Silence,
+}
+
+/// The handling of the events which are received within the interval of a throttled stream.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ThrottleMode {
+    /// The events within the interval are dropped.
+    #[default]
+    DropIntermediate,
+    /// The latest event within the interval is received at the end of the interval.
+    /// The pending latest event is received immediately when the underlying stream is closed.
+    EmitLatest,
+}
+
+/// The future of [EventStream::recv], which resolves with the next event of the stream.
+#[derive(Debug)]
+pub struct Recv<'a, S> {
+    stream: &'a mut S,
+}
+
+impl<S> Future for Recv<'_, S>
+where
+    S: EventStream,
+{
+    type Output = Option<S::Item>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.stream.poll_recv(cx)
+    }
+}
+
+impl<T> EventStream for Subscription<T> {
+    type Item = Arc<T>;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Subscription::poll_recv(self, cx)
+    }
+}
+
+impl<T> EventStream for BoundedSubscription<T> {
+    type Item = Arc<T>;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        BoundedSubscription::poll_recv(self, cx)
+    }
+}
+
+impl<T> EventStream for AdaptiveSubscription<T> {
+    type Item = Arc<T>;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        AdaptiveSubscription::poll_recv(self, cx)
+    }
+}
+
+impl<T> EventStream for AckedSubscription<T> {
+    type Item = Arc<T>;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        AckedSubscription::poll_recv(self, cx)
+    }
+}
+
+impl<T> EventStream for LatestSubscription<T> {
+    type Item = Arc<T>;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        LatestSubscription::poll_recv(self, cx)
+    }
+}
+
+impl<T> EventStream for ManagedSubscription<T>
+where
+    T: Debug + Send + Sync,
+{
+    type Item = Arc<T>;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        ManagedSubscription::poll_recv(self, cx)
+    }
+}
+
+impl<T> EventStream for OverflowSubscription<T> {
+    type Item = Arc<T>;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        OverflowSubscription::poll_recv(self, cx)
+    }
+}
+
+impl<T> EventStream for SubscriptionSet<T> {
+    type Item = (usize, Arc<T>);
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_recv_any(cx)
+    }
+}
+
+/// Merge the given subscriptions into a single subscription, which receives the events of all subscriptions as they arrive.
+/// The order of the events of each subscription is preserved, and the merged subscription is closed once all subscriptions are closed.
+///
+/// The subscriptions are polled within the receiving task in a round-robin order, starting from a different subscription on each receive,
+/// so a busy subscription can't starve the others. No task is spawned per subscription.
+pub fn merge<T, I>(subscriptions: I) -> MergedSubscription<T>
+where
+    I: IntoIterator<Item = Subscription<T>>,
+{
+    let mut set = SubscriptionSet::new();
+    for subscription in subscriptions {
+        set.push(subscription);
+    }
+    MergedSubscription { set }
+}
+
+/// Receive the first event of either of the given streams.
+/// The streams are borrowed, so both of them can still be used afterwards, and the event of the other stream isn't consumed.
+///
+/// The first stream is polled before the second stream, so its event is received when both streams have an event available.
+/// The future is cancel safe, no event is lost when it's used within `select!`.
+///
+/// # Returns
+///
+/// It returns the event together with its source, or [None] when both streams have been closed.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use fx_callback::{select2, Callback, Either, MultiThreadedCallback};
+///
+/// #[derive(Debug)]
+/// pub struct Completed;
+///
+/// #[derive(Debug)]
+/// pub struct Failed;
+///
+/// async fn example(completed: &MultiThreadedCallback<Completed>, failed: &MultiThreadedCallback<Failed>) {
+///     let mut completed = completed.subscribe();
+///     let mut failed = failed.subscribe();
+///
+///     match select2(&mut completed, &mut failed).await {
+///         Some(Either::Left(_)) => {}  // the download completed
+///         Some(Either::Right(_)) => {} // the download failed
+///         None => {}
+///     }
+/// }
+/// ```
+pub fn select2<'a, A, B>(first: &'a mut A, second: &'a mut B) -> Select2<'a, A, B>
+where
+    A: EventStream,
+    B: EventStream,
+{
+    Select2 { first, second }
+}
+
+/// Receive the first event of either of the given streams within the given timeout, see [select2].
+pub fn select2_timeout<'a, A, B>(
+    first: &'a mut A,
+    second: &'a mut B,
+    timeout: Duration,
+) -> Timeout<Select2<'a, A, B>>
+where
+    A: EventStream,
+    B: EventStream,
+{
+    tokio::time::timeout(timeout, select2(first, second))
+}
+
+/// Receive the first event of any of the given streams.
+/// The streams are borrowed, so all of them can still be used afterwards, and the events of the other streams aren't consumed.
+///
+/// The streams are polled in their given order, so the event of the first stream is received when multiple streams have an event available.
+/// The future is cancel safe, no event is lost when it's used within `select!`.
+///
+/// # Returns
+///
+/// It returns the index of the source stream together with the event, or [None] when all streams have been closed.
+pub fn select_first<S>(streams: &mut [S]) -> SelectFirst<'_, S>
+where
+    S: EventStream,
+{
+    SelectFirst { streams }
+}
+
+/// Receive the first event of any of the given streams within the given timeout, see [select_first].
+pub fn select_first_timeout<S>(streams: &mut [S], timeout: Duration) -> Timeout<SelectFirst<'_, S>>
+where
+    S: EventStream,
+{
+    tokio::time::timeout(timeout, select_first(streams))
+}
+
+/// The event of one of two streams, see [select2].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Either<A, B> {
+    /// The event of the first stream.
+    Left(A),
+    /// The event of the second stream.
+    Right(B),
+}
+
+/// The consumer side combinators of an [EventStream] of shared events,
+/// which is implemented for all subscriptions of this crate.
+///
+/// The combinators are applied while the events are being received, without spawning any task.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use fx_callback::{Callback, EventStream, EventStreamExt, MultiThreadedCallback};
+///
+/// #[derive(Debug)]
+/// pub enum MyEvent {
+///     Foo,
+///     Bar,
+/// }
+///
+/// async fn example(callback: &MultiThreadedCallback<MyEvent>) {
+///     let mut receiver = callback.subscribe().filter(|e| matches!(e, MyEvent::Foo));
+///
+///     while let Some(event) = receiver.recv().await {
+///         // only the foo events are received
+///     }
+/// }
+/// ```
+pub trait EventStreamExt<T>: EventStream<Item = Arc<T>> + Sized {
+    /// Only receive the events which match the given predicate, the other events are skipped.
+    fn filter<F>(self, predicate: F) -> FilteredSubscription<Self, F>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        FilteredSubscription {
+            stream: self,
+            predicate,
+        }
+    }
+
+    /// Transform each event into an owned value of another type.
+    /// The closure is applied while receiving the event, preserving the order of the events.
+    fn map<U, F>(self, f: F) -> MappedSubscription<Self, F>
+    where
+        F: FnMut(&T) -> U,
+    {
+        MappedSubscription { stream: self, f }
+    }
+
+    /// Transform each event into a shared value of another type.
+    /// Unlike [EventStreamExt::map], the resulting stream can be combined further with the combinators of this trait.
+    fn map_arc<U, F>(self, f: F) -> MappedArcSubscription<Self, F>
+    where
+        F: FnMut(&T) -> U,
+    {
+        MappedArcSubscription { stream: self, f }
+    }
+
+    /// Filter and transform the events at once, only the events for which the closure returns a value are received.
+    /// The skipped events are consumed without waking the consumer, only the next matching event resolves the stream.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use fx_callback::{Callback, EventStream, EventStreamExt, MultiThreadedCallback};
+    ///
+    /// #[derive(Debug)]
+    /// pub enum MyEvent {
+    ///     Progress(u32),
+    ///     Completed,
+    /// }
+    ///
+    /// async fn example(callback: &MultiThreadedCallback<MyEvent>) {
+    ///     let mut progress = callback.subscribe().filter_map(|e| match e {
+    ///         MyEvent::Progress(p) => Some(*p),
+    ///         _ => None,
+    ///     });
+    ///
+    ///     while let Some(progress) = progress.recv().await {
+    ///         // the progress is received as u32
+    ///     }
+    /// }
+    /// ```
+    fn filter_map<U, F>(self, f: F) -> FilterMappedSubscription<Self, F>
+    where
+        F: FnMut(&T) -> Option<U>,
+    {
+        FilterMappedSubscription { stream: self, f }
+    }
+
+    /// Skip the events while they match the given predicate.
+    /// Once an event doesn't match, that event and all following events are received without evaluating the predicate again.
+    fn skip_while<F>(self, predicate: F) -> SkipWhileSubscription<Self, F>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        SkipWhileSubscription {
+            stream: self,
+            predicate: Some(predicate),
+        }
+    }
+
+    /// Skip the events which are equal to the last received event.
+    /// The events are compared by their value, not by the identity of their shared reference.
+    fn distinct_until_changed(self) -> DistinctSubscription<Self, T>
+    where
+        T: PartialEq,
+    {
+        DistinctSubscription {
+            stream: self,
+            last: None,
+        }
+    }
+
+    /// Skip the events of which the key is equal to the key of the last received event.
+    fn distinct_by_key<K, F>(self, key: F) -> DistinctByKeySubscription<Self, F, K>
+    where
+        K: PartialEq,
+        F: FnMut(&T) -> K,
+    {
+        DistinctByKeySubscription {
+            stream: self,
+            key,
+            last: None,
+        }
+    }
+
+    /// Wait for the first event matching the given predicate.
+    /// The events which don't match are consumed from the stream, and can't be received anymore afterwards.
+    ///
+    /// # Returns
+    ///
+    /// It returns the first matching event, or [None] when the stream has been closed before any event matched.
+    fn wait_for<F>(&mut self, predicate: F) -> WaitFor<'_, Self, F>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        WaitFor {
+            stream: self,
+            predicate,
+        }
+    }
+
+    /// Wait for the first event matching the given predicate within the given timeout, see [EventStreamExt::wait_for].
+    ///
+    /// # Returns
+    ///
+    /// It returns `Ok(None)` when the stream has been closed, or an [tokio::time::error::Elapsed] error when the timeout elapsed.
+    fn wait_for_timeout<F>(
+        &mut self,
+        predicate: F,
+        timeout: Duration,
+    ) -> Timeout<WaitFor<'_, Self, F>>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        tokio::time::timeout(timeout, self.wait_for(predicate))
+    }
+
+    /// Maintain a running state over the events, which is updated by the given closure for each event.
+    /// The closure optionally returns a value derived from the updated state, which is received downstream,
+    /// the events for which the closure returns [None] only update the state.
+    ///
+    /// The final state can be retrieved through [ScanSubscription::finish] once the underlying stream has been closed.
+    fn scan<St, U, F>(self, initial: St, f: F) -> ScanSubscription<Self, St, F>
+    where
+        F: FnMut(&mut St, &T) -> Option<U>,
+    {
+        ScanSubscription {
+            stream: self,
+            state: initial,
+            f,
+        }
+    }
+}
+
+impl<T, S> EventStreamExt<T> for S where S: EventStream<Item = Arc<T>> {}
+
+/// A subscription which only yields the events matching its predicate, see [EventStreamExt::filter].
+pub struct FilteredSubscription<S, F> {
+    stream: S,
+    predicate: F,
+}
+
+impl<S, F> FilteredSubscription<S, F> {
+    /// Get the underlying stream back.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<T, S, F> EventStream for FilteredSubscription<S, F>
+where
+    S: EventStream<Item = Arc<T>>,
+    F: FnMut(&T) -> bool,
+{
+    type Item = Arc<T>;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match self.stream.poll_recv(cx) {
+                Poll::Ready(Some(event)) if (self.predicate)(&event) => {
+                    return Poll::Ready(Some(event))
+                }
+                Poll::Ready(Some(_)) => {}
+                result => return result,
+            }
+        }
+    }
+}
+
+impl<S, F> Debug for FilteredSubscription<S, F>
+where
+    S: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FilteredSubscription")
+            .field("stream", &self.stream)
+            .finish()
+    }
+}
+
+/// A subscription which transforms each event into an owned value, see [EventStreamExt::map].
+pub struct MappedSubscription<S, F> {
+    stream: S,
+    f: F,
+}
+
+impl<S, F> MappedSubscription<S, F> {
+    /// Get the underlying stream back.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<T, U, S, F> EventStream for MappedSubscription<S, F>
+where
+    S: EventStream<Item = Arc<T>>,
+    F: FnMut(&T) -> U,
+{
+    type Item = U;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream
+            .poll_recv(cx)
+            .map(|e| e.map(|event| (self.f)(&event)))
+    }
+}
+
+impl<S, F> Debug for MappedSubscription<S, F>
+where
+    S: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MappedSubscription")
+            .field("stream", &self.stream)
+            .finish()
+    }
+}
+
+/// A subscription which transforms each event into a shared value, see [EventStreamExt::map_arc].
+pub struct MappedArcSubscription<S, F> {
+    stream: S,
+    f: F,
+}
+
+impl<S, F> MappedArcSubscription<S, F> {
+    /// Get the underlying stream back.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<T, U, S, F> EventStream for MappedArcSubscription<S, F>
+where
+    S: EventStream<Item = Arc<T>>,
+    F: FnMut(&T) -> U,
+{
+    type Item = Arc<U>;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream
+            .poll_recv(cx)
+            .map(|e| e.map(|event| Arc::new((self.f)(&event))))
+    }
+}
+
+impl<S, F> Debug for MappedArcSubscription<S, F>
+where
+    S: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MappedArcSubscription")
+            .field("stream", &self.stream)
+            .finish()
+    }
+}
+
+/// A subscription which filters and transforms the events at once, see [EventStreamExt::filter_map].
+pub struct FilterMappedSubscription<S, F> {
+    stream: S,
+    f: F,
+}
+
+impl<S, F> FilterMappedSubscription<S, F> {
+    /// Get the underlying stream back.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<T, U, S, F> EventStream for FilterMappedSubscription<S, F>
+where
+    S: EventStream<Item = Arc<T>>,
+    F: FnMut(&T) -> Option<U>,
+{
+    type Item = U;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match self.stream.poll_recv(cx) {
+                Poll::Ready(Some(event)) => {
+                    if let Some(value) = (self.f)(&event) {
+                        return Poll::Ready(Some(value));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<S, F> Debug for FilterMappedSubscription<S, F>
+where
+    S: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FilterMappedSubscription")
+            .field("stream", &self.stream)
+            .finish()
+    }
+}
+
+/// A subscription which is closed after a number of events, see [EventStream::take].
+#[derive(Debug)]
+pub struct TakeSubscription<S> {
+    stream: Option<S>,
+    remaining: usize,
+}
+
+impl<S> TakeSubscription<S> {
+    /// Get the remaining number of events which will be received.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+}
+
+impl<S> EventStream for TakeSubscription<S>
+where
+    S: EventStream,
+{
+    type Item = S::Item;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let Some(stream) = self.stream.as_mut() else {
+            return Poll::Ready(None);
+        };
+
+        let result = stream.poll_recv(cx);
+        match &result {
+            Poll::Ready(Some(_)) => {
+                self.remaining -= 1;
+                if self.remaining == 0 {
+                    self.stream = None;
+                }
+            }
+            Poll::Ready(None) => {
+                self.remaining = 0;
+                self.stream = None;
+            }
+            Poll::Pending => {}
+        }
+        result
+    }
+}
+
+/// A subscription which skips a number of events, see [EventStream::skip].
+#[derive(Debug)]
+pub struct SkipSubscription<S> {
+    stream: S,
+    remaining: usize,
+}
+
+impl<S> SkipSubscription<S> {
+    /// Get the underlying stream back.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S> EventStream for SkipSubscription<S>
+where
+    S: EventStream,
+{
+    type Item = S::Item;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        while self.remaining > 0 {
+            match self.stream.poll_recv(cx) {
+                Poll::Ready(Some(_)) => self.remaining -= 1,
+                result => return result,
+            }
+        }
+
+        self.stream.poll_recv(cx)
+    }
+}
+
+/// A subscription which skips the events while they match its predicate, see [EventStreamExt::skip_while].
+pub struct SkipWhileSubscription<S, F> {
+    stream: S,
+    predicate: Option<F>,
+}
+
+impl<S, F> SkipWhileSubscription<S, F> {
+    /// Get the underlying stream back.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<T, S, F> EventStream for SkipWhileSubscription<S, F>
+where
+    S: EventStream<Item = Arc<T>>,
+    F: FnMut(&T) -> bool,
+{
+    type Item = Arc<T>;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let Some(predicate) = self.predicate.as_mut() else {
+            return self.stream.poll_recv(cx);
+        };
+
+        loop {
+            match self.stream.poll_recv(cx) {
+                Poll::Ready(Some(event)) if predicate(&event) => {}
+                Poll::Ready(Some(event)) => {
+                    self.predicate = None;
+                    return Poll::Ready(Some(event));
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl<S, F> Debug for SkipWhileSubscription<S, F>
+where
+    S: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SkipWhileSubscription")
+            .field("stream", &self.stream)
+            .field("skipping", &self.predicate.is_some())
+            .finish()
+    }
+}
+
+/// A subscription which skips consecutive equal events, see [EventStreamExt::distinct_until_changed].
+#[derive(Debug)]
+pub struct DistinctSubscription<S, T> {
+    stream: S,
+    last: Option<Arc<T>>,
+}
+
+impl<S, T> DistinctSubscription<S, T> {
+    /// Get the underlying stream back.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<T, S> EventStream for DistinctSubscription<S, T>
+where
+    S: EventStream<Item = Arc<T>>,
+    T: PartialEq,
+{
+    type Item = Arc<T>;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match self.stream.poll_recv(cx) {
+                Poll::Ready(Some(event)) => {
+                    if self.last.as_deref() != Some(&*event) {
+                        self.last = Some(event.clone());
+                        return Poll::Ready(Some(event));
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// A subscription which skips consecutive events with an equal key, see [EventStreamExt::distinct_by_key].
+pub struct DistinctByKeySubscription<S, F, K> {
+    stream: S,
+    key: F,
+    last: Option<K>,
+}
+
+impl<S, F, K> DistinctByKeySubscription<S, F, K> {
+    /// Get the underlying stream back.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<T, S, F, K> EventStream for DistinctByKeySubscription<S, F, K>
+where
+    S: EventStream<Item = Arc<T>>,
+    F: FnMut(&T) -> K,
+    K: PartialEq,
+{
+    type Item = Arc<T>;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match self.stream.poll_recv(cx) {
+                Poll::Ready(Some(event)) => {
+                    let key = (self.key)(&event);
+                    if self.last.as_ref() != Some(&key) {
+                        self.last = Some(key);
+                        return Poll::Ready(Some(event));
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl<S, F, K> Debug for DistinctByKeySubscription<S, F, K>
+where
+    S: Debug,
+    K: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DistinctByKeySubscription")
+            .field("stream", &self.stream)
+            .field("last", &self.last)
+            .finish()
+    }
+}
+
+/// A subscription which only yields the last event of a burst, see [EventStream::debounce].
+pub struct DebouncedSubscription<S>
+where
+    S: EventStream,
+{
+    stream: S,
+    window: Duration,
+    pending: Option<S::Item>,
+    sleep: Option<Pin<Box<Sleep>>>,
+    closed: bool,
+}
+
+impl<S> EventStream for DebouncedSubscription<S>
+where
+    S: EventStream,
+{
+    type Item = S::Item;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        while !self.closed {
+            match self.stream.poll_recv(cx) {
+                Poll::Ready(Some(event)) => {
+                    let deadline = Instant::now() + self.window;
+                    self.pending = Some(event);
+                    match self.sleep.as_mut() {
+                        Some(sleep) => sleep.as_mut().reset(deadline),
+                        None => self.sleep = Some(Box::pin(tokio::time::sleep_until(deadline))),
+                    }
+                }
+                Poll::Ready(None) => self.closed = true,
+                Poll::Pending => break,
+            }
+        }
+
+        if self.closed {
+            return Poll::Ready(self.pending.take());
+        }
+        if self.pending.is_some() {
+            if let Some(sleep) = self.sleep.as_mut() {
+                if sleep.as_mut().poll(cx).is_ready() {
+                    return Poll::Ready(self.pending.take());
+                }
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl<S> Debug for DebouncedSubscription<S>
+where
+    S: EventStream + Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DebouncedSubscription")
+            .field("stream", &self.stream)
+            .field("window", &self.window)
+            .field("pending", &self.pending.is_some())
+            .finish()
+    }
+}
+
+/// A subscription which yields at most one event per interval, see [EventStream::throttle].
+pub struct ThrottledSubscription<S>
+where
+    S: EventStream,
+{
+    stream: S,
+    interval: Duration,
+    mode: ThrottleMode,
+    latest: Option<S::Item>,
+    window: Option<(Instant, Pin<Box<Sleep>>)>,
+    closed: bool,
+}
+
+impl<S> ThrottledSubscription<S>
+where
+    S: EventStream,
+{
+    fn start_window(&mut self) {
+        let deadline = Instant::now() + self.interval;
+        match self.window.as_mut() {
+            Some((end, sleep)) => {
+                *end = deadline;
+                sleep.as_mut().reset(deadline);
+            }
+            None => self.window = Some((deadline, Box::pin(tokio::time::sleep_until(deadline)))),
+        }
+    }
+
+    fn is_within_window(&self) -> bool {
+        self.window
+            .as_ref()
+            .is_some_and(|(end, _)| Instant::now() < *end)
+    }
+}
+
+impl<S> EventStream for ThrottledSubscription<S>
+where
+    S: EventStream,
+{
+    type Item = S::Item;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        while !self.closed {
+            match self.stream.poll_recv(cx) {
+                Poll::Ready(Some(event)) => {
+                    if !self.is_within_window() {
+                        // the latest event of the previous interval precedes this event
+                        if let Some(latest) = self.latest.replace(event) {
+                            self.start_window();
+                            return Poll::Ready(Some(latest));
+                        }
+                        self.start_window();
+                        return Poll::Ready(self.latest.take());
+                    }
+                    if self.mode == ThrottleMode::EmitLatest {
+                        self.latest = Some(event);
+                    }
+                }
+                Poll::Ready(None) => self.closed = true,
+                Poll::Pending => break,
+            }
+        }
+
+        if self.closed {
+            return Poll::Ready(self.latest.take());
+        }
+        if self.latest.is_some() {
+            if let Some((_, sleep)) = self.window.as_mut() {
+                if sleep.as_mut().poll(cx).is_ready() {
+                    self.start_window();
+                    return Poll::Ready(self.latest.take());
+                }
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl<S> Debug for ThrottledSubscription<S>
+where
+    S: EventStream + Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ThrottledSubscription")
+            .field("stream", &self.stream)
+            .field("interval", &self.interval)
+            .field("mode", &self.mode)
+            .finish()
+    }
+}
+
+/// A subscription which yields the most recent event on each tick, see [EventStream::sample].
+pub struct SampledSubscription<S>
+where
+    S: EventStream,
+{
+    stream: S,
+    period: Duration,
+    latest: Option<(Instant, S::Item)>,
+    interval: Option<Interval>,
+    closed: bool,
+}
+
+impl<S> EventStream for SampledSubscription<S>
+where
+    S: EventStream,
+{
+    type Item = S::Item;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        while !self.closed {
+            match self.stream.poll_recv(cx) {
+                Poll::Ready(Some(event)) => self.latest = Some((Instant::now(), event)),
+                Poll::Ready(None) => self.closed = true,
+                Poll::Pending => break,
+            }
+        }
+
+        if self.closed {
+            return Poll::Ready(self.latest.take().map(|(_, event)| event));
+        }
+
+        let period = self.period;
+        let interval = self.interval.get_or_insert_with(|| {
+            let mut interval = tokio::time::interval_at(Instant::now() + period, period);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            interval
+        });
+        while let Poll::Ready(tick) = interval.poll_tick(cx) {
+            // a missed tick from before the event has been received doesn't sample the event
+            if self
+                .latest
+                .as_ref()
+                .is_some_and(|(received, _)| tick >= *received)
+            {
+                return Poll::Ready(self.latest.take().map(|(_, event)| event));
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl<S> Debug for SampledSubscription<S>
+where
+    S: EventStream + Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SampledSubscription")
+            .field("stream", &self.stream)
+            .field("period", &self.period)
+            .field("latest", &self.latest.is_some())
+            .finish()
+    }
+}
+
+/// The future of [EventStreamExt::wait_for], which resolves with the first event matching its predicate.
+pub struct WaitFor<'a, S, F> {
+    stream: &'a mut S,
+    predicate: F,
+}
+
+// the predicate is never pinned, it's only called through a mutable reference
+impl<S, F> Unpin for WaitFor<'_, S, F> {}
+
+impl<T, S, F> Future for WaitFor<'_, S, F>
+where
+    S: EventStream<Item = Arc<T>>,
+    F: FnMut(&T) -> bool,
+{
+    type Output = Option<Arc<T>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        loop {
+            match this.stream.poll_recv(cx) {
+                Poll::Ready(Some(event)) if (this.predicate)(&event) => {
+                    return Poll::Ready(Some(event))
+                }
+                Poll::Ready(Some(_)) => {}
+                result => return result,
+            }
+        }
+    }
+}
+
+impl<S, F> Debug for WaitFor<'_, S, F>
+where
+    S: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WaitFor")
+            .field("stream", &self.stream)
+            .finish()
+    }
+}
+
+/// A subscription which reports the inactivity of its underlying stream, see [EventStream::watchdog].
+#[derive(Debug)]
+pub struct WatchdogSubscription<S> {
+    stream: S,
+    window: Duration,
+    sleep: Option<Pin<Box<Sleep>>>,
+}
+
+impl<S> WatchdogSubscription<S> {
+    /// Get the underlying stream back.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S> EventStream for WatchdogSubscription<S>
+where
+    S: EventStream,
+{
+    type Item = WatchdogItem<S::Item>;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let deadline = Instant::now() + self.window;
+        let sleep = self
+            .sleep
+            .get_or_insert_with(|| Box::pin(tokio::time::sleep_until(deadline)));
+
+        match self.stream.poll_recv(cx) {
+            Poll::Ready(Some(event)) => {
+                sleep.as_mut().reset(deadline);
+                Poll::Ready(Some(WatchdogItem::Event(event)))
+            }
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => match sleep.as_mut().poll(cx) {
+                Poll::Ready(_) => {
+                    sleep.as_mut().reset(deadline);
+                    Poll::Ready(Some(WatchdogItem::Silence))
+                }
+                Poll::Pending => Poll::Pending,
+            },
+        }
+    }
+}
+
+/// A subscription which yields the events in batches, see [EventStream::chunks].
+pub struct ChunkedSubscription<S>
+where
+    S: EventStream,
+{
+    stream: S,
+    max_len: usize,
+    max_wait: Duration,
+    buffer: Vec<S::Item>,
+    sleep: Option<Pin<Box<Sleep>>>,
+    closed: bool,
+}
+
+impl<S> ChunkedSubscription<S>
+where
+    S: EventStream,
+{
+    fn take_buffer(&mut self) -> Vec<S::Item> {
+        std::mem::replace(&mut self.buffer, Vec::with_capacity(self.max_len))
+    }
+}
+
+impl<S> EventStream for ChunkedSubscription<S>
+where
+    S: EventStream,
+{
+    type Item = Vec<S::Item>;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        while !self.closed && self.buffer.len() < self.max_len {
+            match self.stream.poll_recv(cx) {
+                Poll::Ready(Some(event)) => {
+                    if self.buffer.is_empty() {
+                        let deadline = Instant::now() + self.max_wait;
+                        match self.sleep.as_mut() {
+                            Some(sleep) => sleep.as_mut().reset(deadline),
+                            None => self.sleep = Some(Box::pin(tokio::time::sleep_until(deadline))),
+                        }
+                    }
+                    self.buffer.push(event);
+                }
+                Poll::Ready(None) => self.closed = true,
+                Poll::Pending => break,
+            }
+        }
+
+        if self.buffer.len() >= self.max_len || (self.closed && !self.buffer.is_empty()) {
+            return Poll::Ready(Some(self.take_buffer()));
+        }
+        if self.closed {
+            return Poll::Ready(None);
+        }
+        if !self.buffer.is_empty() {
+            if let Some(sleep) = self.sleep.as_mut() {
+                if sleep.as_mut().poll(cx).is_ready() {
+                    return Poll::Ready(Some(self.take_buffer()));
+                }
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl<S> Debug for ChunkedSubscription<S>
+where
+    S: EventStream + Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChunkedSubscription")
+            .field("stream", &self.stream)
+            .field("max_len", &self.max_len)
+            .field("max_wait", &self.max_wait)
+            .field("buffered", &self.buffer.len())
+            .finish()
+    }
+}
+
+/// A subscription which receives the events of multiple subscriptions, see [merge].
+#[derive(Debug)]
+pub struct MergedSubscription<T> {
+    set: SubscriptionSet<T>,
+}
+
+impl<T> MergedSubscription<T> {
+    /// Add the given subscription to the merged subscriptions.
+    pub fn push(&mut self, subscription: Subscription<T>) {
+        self.set.push(subscription);
+    }
+
+    /// Get the number of merged subscriptions which haven't been closed yet.
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    /// Check if all merged subscriptions have been closed.
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+}
+
+impl<T> EventStream for MergedSubscription<T> {
+    type Item = Arc<T>;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.set
+            .poll_recv_any(cx)
+            .map(|e| e.map(|(_, event)| event))
+    }
+}
+
+/// The future of [select2], which resolves with the first event of either stream.
+#[derive(Debug)]
+pub struct Select2<'a, A, B> {
+    first: &'a mut A,
+    second: &'a mut B,
+}
+
+impl<A, B> Future for Select2<'_, A, B>
+where
+    A: EventStream,
+    B: EventStream,
+{
+    type Output = Option<Either<A::Item, B::Item>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let first = match self.first.poll_recv(cx) {
+            Poll::Ready(Some(event)) => return Poll::Ready(Some(Either::Left(event))),
+            Poll::Ready(None) => true,
+            Poll::Pending => false,
+        };
+        match self.second.poll_recv(cx) {
+            Poll::Ready(Some(event)) => Poll::Ready(Some(Either::Right(event))),
+            Poll::Ready(None) if first => Poll::Ready(None),
+            _ => Poll::Pending,
+        }
+    }
+}
+
+/// The future of [select_first], which resolves with the first event of any of the streams.
+#[derive(Debug)]
+pub struct SelectFirst<'a, S> {
+    streams: &'a mut [S],
+}
+
+impl<S> Future for SelectFirst<'_, S>
+where
+    S: EventStream,
+{
+    type Output = Option<(usize, S::Item)>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut closed = 0;
+        for (index, stream) in self.streams.iter_mut().enumerate() {
+            match stream.poll_recv(cx) {
+                Poll::Ready(Some(event)) => return Poll::Ready(Some((index, event))),
+                Poll::Ready(None) => closed += 1,
+                Poll::Pending => {}
+            }
+        }
+
+        if closed == self.streams.len() {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// A subscription which delays each event by a fixed duration, see [EventStream::delay].
+pub struct DelayedSubscription<S>
+where
+    S: EventStream,
+{
+    stream: S,
+    delay: Duration,
+    events: VecDeque<(Instant, S::Item)>,
+    sleep: Option<Pin<Box<Sleep>>>,
+    closed: bool,
+}
+
+impl<S> EventStream for DelayedSubscription<S>
+where
+    S: EventStream,
+{
+    type Item = S::Item;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        while !self.closed {
+            match self.stream.poll_recv(cx) {
+                Poll::Ready(Some(event)) => {
+                    self.events.push_back((Instant::now() + self.delay, event))
+                }
+                Poll::Ready(None) => self.closed = true,
+                Poll::Pending => break,
+            }
+        }
+
+        let Some((deadline, _)) = self.events.front() else {
+            return if self.closed {
+                Poll::Ready(None)
+            } else {
+                Poll::Pending
+            };
+        };
+        let deadline = *deadline;
+        let sleep = self
+            .sleep
+            .get_or_insert_with(|| Box::pin(tokio::time::sleep_until(deadline)));
+        if sleep.deadline() != deadline {
+            sleep.as_mut().reset(deadline);
+        }
+        match sleep.as_mut().poll(cx) {
+            Poll::Ready(_) => Poll::Ready(self.events.pop_front().map(|(_, event)| event)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<S> Debug for DelayedSubscription<S>
+where
+    S: EventStream + Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DelayedSubscription")
+            .field("stream", &self.stream)
+            .field("delay", &self.delay)
+            .field("pending", &self.events.len())
+            .finish()
+    }
+}
+
+/// A subscription which invokes a closure for each event, see [EventStream::inspect].
+pub struct InspectSubscription<S, F> {
+    stream: S,
+    f: F,
+}
+
+impl<S, F> InspectSubscription<S, F> {
+    /// Get the underlying stream back.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S, F> EventStream for InspectSubscription<S, F>
+where
+    S: EventStream,
+    F: FnMut(&S::Item),
+{
+    type Item = S::Item;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let result = self.stream.poll_recv(cx);
+        if let Poll::Ready(Some(event)) = &result {
+            (self.f)(event);
+        }
+        result
+    }
+}
+
+impl<S, F> Debug for InspectSubscription<S, F>
+where
+    S: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InspectSubscription")
+            .field("stream", &self.stream)
+            .finish()
+    }
+}
+
+/// A subscription which maintains a running state over the events, see [EventStreamExt::scan].
+pub struct ScanSubscription<S, St, F> {
+    stream: S,
+    state: St,
+    f: F,
+}
+
+impl<S, St, F> ScanSubscription<S, St, F> {
+    /// Get a reference to the current state.
+    pub fn state(&self) -> &St {
+        &self.state
+    }
+
+    /// Get the final state, dropping the underlying stream.
+    pub fn finish(self) -> St {
+        self.state
+    }
+}
+
+impl<T, S, St, U, F> EventStream for ScanSubscription<S, St, F>
+where
+    S: EventStream<Item = Arc<T>>,
+    F: FnMut(&mut St, &T) -> Option<U>,
+{
+    type Item = U;
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match self.stream.poll_recv(cx) {
+                Poll::Ready(Some(event)) => {
+                    if let Some(value) = (self.f)(&mut self.state, &event) {
+                        return Poll::Ready(Some(value));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<S, St, F> Debug for ScanSubscription<S, St, F>
+where
+    S: Debug,
+    St: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScanSubscription")
+            .field("stream", &self.stream)
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{init_logger, Callback, SingleThreadedCallback};
+
+    #[tokio::test]
+    async fn test_filter() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe().filter(|e| e % 2 == 0);
+
+        for i in 1..=4 {
+            callback.invoke(i);
+        }
+        drop(callback);
+
+        assert_eq!(Some(Arc::new(2)), subscription.recv().await);
+        assert_eq!(Some(Arc::new(4)), subscription.recv().await);
+        assert_eq!(None, subscription.recv().await);
+    }
+
+    #[tokio::test]
+    async fn test_filter_managed() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe_managed().filter(|e| e % 2 == 0);
+
+        for i in 1..=4 {
+            callback.invoke(i);
+        }
+        drop(callback);
+
+        assert_eq!(Some(Arc::new(2)), subscription.recv().await);
+        assert_eq!(Some(Arc::new(4)), subscription.recv().await);
+        assert_eq!(None, subscription.recv().await);
+    }
+
+    #[tokio::test]
+    async fn test_map() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback
+            .subscribe()
+            .filter(|e| *e > 1)
+            .map(|e| e.to_string());
+
+        for i in 1..=3 {
+            callback.invoke(i);
+        }
+        drop(callback);
+
+        assert_eq!(Some("2".to_string()), subscription.recv().await);
+        assert_eq!(Some("3".to_string()), subscription.recv().await);
+        assert_eq!(None, subscription.recv().await);
+    }
+
+    #[tokio::test]
+    async fn test_map_arc() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe().map_arc(|e| e * 10).filter(|e| *e > 10);
+
+        for i in 1..=3 {
+            callback.invoke(i);
+        }
+        drop(callback);
+
+        assert_eq!(Some(Arc::new(20)), subscription.recv().await);
+        assert_eq!(Some(Arc::new(30)), subscription.recv().await);
+        assert_eq!(None, subscription.recv().await);
+    }
+
+    #[tokio::test]
+    async fn test_filter_map() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<Result<u32, String>>::new();
+        let mut subscription = callback
+            .subscribe()
+            .filter_map(|e| e.as_ref().ok().copied());
+
+        callback.invoke(Err("foo".to_string()));
+        callback.invoke(Ok(1));
+        callback.invoke(Err("bar".to_string()));
+        callback.invoke(Ok(2));
+        drop(callback);
+
+        assert_eq!(Some(1), subscription.recv().await);
+        assert_eq!(Some(2), subscription.recv().await);
+        assert_eq!(None, subscription.recv().await);
+    }
+
+    #[tokio::test]
+    async fn test_filter_map_select() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<Option<u32>>::new();
+        let mut subscription = callback.subscribe().filter_map(|e| *e);
+
+        callback.invoke(None);
+        let result = tokio::select! {
+            _ = tokio::time::sleep(std::time::Duration::from_millis(50)) => None,
+            e = subscription.recv() => e,
+        };
+        assert_eq!(
+            None, result,
+            "expected the skipped event to not resolve the stream"
+        );
+
+        callback.invoke(Some(3));
+        assert_eq!(Some(3), subscription.recv().await);
+    }
+
+    #[tokio::test]
+    async fn test_take() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe().take(2);
+
+        for i in 1..=3 {
+            callback.invoke(i);
+        }
+
+        assert_eq!(Some(Arc::new(1)), subscription.recv().await);
+        assert_eq!(Some(Arc::new(2)), subscription.recv().await);
+        assert_eq!(None, subscription.recv().await);
+        assert_eq!(0, subscription.remaining());
+
+        callback.invoke(4);
+        assert_eq!(0, callback.subscriber_count());
+    }
+
+    #[tokio::test]
+    async fn test_take_closed() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe().map(|e| *e).take(3);
+
+        callback.invoke(1);
+        drop(callback);
+
+        assert_eq!(Some(1), subscription.recv().await);
+        assert_eq!(None, subscription.recv().await);
+    }
+
+    #[tokio::test]
+    async fn test_skip() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe().skip(2);
+
+        for i in 1..=3 {
+            callback.invoke(i);
+        }
+        drop(callback);
+
+        assert_eq!(Some(Arc::new(3)), subscription.recv().await);
+        assert_eq!(None, subscription.recv().await);
+    }
+
+    #[tokio::test]
+    async fn test_skip_longer_than_events() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut skip = callback.subscribe().skip(5);
+        let mut skip_while = callback.subscribe().skip_while(|_| true);
+
+        for i in 1..=3 {
+            callback.invoke(i);
+        }
+        drop(callback);
+
+        assert_eq!(None, skip.recv().await);
+        assert_eq!(None, skip_while.recv().await);
+    }
+
+    #[tokio::test]
+    async fn test_skip_while() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe().skip_while(|e| *e < 3);
+
+        for i in [1, 2, 3, 1, 4] {
+            callback.invoke(i);
+        }
+        drop(callback);
+
+        assert_eq!(Some(Arc::new(3)), subscription.recv().await);
+        assert_eq!(Some(Arc::new(1)), subscription.recv().await);
+        assert_eq!(Some(Arc::new(4)), subscription.recv().await);
+        assert_eq!(None, subscription.recv().await);
+    }
+
+    #[tokio::test]
+    async fn test_distinct_until_changed() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback
+            .subscribe()
+            .filter(|e| *e > 0)
+            .distinct_until_changed();
+
+        for i in [1, 1, 0, 1, 2, 2, 1] {
+            callback.invoke(i);
+        }
+        drop(callback);
+
+        assert_eq!(Some(Arc::new(1)), subscription.recv().await);
+        assert_eq!(Some(Arc::new(2)), subscription.recv().await);
+        assert_eq!(Some(Arc::new(1)), subscription.recv().await);
+        assert_eq!(None, subscription.recv().await);
+    }
+
+    #[tokio::test]
+    async fn test_distinct_by_key() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<(u32, &'static str)>::new();
+        let mut subscription = callback.subscribe().distinct_by_key(|e| e.0).map(|e| e.1);
+
+        callback.invoke((1, "foo"));
+        callback.invoke((1, "bar"));
+        callback.invoke((2, "lorem"));
+        drop(callback);
+
+        assert_eq!(Some("foo"), subscription.recv().await);
+        assert_eq!(Some("lorem"), subscription.recv().await);
+        assert_eq!(None, subscription.recv().await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_debounce() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe().debounce(Duration::from_millis(100));
+
+        callback.invoke(1);
+        callback.invoke(2);
+        let start = Instant::now();
+        assert_eq!(Some(Arc::new(2)), subscription.recv().await);
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        callback.invoke(3);
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_millis(50)) => {},
+            _ = subscription.recv() => panic!("expected the event to still be debounced"),
+        }
+        callback.invoke(4);
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_millis(50)) => {},
+            _ = subscription.recv() => panic!("expected the event to still be debounced"),
+        }
+        assert_eq!(Some(Arc::new(4)), subscription.recv().await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_debounce_closed() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe().debounce(Duration::from_secs(60));
+
+        callback.invoke(1);
+        callback.invoke(2);
+        drop(callback);
+
+        let start = Instant::now();
+        assert_eq!(Some(Arc::new(2)), subscription.recv().await);
+        assert_eq!(None, subscription.recv().await);
+        assert!(start.elapsed() < Duration::from_secs(60));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_throttle() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe().throttle(Duration::from_millis(100));
+
+        callback.invoke(1);
+        callback.invoke(2);
+        assert_eq!(Some(Arc::new(1)), subscription.recv().await);
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_millis(150)) => {},
+            _ = subscription.recv() => panic!("expected the intermediate event to be dropped"),
+        }
+
+        callback.invoke(3);
+        callback.invoke(4);
+        drop(callback);
+        assert_eq!(Some(Arc::new(3)), subscription.recv().await);
+        assert_eq!(None, subscription.recv().await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_throttle_emit_latest() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback
+            .subscribe()
+            .throttle_with(Duration::from_millis(100), ThrottleMode::EmitLatest);
+
+        for i in 1..=3 {
+            callback.invoke(i);
+        }
+        let start = Instant::now();
+        assert_eq!(Some(Arc::new(1)), subscription.recv().await);
+        assert_eq!(Some(Arc::new(3)), subscription.recv().await);
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        callback.invoke(4);
+        callback.invoke(5);
+        drop(callback);
+        assert_eq!(Some(Arc::new(5)), subscription.recv().await);
+        assert_eq!(None, subscription.recv().await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_sample() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe().sample(Duration::from_millis(100));
+
+        callback.invoke(1);
+        callback.invoke(2);
+        let start = Instant::now();
+        assert_eq!(Some(Arc::new(2)), subscription.recv().await);
+        assert_eq!(Duration::from_millis(100), start.elapsed());
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        callback.invoke(3);
+        assert_eq!(Some(Arc::new(3)), subscription.recv().await);
+        assert_eq!(Duration::from_millis(300), start.elapsed());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_sample_closed() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe().sample(Duration::from_secs(60));
+
+        callback.invoke(1);
+        callback.invoke(2);
+        drop(callback);
+
+        assert_eq!(Some(Arc::new(2)), subscription.recv().await);
+        assert_eq!(None, subscription.recv().await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_recv_timeout() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe();
+
+        let result = subscription.recv_timeout(Duration::from_millis(100)).await;
+        assert!(result.is_err(), "expected the timeout to have elapsed");
+
+        callback.invoke(1);
+        let result = subscription.recv_timeout(Duration::from_millis(100)).await;
+        assert_eq!(Ok(Some(Arc::new(1))), result);
+
+        drop(callback);
+        let result = subscription.recv_timeout(Duration::from_millis(100)).await;
+        assert_eq!(Ok(None), result);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe();
+
+        for i in 1..=4 {
+            callback.invoke(i);
+        }
+
+        let result = subscription.wait_for(|e| *e == 3).await;
+        assert_eq!(Some(Arc::new(3)), result);
+        assert_eq!(Some(Arc::new(4)), subscription.recv().await);
+
+        drop(callback);
+        let result = subscription.wait_for(|_| true).await;
+        assert_eq!(None, result);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_for_timeout() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe();
+
+        callback.invoke(1);
+        let result = subscription
+            .wait_for_timeout(|e| *e == 2, Duration::from_millis(100))
+            .await;
+        assert!(result.is_err(), "expected the timeout to have elapsed");
+
+        callback.invoke(2);
+        let result = subscription
+            .wait_for_timeout(|e| *e == 2, Duration::from_millis(100))
+            .await;
+        assert_eq!(Ok(Some(Arc::new(2))), result);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_watchdog() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe().watchdog(Duration::from_millis(100));
+
+        callback.invoke(1);
+        assert_eq!(
+            Some(WatchdogItem::Event(Arc::new(1))),
+            subscription.recv().await
+        );
+
+        let start = Instant::now();
+        assert_eq!(Some(WatchdogItem::Silence), subscription.recv().await);
+        assert_eq!(Some(WatchdogItem::Silence), subscription.recv().await);
+        assert_eq!(Duration::from_millis(200), start.elapsed());
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        callback.invoke(2);
+        assert_eq!(
+            Some(WatchdogItem::Event(Arc::new(2))),
+            subscription.recv().await
+        );
+        assert_eq!(Some(WatchdogItem::Silence), subscription.recv().await);
+        assert_eq!(Duration::from_millis(350), start.elapsed());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_watchdog_closed() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe().watchdog(Duration::from_millis(100));
+
+        callback.invoke(1);
+        drop(callback);
+
+        assert_eq!(
+            Some(WatchdogItem::Event(Arc::new(1))),
+            subscription.recv().await
+        );
+        assert_eq!(None, subscription.recv().await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_chunks() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe().chunks(2, Duration::from_millis(100));
+
+        for i in 1..=3 {
+            callback.invoke(i);
+        }
+        assert_eq!(
+            Some(vec![Arc::new(1), Arc::new(2)]),
+            subscription.recv().await
+        );
+
+        let start = Instant::now();
+        assert_eq!(Some(vec![Arc::new(3)]), subscription.recv().await);
+        assert_eq!(Duration::from_millis(100), start.elapsed());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_chunks_cancelled() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe().chunks(3, Duration::from_secs(60));
+
+        callback.invoke(1);
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_millis(50)) => {},
+            _ = subscription.recv() => panic!("expected the chunk to not be complete"),
+        }
+        callback.invoke(2);
+        drop(callback);
+
+        assert_eq!(
+            Some(vec![Arc::new(1), Arc::new(2)]),
+            subscription.recv().await
+        );
+        assert_eq!(None, subscription.recv().await);
+    }
+
+    #[tokio::test]
+    async fn test_merge() {
+        init_logger!();
+        let first = SingleThreadedCallback::<u32>::new();
+        let second = SingleThreadedCallback::<u32>::new();
+        let third = SingleThreadedCallback::<u32>::new();
+        let mut subscription = merge(vec![first.subscribe(), second.subscribe()]);
+        subscription.push(third.subscribe());
+
+        first.invoke(1);
+        first.invoke(2);
+        second.invoke(10);
+        third.invoke(20);
+        drop(first);
+        drop(second);
+
+        let mut result = Vec::new();
+        for _ in 0..4 {
+            result.push(*subscription.recv().await.unwrap());
+        }
+        let first_events: Vec<_> = result.iter().filter(|e| **e < 10).collect();
+        assert_eq!(vec![&1, &2], first_events);
+        assert!(
+            result.contains(&10),
+            "expected the second event to be received"
+        );
+        assert!(
+            result.contains(&20),
+            "expected the third event to be received"
+        );
+
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_millis(50)) => {},
+            _ = subscription.recv() => panic!("expected the merged subscription to still be open"),
+        }
+        assert_eq!(1, subscription.len());
+        drop(third);
+        assert_eq!(None, subscription.recv().await);
+    }
+
+    #[tokio::test]
+    async fn test_select2() {
+        init_logger!();
+        let first = SingleThreadedCallback::<u32>::new();
+        let second = SingleThreadedCallback::<String>::new();
+        let mut first_subscription = first.subscribe();
+        let mut second_subscription = second.subscribe();
+
+        second.invoke("foo".to_string());
+        let result = select2(&mut first_subscription, &mut second_subscription).await;
+        assert_eq!(Some(Either::Right(Arc::new("foo".to_string()))), result);
+
+        first.invoke(1);
+        second.invoke("bar".to_string());
+        let result = select2(&mut first_subscription, &mut second_subscription).await;
+        assert_eq!(Some(Either::Left(Arc::new(1))), result);
+        assert_eq!(
+            Some(Arc::new("bar".to_string())),
+            second_subscription.recv().await
+        );
+
+        drop(first);
+        drop(second);
+        let result = select2(&mut first_subscription, &mut second_subscription).await;
+        assert_eq!(None, result);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_select_first() {
+        init_logger!();
+        let callbacks: Vec<_> = (0..3)
+            .map(|_| SingleThreadedCallback::<u32>::new())
+            .collect();
+        let mut subscriptions: Vec<_> = callbacks.iter().map(|e| e.subscribe()).collect();
+
+        let result = select_first_timeout(&mut subscriptions, Duration::from_millis(100)).await;
+        assert!(result.is_err(), "expected the timeout to have elapsed");
+
+        callbacks[2].invoke(3);
+        callbacks[1].invoke(2);
+        let result = select_first(&mut subscriptions).await;
+        assert_eq!(Some((1, Arc::new(2))), result);
+        assert_eq!(Some(Arc::new(3)), subscriptions[2].recv().await);
+
+        drop(callbacks);
+        assert_eq!(None, select_first(&mut subscriptions).await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_delay() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe().delay(Duration::from_millis(800));
+        let start = Instant::now();
+
+        callback.invoke(1);
+        assert_eq!(Some(Arc::new(1)), subscription.recv().await);
+        assert_eq!(Duration::from_millis(800), start.elapsed());
+
+        callback.invoke(2);
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_millis(200)) => {},
+            _ = subscription.recv() => panic!("expected the event to still be delayed"),
+        }
+        callback.invoke(3);
+        drop(callback);
+
+        assert_eq!(Some(Arc::new(2)), subscription.recv().await);
+        assert_eq!(Duration::from_millis(1600), start.elapsed());
+        assert_eq!(Some(Arc::new(3)), subscription.recv().await);
+        assert_eq!(Duration::from_millis(1800), start.elapsed());
+        assert_eq!(None, subscription.recv().await);
+    }
+
+    #[tokio::test]
+    async fn test_inspect() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut inspected = Vec::new();
+        let mut subscription = callback
+            .subscribe()
+            .inspect(|e| inspected.push(e.clone()))
+            .filter(|e| *e != 2);
+
+        for i in 1..=3 {
+            callback.invoke(i);
+        }
+        drop(callback);
+
+        assert_eq!(Some(Arc::new(1)), subscription.recv().await);
+        assert_eq!(Some(Arc::new(3)), subscription.recv().await);
+        assert_eq!(None, subscription.recv().await);
+        drop(subscription);
+        assert_eq!(vec![Arc::new(1), Arc::new(2), Arc::new(3)], inspected);
+    }
+
+    #[tokio::test]
+    async fn test_scan() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u64>::new();
+        let mut subscription = callback.subscribe().scan(0u64, |total, e| {
+            let previous = *total * 100 / 1000;
+            *total += *e;
+            let percentage = *total * 100 / 1000;
+            (percentage != previous).then_some(percentage)
+        });
+
+        for bytes in [50, 5, 200, 745] {
+            callback.invoke(bytes);
+        }
+        drop(callback);
+
+        assert_eq!(Some(5), subscription.recv().await);
+        assert_eq!(Some(25), subscription.recv().await);
+        assert_eq!(Some(100), subscription.recv().await);
+        assert_eq!(None, subscription.recv().await);
+        assert_eq!(1000, subscription.finish());
+    }
+}
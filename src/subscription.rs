@@ -0,0 +1,234 @@
+use crate::Subscription;
+use std::future::poll_fn;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// The extension of a [Subscription] with consumer side helpers.
+pub trait SubscriptionExt<T> {
+    /// Receive each event together with the number of events which are still pending within the subscription,
+    /// allowing the consumer to adapt its processing when it falls behind,
+    /// e.g. by skipping expensive work while the number of pending events is high.
+    fn with_lag_signal(self) -> LagSignalSubscription<T>;
+}
+
+impl<T> SubscriptionExt<T> for Subscription<T> {
+    fn with_lag_signal(self) -> LagSignalSubscription<T> {
+        LagSignalSubscription { receiver: self }
+    }
+}
+
+/// A subscription which yields each event together with the number of events which are still pending.
+///
+/// The pending count is a snapshot of the queue depth right after the event has been received, excluding the event itself.
+/// Events which are invoked concurrently might already have been added by the time the count is inspected,
+/// so it should be treated as a lower bound of the actual lag.
+#[derive(Debug)]
+pub struct LagSignalSubscription<T> {
+    receiver: Subscription<T>,
+}
+
+impl<T> LagSignalSubscription<T> {
+    /// Receive the next event together with the number of events which are still pending.
+    ///
+    /// # Returns
+    ///
+    /// It returns [None] when the subscription has been closed.
+    pub async fn recv(&mut self) -> Option<(Arc<T>, usize)> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Poll to receive the next event together with the number of events which are still pending.
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<(Arc<T>, usize)>> {
+        self.receiver
+            .poll_recv(cx)
+            .map(|event| event.map(|event| (event, self.receiver.len())))
+    }
+
+    /// Get the underlying subscription back.
+    pub fn into_inner(self) -> Subscription<T> {
+        self.receiver
+    }
+}
+
+/// A set of subscriptions which can be consumed as a single multiplexed source of events.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use fx_callback::{Callback, MultiThreadedCallback, SubscriptionSet};
+///
+/// #[derive(Debug)]
+/// pub enum MyEvent {
+///     Foo,
+/// }
+///
+/// async fn example(first: &MultiThreadedCallback<MyEvent>, second: &MultiThreadedCallback<MyEvent>) {
+///     let mut set = SubscriptionSet::new();
+///     set.push(first.subscribe());
+///     set.push(second.subscribe());
+///
+///     while let Some((index, event)) = set.recv_any().await {
+///         // do something with the event of the source at the index
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SubscriptionSet<T> {
+    subscriptions: Vec<(usize, Subscription<T>)>,
+    next_index: usize,
+    offset: usize,
+}
+
+impl<T> SubscriptionSet<T> {
+    /// Create a new empty subscription set.
+    pub fn new() -> Self {
+        Self {
+            subscriptions: Vec::new(),
+            next_index: 0,
+            offset: 0,
+        }
+    }
+
+    /// Add the given subscription to the set.
+    ///
+    /// # Returns
+    ///
+    /// It returns the index of the subscription within the set, which identifies it as event source in [SubscriptionSet::recv_any].
+    pub fn push(&mut self, subscription: Subscription<T>) -> usize {
+        let index = self.next_index;
+        self.next_index += 1;
+        self.subscriptions.push((index, subscription));
+        index
+    }
+
+    /// Get the number of subscriptions within the set which haven't been closed yet.
+    pub fn len(&self) -> usize {
+        self.subscriptions.len()
+    }
+
+    /// Check if the set doesn't contain any open subscriptions.
+    pub fn is_empty(&self) -> bool {
+        self.subscriptions.is_empty()
+    }
+
+    /// Receive the next event from any of the subscriptions within the set.
+    ///
+    /// The subscriptions are polled in a round-robin order, starting from a different subscription on each call,
+    /// so a busy subscription can't starve the others.
+    /// Subscriptions which have been closed are removed from the set automatically.
+    /// This method is cancel safe, no event is lost when it's used within `select!`.
+    ///
+    /// # Returns
+    ///
+    /// It returns the index of the source subscription together with the event,
+    /// or [None] when all subscriptions of the set have been closed.
+    pub async fn recv_any(&mut self) -> Option<(usize, Arc<T>)> {
+        poll_fn(|cx| self.poll_recv_any(cx)).await
+    }
+
+    /// Poll to receive the next event from any of the subscriptions within the set, see [SubscriptionSet::recv_any].
+    pub fn poll_recv_any(&mut self, cx: &mut Context<'_>) -> Poll<Option<(usize, Arc<T>)>> {
+        let total = self.subscriptions.len();
+        if total == 0 {
+            return Poll::Ready(None);
+        }
+
+        let start = self.offset % total;
+        self.offset = self.offset.wrapping_add(1);
+        let mut closed = Vec::new();
+        let mut result = None;
+
+        for position in (start..total).chain(0..start) {
+            let (index, subscription) = &mut self.subscriptions[position];
+            match subscription.poll_recv(cx) {
+                Poll::Ready(Some(event)) => {
+                    result = Some((*index, event));
+                    break;
+                }
+                Poll::Ready(None) => closed.push(position),
+                Poll::Pending => {}
+            }
+        }
+
+        closed.sort_unstable();
+        for position in closed.into_iter().rev() {
+            self.subscriptions.remove(position);
+        }
+
+        match result {
+            Some(result) => Poll::Ready(Some(result)),
+            None if self.subscriptions.is_empty() => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl<T> Default for SubscriptionSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{init_logger, Callback, SingleThreadedCallback};
+
+    #[tokio::test]
+    async fn test_with_lag_signal() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<u32>::new();
+        let mut subscription = callback.subscribe().with_lag_signal();
+
+        callback.invoke(1);
+        callback.invoke(2);
+        callback.invoke(3);
+
+        assert_eq!(Some((Arc::new(1), 2)), subscription.recv().await);
+        assert_eq!(Some((Arc::new(2), 1)), subscription.recv().await);
+        assert_eq!(Some((Arc::new(3), 0)), subscription.recv().await);
+        drop(callback);
+        assert_eq!(None, subscription.recv().await);
+    }
+
+    #[tokio::test]
+    async fn test_recv_any() {
+        init_logger!();
+        let first = SingleThreadedCallback::<u32>::new();
+        let second = SingleThreadedCallback::<u32>::new();
+        let mut set = SubscriptionSet::new();
+
+        let first_index = set.push(first.subscribe());
+        let second_index = set.push(second.subscribe());
+        second.invoke(2);
+        let result = set.recv_any().await;
+        assert_eq!(Some((second_index, Arc::new(2))), result);
+
+        first.invoke(1);
+        let result = set.recv_any().await;
+        assert_eq!(Some((first_index, Arc::new(1))), result);
+    }
+
+    #[tokio::test]
+    async fn test_recv_any_closed() {
+        init_logger!();
+        let first = SingleThreadedCallback::<u32>::new();
+        let second = SingleThreadedCallback::<u32>::new();
+        let mut set = SubscriptionSet::new();
+
+        set.push(first.subscribe());
+        set.push(second.subscribe());
+        drop(first);
+        second.invoke(2);
+        let result = set.recv_any().await;
+        assert_eq!(Some((1, Arc::new(2))), result);
+
+        drop(second);
+        let result = set.recv_any().await;
+        assert_eq!(None, result);
+        assert!(
+            set.is_empty(),
+            "expected all subscriptions to have been removed"
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use crate::{Callback, Subscription};
+use log::{debug, trace, warn};
+use serde::Serialize;
+use std::fmt::Debug;
+use std::future::{poll_fn, Future};
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::Poll;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::runtime::Runtime;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tokio::sync::Notify;
+
+/// The maximum number of frames which may be queued for a connection before the connection is dropped.
+const CLIENT_QUEUE_CAPACITY: usize = 64;
+
+/// A debug tap which streams the serialized events of a [Callback] to all connected TCP clients.
+///
+/// The tap is intended for development only, e.g. to observe live events through `nc` or a custom viewer.
+/// Each event is written as a frame consisting of a 4 byte big-endian length prefix followed by the JSON encoded event.
+/// Every connection has its own bounded queue of frames which is written by a dedicated task,
+/// so a slow connection never delays the others. Clients which can't keep up are disconnected.
+///
+/// The tap stops listening and streaming events when it's dropped.
+/// This requires the `debug-tap` feature to be enabled.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use fx_callback::{DebugTap, MultiThreadedCallback};
+///
+/// let callback = MultiThreadedCallback::<String>::new();
+/// let tap = DebugTap::bind(&callback, "127.0.0.1:9000").unwrap();
+///
+/// callback.invoke("Foo".to_string());
+/// ```
+#[derive(Debug)]
+pub struct DebugTap {
+    addr: SocketAddr,
+    stop: Arc<Notify>,
+}
+
+/// The next action of the tap's serve loop.
+enum Next<T> {
+    Accept(io::Result<(TcpStream, SocketAddr)>),
+    Event(Option<Arc<T>>),
+    Stop,
+}
+
+impl DebugTap {
+    /// Start a new debug tap for the given callback on the given address.
+    ///
+    /// # Returns
+    ///
+    /// It returns an error when the tap couldn't bind to the given address.
+    pub fn bind<T, C, A>(callback: &C, addr: A) -> io::Result<Self>
+    where
+        T: Debug + Serialize + Send + Sync + 'static,
+        C: Callback<T> + ?Sized,
+        A: ToSocketAddrs,
+    {
+        let listener = std::net::TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .build()?;
+        let listener = {
+            let _guard = runtime.enter();
+            TcpListener::from_std(listener)?
+        };
+        let stop = Arc::new(Notify::new());
+        let receiver = callback.subscribe();
+
+        let serve_stop = stop.clone();
+        std::thread::Builder::new()
+            .name("debug-tap".to_string())
+            .spawn(move || Self::run(runtime, listener, receiver, serve_stop))?;
+
+        debug!("Debug tap is listening on {}", addr);
+        Ok(Self { addr, stop })
+    }
+
+    /// Get the local address on which the tap is listening for connections.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    fn run<T>(runtime: Runtime, listener: TcpListener, receiver: Subscription<T>, stop: Arc<Notify>)
+    where
+        T: Debug + Serialize + Send + Sync + 'static,
+    {
+        runtime.block_on(Self::serve(listener, receiver, stop));
+        // dropping the runtime drops the remaining connection tasks, which closes their connections
+        drop(runtime);
+        trace!("Debug tap serve loop has stopped");
+    }
+
+    async fn serve<T>(listener: TcpListener, mut receiver: Subscription<T>, stop: Arc<Notify>)
+    where
+        T: Debug + Serialize + Send + Sync,
+    {
+        let mut clients: Vec<Sender<Arc<[u8]>>> = Vec::new();
+        let mut stopped = pin!(stop.notified());
+        loop {
+            let next = poll_fn(|cx| {
+                if stopped.as_mut().poll(cx).is_ready() {
+                    return Poll::Ready(Next::Stop);
+                }
+                if let Poll::Ready(result) = listener.poll_accept(cx) {
+                    return Poll::Ready(Next::Accept(result));
+                }
+                receiver.poll_recv(cx).map(Next::Event)
+            })
+            .await;
+
+            match next {
+                Next::Accept(Ok((stream, addr))) => {
+                    debug!("Debug tap accepted connection {}", addr);
+                    let (sender, frames) = channel(CLIENT_QUEUE_CAPACITY);
+                    tokio::spawn(Self::write(stream, addr, frames));
+                    clients.push(sender);
+                }
+                Next::Accept(Err(e)) => warn!("Debug tap failed to accept connection, {}", e),
+                Next::Event(Some(event)) => match Self::frame(&*event) {
+                    Ok(frame) => {
+                        let frame: Arc<[u8]> = frame.into();
+                        clients.retain(|client| match client.try_send(frame.clone()) {
+                            Ok(_) => true,
+                            Err(TrySendError::Full(_)) => {
+                                warn!("Debug tap connection can't keep up, disconnecting");
+                                false
+                            }
+                            Err(TrySendError::Closed(_)) => false,
+                        });
+                    }
+                    Err(e) => warn!("Debug tap failed to serialize event {:?}, {}", event, e),
+                },
+                Next::Event(None) | Next::Stop => break,
+            }
+        }
+    }
+
+    /// Write the queued frames to the given connection until the queue is closed or the write fails.
+    async fn write(mut stream: TcpStream, addr: SocketAddr, mut frames: Receiver<Arc<[u8]>>) {
+        while let Some(frame) = frames.recv().await {
+            if let Err(e) = stream.write_all(&frame).await {
+                debug!("Debug tap failed to write to connection {}, {}", addr, e);
+                break;
+            }
+        }
+        trace!("Debug tap connection {} has been closed", addr);
+    }
+
+    /// Create the length-prefixed JSON frame of the given event.
+    fn frame<T: Serialize>(event: &T) -> serde_json::Result<Vec<u8>> {
+        let payload = serde_json::to_vec(event)?;
+        let mut frame = Vec::with_capacity(payload.len() + 4);
+        frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        frame.extend_from_slice(&payload);
+        Ok(frame)
+    }
+}
+
+impl Drop for DebugTap {
+    fn drop(&mut self) {
+        self.stop.notify_one();
+        debug!("Debug tap on {} has been stopped", self.addr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{init_logger, SingleThreadedCallback};
+    use std::io::Read;
+    use std::net::TcpStream;
+    use std::time::Duration;
+
+    #[test]
+    fn test_debug_tap() {
+        init_logger!();
+        let callback = SingleThreadedCallback::<String>::new();
+        let tap = DebugTap::bind(&callback, "127.0.0.1:0").unwrap();
+
+        let mut client = TcpStream::connect(tap.local_addr()).unwrap();
+        client
+            .set_read_timeout(Some(Duration::from_millis(500)))
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(100));
+        callback.invoke("Foo".to_string());
+
+        let mut length = [0u8; 4];
+        client.read_exact(&mut length).unwrap();
+        let mut payload = vec![0u8; u32::from_be_bytes(length) as usize];
+        client.read_exact(&mut payload).unwrap();
+        assert_eq!("\"Foo\"", String::from_utf8(payload).unwrap());
+
+        drop(tap);
+        let mut buffer = [0u8; 1];
+        assert_eq!(
+            0,
+            client.read(&mut buffer).unwrap(),
+            "expected the connection to have been closed"
+        );
+    }
+}